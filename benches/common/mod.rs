@@ -0,0 +1,53 @@
+//! Heap-allocation counting shared by the benchmarks that report
+//! allocations per call.
+//!
+//! Installs a global allocator that wraps `System` and counts every
+//! allocation and reallocation, with the bytes requested.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const ITERATIONS: usize = 1_000;
+
+/// Return `(allocations, bytes)` per call of `f`, averaged over `ITERATIONS`.
+pub fn allocations_per_call(mut f: impl FnMut()) -> (f64, f64) {
+    // Warm up once so reusable buffers reach their steady-state capacity.
+    f();
+    let count_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes_before = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let count = ALLOCATIONS.load(Ordering::Relaxed) - count_before;
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes_before;
+    (
+        count as f64 / ITERATIONS as f64,
+        bytes as f64 / ITERATIONS as f64,
+    )
+}
@@ -42,8 +42,8 @@ fn bench_hexvault_traversal(c: &mut Criterion) {
     let master = generate_master_key().unwrap();
     let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
     let partition = vault.get_partition("bench").unwrap();
-    let mut cell_a = partition.create_cell("cell-a".into());
-    let mut cell_b = partition.create_cell("cell-b".into());
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
     let token = "";
 
     let payload = vec![0u8; 10 * 1024]; // 10KB
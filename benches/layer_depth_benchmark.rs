@@ -0,0 +1,72 @@
+//! Layer-depth benchmark: `stack::seal` and `stack::peel` at every layer.
+//!
+//! Sealing at `SessionBound` runs three key derivations and three AEAD
+//! passes. The payload is sealed and peeled in place in a single buffer,
+//! sized for every layer up front, so deeper layers add derivation and
+//! cipher work but no extra payload buffers.
+//!
+//! Run with: `cargo bench --bench layer_depth_benchmark`
+//!
+//! Before the timing runs, the benchmark prints the heap allocations and
+//! allocated bytes per call at each layer and payload size, counted by a
+//! wrapping global allocator (see `common`).
+
+mod common;
+
+use common::allocations_per_call;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hexvault::keys::{self, MasterKey};
+use hexvault::stack::{self, Layer, LayerContext};
+
+const SIZES: [usize; 3] = [64, 4 * 1024, 64 * 1024];
+
+fn benchmark_layer_depth(c: &mut Criterion) {
+    let master = MasterKey::from_bytes([7u8; 32]);
+    let partition = keys::derive_partition_key(&master, "bench").unwrap();
+    let context = LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap();
+
+    for &size in &SIZES {
+        let payload = vec![0u8; size];
+        for &layer in Layer::all() {
+            let sealed = stack::seal(&partition, "cell", layer, &context, &payload).unwrap();
+            let (seal_count, seal_bytes) = allocations_per_call(|| {
+                black_box(stack::seal(&partition, "cell", layer, &context, &payload).unwrap());
+            });
+            let (peel_count, peel_bytes) = allocations_per_call(|| {
+                black_box(stack::peel(&partition, "cell", layer, &context, &sealed).unwrap());
+            });
+            println!(
+                "{layer:?} {size}B: seal {seal_count:.1} allocations / {seal_bytes:.0} bytes, \
+                 peel {peel_count:.1} allocations / {peel_bytes:.0} bytes per call"
+            );
+        }
+    }
+
+    let mut group = c.benchmark_group("layer_depth");
+    for &size in &SIZES {
+        let payload = vec![0u8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        for &layer in Layer::all() {
+            let id = format!("{layer:?}/{size}");
+            group.bench_function(BenchmarkId::new("seal", &id), |b| {
+                b.iter(|| {
+                    stack::seal(&partition, "cell", layer, &context, black_box(&payload)).unwrap()
+                });
+            });
+            let sealed = stack::seal(&partition, "cell", layer, &context, &payload).unwrap();
+            group.bench_function(BenchmarkId::new("peel", &id), |b| {
+                b.iter(|| {
+                    stack::peel(&partition, "cell", layer, &context, black_box(&sealed)).unwrap()
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_layer_depth);
+criterion_main!(benches);
@@ -0,0 +1,74 @@
+//! Allocation benchmark: `Partition::open` vs. `Partition::open_into`.
+//!
+//! Both peel every layer in place in one buffer. `open` allocates that
+//! buffer per call; `open_into` reuses a caller-owned one, so once it has
+//! grown a tight read loop performs no buffer allocations at all.
+//!
+//! Run with: `cargo bench --bench open_into_benchmark`
+//!
+//! Before the timing runs, the benchmark prints the heap allocations and
+//! allocated bytes per call for each path, counted by a wrapping global
+//! allocator (see `common`). The remaining small allocations in
+//! `open_into` come from token resolution and key derivation, not from
+//! payload buffers.
+
+mod common;
+
+use common::allocations_per_call;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use hexvault::error::HexvaultError;
+use hexvault::stack::{Layer, LayerContext, TokenResolver};
+use hexvault::{generate_master_key, Vault};
+
+struct FixedResolver;
+impl TokenResolver for FixedResolver {
+    fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+        LayerContext::new(Some("policy".try_into()?), Some("session".try_into()?))
+    }
+}
+
+fn benchmark_open_into(c: &mut Criterion) {
+    let mut group = c.benchmark_group("open_vs_open_into");
+
+    let master = generate_master_key().unwrap();
+    let vault = Vault::new(master, std::sync::Arc::new(FixedResolver));
+    let partition = vault.get_partition("bench").unwrap();
+    let mut cell = partition.create_cell("bench-cell".into()).unwrap();
+
+    let payload = vec![0u8; 1024];
+    partition
+        .seal(&mut cell, "data", &payload, Layer::SessionBound, "")
+        .unwrap();
+
+    let mut out = Vec::new();
+
+    let open_allocs = allocations_per_call(|| {
+        black_box(partition.open(&cell, "data", "").unwrap());
+    });
+    let open_into_allocs = allocations_per_call(|| {
+        partition.open_into(&cell, "data", "", &mut out).unwrap();
+        black_box(&out);
+    });
+    for (name, (count, bytes)) in [("open", open_allocs), ("open_into", open_into_allocs)] {
+        println!(
+            "{name}: {count:.1} allocations, {bytes:.0} bytes per call (1KB payload, 3 layers)"
+        );
+    }
+
+    group.throughput(Throughput::Bytes(payload.len() as u64));
+    group.bench_function("open", |b| {
+        b.iter(|| black_box(partition.open(black_box(&cell), "data", "").unwrap()));
+    });
+    group.bench_function("open_into", |b| {
+        b.iter(|| {
+            partition
+                .open_into(black_box(&cell), "data", "", &mut out)
+                .unwrap();
+            black_box(&out);
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_open_into);
+criterion_main!(benches);
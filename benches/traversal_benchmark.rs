@@ -21,8 +21,8 @@ fn benchmark_traversal(c: &mut Criterion) {
     // Setup cells
     let cell_a_id = "bench-source";
     let cell_b_id = "bench-dest";
-    let mut cell_a = partition.create_cell(cell_a_id.into());
-    let mut cell_b = partition.create_cell(cell_b_id.into());
+    let mut cell_a = partition.create_cell(cell_a_id.into()).unwrap();
+    let mut cell_b = partition.create_cell(cell_b_id.into()).unwrap();
 
     let token = "";
 
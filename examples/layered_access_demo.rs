@@ -21,8 +21,8 @@ impl TokenResolver for SimpleTokenResolver {
 
         let parts: Vec<&str> = token.split(':').collect();
         match parts.len() {
-            1 => LayerContext::new(Some(parts[0].to_string()), None),
-            2 => LayerContext::new(Some(parts[0].to_string()), Some(parts[1].to_string())),
+            1 => LayerContext::new(Some(parts[0].try_into()?), None),
+            2 => LayerContext::new(Some(parts[0].try_into()?), Some(parts[1].try_into()?)),
             _ => Err(HexvaultError::MissingOrInvalidContext),
         }
     }
@@ -33,7 +33,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let vault = Vault::new(master_key, std::sync::Arc::new(SimpleTokenResolver));
     let partition = vault.get_partition("dept-engineering")?;
 
-    let mut cell = partition.create_cell("user-data".into());
+    let mut cell = partition.create_cell("user-data".into())?;
 
     // -----------------------------------------------------------------------
     // Layer 0 — At-rest: No context needed.
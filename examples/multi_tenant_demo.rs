@@ -12,7 +12,6 @@ use hexvault::audit::FileAuditSink;
 use hexvault::error::HexvaultError;
 use hexvault::stack::{Layer, LayerContext, TokenResolver};
 use hexvault::{generate_master_key, Vault};
-use std::path::PathBuf;
 
 struct DummyResolver;
 impl TokenResolver for DummyResolver {
@@ -27,14 +26,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut vault = Vault::new(master_key, std::sync::Arc::new(DummyResolver));
 
     // Optional: persist audit log to file
-    let audit_path = PathBuf::from(std::env::temp_dir()).join("hexvault_audit.jsonl");
+    let audit_path = std::env::temp_dir().join("hexvault_audit.jsonl");
     vault.add_audit_sink(Box::new(FileAuditSink::new(&audit_path)?));
 
     // 2. Create cells (tenants)
     let partition_a = vault.get_partition("tenant-a-part")?;
-    let mut tenant_a = partition_a.create_cell("tenant-a".into());
+    let mut tenant_a = partition_a.create_cell("tenant-a".into())?;
     let partition_b = vault.get_partition("tenant-b-part")?;
-    let mut tenant_b = partition_b.create_cell("tenant-b".into());
+    let mut tenant_b = partition_b.create_cell("tenant-b".into())?;
 
     let token = "";
 
@@ -67,7 +66,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let in_b = partition_b.open(&tenant_b, "customer_pii", token)?;
     assert_eq!(in_b, b"Alice, alice@example.com, SSN-xxx");
 
-    // 6. Audit log
+    // 6. Audit log — flush and close the file sink before reporting
+    vault.flush_audit()?;
+    vault.close_audit();
     let log = vault.audit_log();
     println!("Audit log: {} record(s)", log.len());
     for record in log.iter() {
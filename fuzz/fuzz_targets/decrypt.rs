@@ -0,0 +1,25 @@
+//! Feeds arbitrary bytes to a single-layer decrypt.
+//!
+//! `crypto` is crate-private, so the target goes through an at-rest peel,
+//! which is exactly one `crypto::decrypt` call. Any panic is a bug; every
+//! input must produce `Ok` or `Err`.
+//!
+//! Run with: `cargo +nightly fuzz run decrypt`
+
+#![no_main]
+
+use hexvault::keys::{self, MasterKey};
+use hexvault::stack::{self, Layer, LayerContext};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let master = MasterKey::from_bytes([7u8; 32]);
+    let partition = keys::derive_partition_key(&master, "fuzz").unwrap();
+    let _ = stack::peel(
+        &partition,
+        "cell",
+        Layer::AtRest,
+        &LayerContext::empty(),
+        data,
+    );
+});
@@ -0,0 +1,24 @@
+//! Feeds arbitrary bytes to a multi-layer peel.
+//!
+//! The first byte picks the claimed top layer; the rest is the ciphertext.
+//! Any panic is a bug; every input must produce `Ok` or `Err`.
+//!
+//! Run with: `cargo +nightly fuzz run peel`
+
+#![no_main]
+
+use hexvault::keys::{self, MasterKey};
+use hexvault::stack::{self, Layer, LayerContext};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, ciphertext)) = data.split_first() else {
+        return;
+    };
+    let layer = Layer::all()[usize::from(selector) % Layer::all().len()];
+
+    let master = MasterKey::from_bytes([7u8; 32]);
+    let partition = keys::derive_partition_key(&master, "fuzz").unwrap();
+    let ctx = LayerContext::new(Some("policy".into()), Some("session".into())).unwrap();
+    let _ = stack::peel(&partition, "cell", layer, &ctx, ciphertext);
+});
@@ -0,0 +1,188 @@
+//! An async facade over the synchronous vault, for tokio services.
+//!
+//! Sealing and peeling are CPU-bound and, for large payloads, long enough
+//! to stall an async executor. `AsyncVault` runs each operation on tokio's
+//! blocking pool with `spawn_blocking`, so the calling task yields instead.
+//! The core stays synchronous (ADR-008): every operation still runs to
+//! completion on one thread, and the plaintext never crosses a yield point.
+//!
+//! A traversal locks the vault only to read its settings and to append the
+//! audit record, not while it resolves tokens, peels, and re-seals, so
+//! operations on other cells are not queued behind it. Forward sinks still
+//! run while the record is appended: attach an `AsyncFileAuditSink`, which
+//! hands each record to a tokio task, so that audit I/O holds up neither
+//! the vault nor the executor.
+//!
+//! Because the work moves to another thread, arguments are owned: the vault
+//! lives behind the facade, and partitions and cells are shared through
+//! `Arc`s.
+
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use zeroize::Zeroize;
+
+use crate::audit::{AuditLog, AuditRecord};
+use crate::cell::Cell;
+use crate::edge::{self, TraversalRequest};
+use crate::error::HexvaultError;
+use crate::partition::Partition;
+use crate::secret::SecretBytes;
+use crate::stack::Layer;
+use crate::Vault;
+
+/// A cell shared between tasks.
+pub type SharedCell = Arc<Mutex<Cell>>;
+
+/// A `Vault` whose operations run on tokio's blocking pool.
+///
+/// Cloning is cheap and shares the vault.
+#[derive(Clone)]
+pub struct AsyncVault {
+    vault: Arc<Mutex<Vault>>,
+}
+
+impl AsyncVault {
+    /// Wrap `vault`.
+    pub fn new(vault: Vault) -> Self {
+        Self {
+            vault: Arc::new(Mutex::new(vault)),
+        }
+    }
+
+    /// Run `f` on the blocking pool with the vault locked, e.g. to read the
+    /// audit log or call an operation this facade does not wrap.
+    pub async fn with_vault<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Vault) -> R + Send + 'static,
+    ) -> Result<R, HexvaultError> {
+        let vault = Arc::clone(&self.vault);
+        run(move || Ok(f(&mut lock(&vault)))).await
+    }
+
+    /// Get the partition `id`, shared for use with the other operations.
+    pub async fn get_partition(&self, id: &str) -> Result<Arc<Partition>, HexvaultError> {
+        let id = id.to_string();
+        self.with_vault(move |vault| vault.get_partition(&id).map(Arc::new))
+            .await?
+    }
+
+    /// `Partition::seal` on the blocking pool. `plaintext` is zeroised once
+    /// sealed.
+    pub async fn seal(
+        &self,
+        partition: Arc<Partition>,
+        cell: SharedCell,
+        key: String,
+        mut plaintext: Vec<u8>,
+        layer: Layer,
+        token: String,
+    ) -> Result<(), HexvaultError> {
+        run(move || {
+            let result = partition.seal(&mut lock(&cell), &key, &plaintext, layer, &token);
+            plaintext.zeroize();
+            result
+        })
+        .await
+    }
+
+    /// `Partition::open_guarded` on the blocking pool.
+    pub async fn open(
+        &self,
+        partition: Arc<Partition>,
+        cell: SharedCell,
+        key: String,
+        token: String,
+    ) -> Result<SecretBytes, HexvaultError> {
+        run(move || partition.open_guarded(&lock(&cell), &key, &token)).await
+    }
+
+    /// `Vault::traverse` on the blocking pool. The vault is locked only
+    /// briefly, before and after the payload moves.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidTraversal` if `source` and `dest` are
+    /// the same shared cell, and otherwise the errors of `Vault::traverse`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn traverse(
+        &self,
+        source_partition: Arc<Partition>,
+        source: SharedCell,
+        dest_partition: Arc<Partition>,
+        dest: SharedCell,
+        key: String,
+        target_layer: Layer,
+        source_token: String,
+        dest_token: String,
+    ) -> Result<AuditRecord, HexvaultError> {
+        if Arc::ptr_eq(&source, &dest) {
+            return Err(HexvaultError::InvalidTraversal(
+                "source and destination are the same cell".into(),
+            ));
+        }
+        let vault = Arc::clone(&self.vault);
+        run(move || {
+            let (resolver, clock, actor) = {
+                let vault = lock(&vault);
+                target_layer.check_min(vault.min_layer)?;
+                let resolver = Arc::clone(&vault.token_resolver);
+                (resolver, Arc::clone(&vault.clock), vault.actor.clone())
+            };
+            let source_ctx = resolver.resolve(&source_token)?;
+            let dest_ctx = resolver.resolve(&dest_token)?;
+
+            // Lock the cells in address order, so two traversals in opposite
+            // directions cannot deadlock.
+            let (source, mut dest) = if Arc::as_ptr(&source) < Arc::as_ptr(&dest) {
+                let source = lock(&source);
+                (source, lock(&dest))
+            } else {
+                let dest = lock(&dest);
+                (lock(&source), dest)
+            };
+            // The record is chained into the vault's log once the payload
+            // has moved.
+            let record = edge::traverse(
+                &mut AuditLog::new(),
+                TraversalRequest {
+                    source_partition_key: source_partition.key_for(&source, &key)?,
+                    dest_partition_key: dest_partition.key(),
+                    source: &source,
+                    dest: &mut dest,
+                    key: &key,
+                    dest_key: None,
+                    target_layer,
+                    source_ctx: &source_ctx,
+                    dest_ctx: &dest_ctx,
+                    dest_suite: None,
+                    source_aad: &[],
+                    source_guard: source_partition.peel_options().guard,
+                    dest_aad: &[],
+                    actor: actor.as_deref(),
+                    now: clock.now(),
+                },
+            )?;
+            lock(&vault).append_traversal(record)
+        })
+        .await
+    }
+}
+
+/// Lock `mutex`, recovering the data if another thread panicked while
+/// holding it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Run `f` on the blocking pool. A panic in `f` resumes in the caller.
+async fn run<R: Send + 'static>(
+    f: impl FnOnce() -> Result<R, HexvaultError> + Send + 'static,
+) -> Result<R, HexvaultError> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        // The runtime is shutting down.
+        Err(e) => Err(HexvaultError::Io(io::Error::other(e))),
+    }
+}
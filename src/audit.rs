@@ -1,6 +1,6 @@
 //! Immutable audit logging.
 //!
-//! Records every edge traversal. The log is append-only.
+//! Records every edge traversal and payload removal. The log is append-only.
 //! Supports pluggable sinks for forwarding records to files, S3, etc.
 //!
 //! ## Tamper evidence
@@ -8,19 +8,41 @@
 //! Each record contains a SHA-256 hash that chains to the previous record.
 //! Tampering with or removing any record breaks the chain, which is
 //! detectable via `AuditLog::verify_chain()`.
+//!
+//! ## Concurrent staging
+//!
+//! Worker threads that cannot hold `&mut AuditLog` stage records through a
+//! `PendingAppender`. Staged records are buffered in sharded queues and
+//! merged into the log by `AuditLog::flush_pending()`, which assigns the
+//! chain hashes in the order the records were staged.
+//!
+//! ## Confidential audit files
+//!
+//! `EncryptedFileAuditSink` seals each record under a key derived from the
+//! master key before writing it, so cell IDs and timestamps are not readable
+//! at rest. `EncryptedFileAuditSink::load` decrypts the file back into an
+//! `AuditLog` whose chain can then be checked with `verify_chain()`.
 
+use std::cell::Cell as StdCell;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
 use std::fs::OpenOptions;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Write};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, Utc};
 use ring::digest;
 use serde::{Deserialize, Serialize};
 
+use crate::crypto::{self, CipherSuite, KdfHash};
+use crate::error::HexvaultError;
+use crate::keys::{self, DerivedKey, MasterKey};
 use crate::stack::Layer;
 
-fn to_hex(bytes: &[u8]) -> String {
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
     let mut s = String::with_capacity(bytes.len() * 2);
     for &b in bytes {
         // write! to a String is infallible — String's fmt::Write never
@@ -30,11 +52,68 @@ fn to_hex(bytes: &[u8]) -> String {
     s
 }
 
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
 /// A sink that receives audit records. Implement this to forward records
 /// to a file, database, S3, or other persistent store.
 pub trait AuditSink: Send {
     /// Append a record. Called for every edge traversal.
     fn append(&mut self, record: AuditRecord);
+
+    /// Make every appended record durable. The default does nothing, for
+    /// sinks that hold no buffered state.
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    /// Flush and release the sink's resources, e.g. its file handle, at
+    /// shutdown. The default flushes and drops the sink; a flush error can
+    /// no longer be reported, so call `flush` first where it matters.
+    fn close(mut self: Box<Self>) {
+        let _ = self.flush();
+    }
+}
+
+/// The kind of operation an audit record describes.
+///
+/// Records written before this field existed deserialise as `Traverse`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AuditEvent {
+    /// A payload moved between cells via an edge traversal.
+    #[default]
+    Traverse,
+    /// A payload was removed from a cell, e.g. by a retention sweep.
+    /// Source and destination are both the cell it was removed from.
+    Remove,
+    /// A payload was re-sealed in place at a lower layer. `layer` on the
+    /// record is the new layer; `from` is the layer it was sealed at before.
+    Downgrade { from: Layer },
+    /// A payload was re-sealed in place at the same layer with fresh nonces.
+    Reseal,
+}
+
+impl AuditEvent {
+    /// Mix the event into a record's chain hash. `Traverse` contributes
+    /// nothing so that chains written before events existed still verify.
+    fn hash_into(self, ctx: &mut digest::Context) {
+        match self {
+            Self::Traverse => {}
+            Self::Remove => ctx.update(b"remove"),
+            Self::Downgrade { from } => {
+                ctx.update(b"downgrade");
+                ctx.update(&(from as u8).to_be_bytes());
+            }
+            Self::Reseal => ctx.update(b"reseal"),
+        }
+    }
 }
 
 /// A permanent record of a data movement event.
@@ -50,6 +129,56 @@ pub struct AuditRecord {
     pub timestamp: DateTime<Utc>,
     /// Cryptographic hash linking to the previous record in the chain.
     pub entry_hash: String,
+    /// The record's position in its log, starting at 1 and never reused,
+    /// even across `drain`. Assigned by `append`, like `entry_hash`.
+    /// Records written before sequence numbers existed read back as 0.
+    #[serde(default)]
+    pub seq: u64,
+    /// Who performed the operation, e.g. a user or service ID, if the
+    /// caller supplied one. Part of the chain hash, so it cannot be altered
+    /// after the fact.
+    #[serde(default)]
+    pub actor: Option<String>,
+    /// What happened. Defaults to `Traverse`.
+    #[serde(default)]
+    pub event: AuditEvent,
+    /// The cipher suite the destination payload was sealed with, read
+    /// back from its header after a traversal, so an auditor can confirm
+    /// the algorithm was not downgraded. `None` for other events and for
+    /// records written before this field existed. Part of the chain hash.
+    #[serde(default)]
+    pub cipher_suite: Option<CipherSuite>,
+    /// The ciphertext format version of the destination payload, alongside
+    /// `cipher_suite`. Part of the chain hash.
+    #[serde(default)]
+    pub format_version: Option<u8>,
+}
+
+impl AuditRecord {
+    /// A record of `event` on `cell_id`, sealed at `layer`. Source and
+    /// destination are both `cell_id`, as for in-place events; set
+    /// `dest_cell_id` for a traversal. The chain fields are assigned by
+    /// `AuditLog::append`, and the rest start empty.
+    pub fn new(
+        event: AuditEvent,
+        cell_id: impl Into<String>,
+        layer: Layer,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let cell_id = cell_id.into();
+        Self {
+            source_cell_id: cell_id.clone(),
+            dest_cell_id: cell_id,
+            layer,
+            timestamp,
+            entry_hash: String::new(),
+            seq: 0,
+            actor: None,
+            event,
+            cipher_suite: None,
+            format_version: None,
+        }
+    }
 }
 
 impl fmt::Display for AuditRecord {
@@ -61,25 +190,132 @@ impl fmt::Display for AuditRecord {
         } else {
             &self.entry_hash
         };
+        if self.event != AuditEvent::Traverse {
+            write!(f, "{:?}: ", self.event)?;
+        }
         write!(
             f,
             "{} → {} @ {:?} [{}] (Hash: {})",
             self.source_cell_id, self.dest_cell_id, self.layer, self.timestamp, hash_prefix
-        )
+        )?;
+        if let Some(actor) = &self.actor {
+            write!(f, " by {actor}")?;
+        }
+        Ok(())
     }
 }
 
 /// The genesis hash used as the initial `last_hash` for an empty audit log.
 const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
+fn genesis_hash() -> String {
+    String::from(GENESIS_HASH)
+}
+
+/// Number of shards in the pending queue. Threads are spread across shards
+/// so that concurrent stagers rarely contend on the same lock.
+const PENDING_SHARDS: usize = 16;
+
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SHARD_HINT: StdCell<Option<usize>> = const { StdCell::new(None) };
+}
+
+/// Pick the shard for the calling thread. Each thread is assigned a shard
+/// round-robin on first use and keeps it for its lifetime.
+fn current_shard() -> usize {
+    SHARD_HINT.with(|hint| match hint.get() {
+        Some(shard) => shard,
+        None => {
+            let shard = NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % PENDING_SHARDS;
+            hint.set(Some(shard));
+            shard
+        }
+    })
+}
+
+/// Records staged for a later merge, tagged with a global ticket that fixes
+/// their position in the total order.
+struct PendingQueue {
+    next_ticket: AtomicU64,
+    /// The ticket of the next record to merge. Only `flush_pending` moves it.
+    next_merge: AtomicU64,
+    shards: Vec<Mutex<Vec<(u64, AuditRecord)>>>,
+}
+
+impl Default for PendingQueue {
+    fn default() -> Self {
+        Self {
+            next_ticket: AtomicU64::new(0),
+            next_merge: AtomicU64::new(0),
+            shards: (0..PENDING_SHARDS)
+                .map(|_| Mutex::new(Vec::new()))
+                .collect(),
+        }
+    }
+}
+
+impl PendingQueue {
+    /// A separate queue holding a copy of every record staged so far.
+    fn snapshot(&self) -> Self {
+        Self {
+            next_ticket: AtomicU64::new(self.next_ticket.load(Ordering::Relaxed)),
+            next_merge: AtomicU64::new(self.next_merge.load(Ordering::Relaxed)),
+            shards: self
+                .shards
+                .iter()
+                .map(|shard| Mutex::new(shard.lock().unwrap_or_else(|e| e.into_inner()).clone()))
+                .collect(),
+        }
+    }
+}
+
+/// A cloneable, thread-safe handle for staging audit records.
+///
+/// Staging never touches the hash chain — it only takes a ticket from an
+/// atomic counter and pushes into the calling thread's shard. The records
+/// become part of the log when the owner calls `AuditLog::flush_pending()`.
+#[derive(Clone)]
+pub struct PendingAppender {
+    queue: Arc<PendingQueue>,
+}
+
+impl PendingAppender {
+    /// Stage a record. Its `entry_hash` and `seq` are ignored and assigned
+    /// on merge.
+    pub fn stage(&self, record: AuditRecord) {
+        let ticket = self.queue.next_ticket.fetch_add(1, Ordering::Relaxed);
+        // A poisoned shard still holds valid records — a panic while pushing
+        // cannot leave a half-written entry — so recover the guard.
+        let mut shard = self.queue.shards[current_shard()]
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        shard.push((ticket, record));
+    }
+}
+
 /// An append-only log of all traversals.
 /// Can forward records to additional sinks via `add_forward_sink`.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct AuditLog {
     records: Vec<AuditRecord>,
     last_hash: String,
+    /// The hash the first retained record chains from: genesis, or the
+    /// last record handed out by `drain`. Logs serialised before `drain`
+    /// existed have no anchor and start from genesis.
+    #[serde(default = "genesis_hash")]
+    anchor: String,
+    /// The `seq` of the last record appended, drained or not.
+    #[serde(default)]
+    last_seq: u64,
     #[serde(skip)]
     forward_sinks: Option<Vec<Box<dyn AuditSink>>>,
+    /// Forward sink appends that panicked; see `sink_failures`.
+    #[serde(skip)]
+    sink_failures: u64,
+    #[serde(skip)]
+    pending: Arc<PendingQueue>,
 }
 
 impl std::fmt::Debug for AuditLog {
@@ -90,16 +326,30 @@ impl std::fmt::Debug for AuditLog {
                 "forward_sinks",
                 &self.forward_sinks.as_ref().map(|s| s.len()),
             )
+            .field("sink_failures", &self.sink_failures)
             .finish()
     }
 }
 
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forward sinks are not cloned. Records staged so far are copied into a
+/// queue of the clone's own, so both logs merge them; appenders handed out
+/// before the clone keep staging into the original only.
 impl Clone for AuditLog {
     fn clone(&self) -> Self {
         Self {
             records: self.records.clone(),
             last_hash: self.last_hash.clone(),
-            forward_sinks: None, // Forward sinks are not cloned
+            anchor: self.anchor.clone(),
+            last_seq: self.last_seq,
+            forward_sinks: None,
+            sink_failures: self.sink_failures,
+            pending: Arc::new(self.pending.snapshot()),
         }
     }
 }
@@ -112,6 +362,26 @@ fn compute_record_hash(prev_hash: &str, record: &AuditRecord) -> String {
     ctx.update(record.dest_cell_id.as_bytes());
     ctx.update(&(record.layer as u8).to_be_bytes());
     ctx.update(record.timestamp.timestamp_millis().to_string().as_bytes());
+    record.event.hash_into(&mut ctx);
+    // Legacy records have no sequence number and hash as they always did.
+    if record.seq != 0 {
+        ctx.update(&record.seq.to_be_bytes());
+    }
+    // Length-prefixed so the actor cannot bleed into neighbouring fields.
+    if let Some(actor) = &record.actor {
+        ctx.update(b"actor");
+        ctx.update(&(actor.len() as u64).to_be_bytes());
+        ctx.update(actor.as_bytes());
+    }
+    // Records without sealing parameters hash as they always did.
+    if let Some(suite) = record.cipher_suite {
+        ctx.update(b"suite");
+        ctx.update(&[suite.id()]);
+    }
+    if let Some(version) = record.format_version {
+        ctx.update(b"format");
+        ctx.update(&[version]);
+    }
     to_hex(ctx.finish().as_ref())
 }
 
@@ -120,8 +390,85 @@ impl AuditLog {
         Self {
             records: Vec::new(),
             last_hash: String::from(GENESIS_HASH),
+            anchor: String::from(GENESIS_HASH),
+            last_seq: 0,
             forward_sinks: None,
+            sink_failures: 0,
+            pending: Arc::default(),
+        }
+    }
+
+    /// Return a handle that worker threads can use to stage records
+    /// concurrently. Staged records are merged by `flush_pending()`.
+    pub fn pending_appender(&self) -> PendingAppender {
+        PendingAppender {
+            queue: Arc::clone(&self.pending),
+        }
+    }
+
+    /// Merge every staged record into the log.
+    ///
+    /// Records are appended in ticket order — the order in which `stage`
+    /// was called across all threads — so the hash chain reflects a single
+    /// total order. Merging stops at the first ticket whose `stage` has not
+    /// finished yet: that record and every later one stay staged for the
+    /// next flush. Returns the number of records merged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::AuditSequenceExhausted` if the log fills up;
+    /// the records not yet merged stay staged.
+    pub fn flush_pending(&mut self) -> Result<usize, HexvaultError> {
+        self.flush_pending_with(|_| Ok(()))
+    }
+
+    /// `flush_pending`, passing each record to `prepare` just before it is
+    /// appended. If `prepare` fails, that record and every later one stay
+    /// staged and the error is returned.
+    pub(crate) fn flush_pending_with(
+        &mut self,
+        mut prepare: impl FnMut(&mut AuditRecord) -> Result<(), HexvaultError>,
+    ) -> Result<usize, HexvaultError> {
+        let mut staged = Vec::new();
+        for shard in &self.pending.shards {
+            let mut guard = shard.lock().unwrap_or_else(|e| e.into_inner());
+            staged.append(&mut guard);
+        }
+        staged.sort_unstable_by_key(|(ticket, _)| *ticket);
+
+        // A gap in the tickets is a `stage` still in progress.
+        let mut next = self.pending.next_merge.load(Ordering::Relaxed);
+        let mut staged = staged.into_iter().peekable();
+        let mut count = 0;
+        let mut rest = Vec::new();
+        let mut result = Ok(());
+        while self.last_seq < u64::MAX {
+            let Some((ticket, mut record)) = staged.next_if(|(ticket, _)| *ticket == next) else {
+                break;
+            };
+            if let Err(e) = prepare(&mut record) {
+                rest.push((ticket, record));
+                result = Err(e);
+                break;
+            }
+            self.push(record)?;
+            next += 1;
+            count += 1;
+        }
+        self.pending.next_merge.store(next, Ordering::Relaxed);
+        if result.is_ok() && staged.peek().is_some_and(|(ticket, _)| *ticket == next) {
+            result = Err(HexvaultError::AuditSequenceExhausted);
+        }
+
+        // Tickets keep the original order when these are merged later.
+        rest.extend(staged);
+        if !rest.is_empty() {
+            self.pending.shards[0]
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .extend(rest);
         }
+        result.map(|()| count)
     }
 
     /// Add a sink to receive a copy of every record. Useful for persisting
@@ -130,18 +477,107 @@ impl AuditLog {
         self.forward_sinks.get_or_insert_with(Vec::new).push(sink);
     }
 
+    /// Flush every forward sink, so that all records appended so far are
+    /// durable. Every sink is flushed even if an earlier one fails; the
+    /// first error is returned.
+    pub fn flush_sinks(&mut self) -> Result<(), std::io::Error> {
+        let mut result = Ok(());
+        for sink in self.forward_sinks.iter_mut().flatten() {
+            let flushed = sink.flush();
+            if result.is_ok() {
+                result = flushed;
+            }
+        }
+        result
+    }
+
+    /// Detach and close every forward sink. Records appended afterwards
+    /// stay in the in-memory log only.
+    pub fn close_sinks(&mut self) {
+        for sink in self.forward_sinks.take().into_iter().flatten() {
+            sink.close();
+        }
+    }
+
+    /// Replace the forward sinks with `sinks`, e.g. to rotate to a new
+    /// file. The old sinks are closed first, which flushes them, so no
+    /// record they buffered is lost; `close_sinks` alone removes them.
+    pub fn set_forward_sinks(&mut self, sinks: Vec<Box<dyn AuditSink>>) {
+        self.close_sinks();
+        if !sinks.is_empty() {
+            self.forward_sinks = Some(sinks);
+        }
+    }
+
     /// Append a new record to the log and forward to any attached sinks.
-    pub fn append(&mut self, mut record: AuditRecord) {
+    /// Returns the stored record, with its `seq` and `entry_hash` filled in.
+    ///
+    /// A sink that panics is isolated: the panic is caught and counted in
+    /// `sink_failures`, and the remaining sinks still receive the record.
+    /// The sink stays attached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::AuditSinkFailed` with the number of sinks
+    /// that panicked. The record is in the log regardless.
+    ///
+    /// Returns `HexvaultError::AuditSequenceExhausted`, appending nothing,
+    /// if the last record's `seq` is `u64::MAX`: sequence numbers never wrap.
+    pub fn append(&mut self, record: AuditRecord) -> Result<&AuditRecord, HexvaultError> {
+        let failures = self.sink_failures;
+        self.push(record)?;
+        match self.sink_failures - failures {
+            0 => Ok(&self.records[self.records.len() - 1]),
+            failed => Err(HexvaultError::AuditSinkFailed(failed as usize)),
+        }
+    }
+
+    /// Append like `append`, but only count sink failures. For the vault's
+    /// own records, which are written after the operation they describe has
+    /// changed its cells: a broken sink must not fail it halfway. Sequence
+    /// exhaustion is still an error.
+    pub(crate) fn push(&mut self, mut record: AuditRecord) -> Result<&AuditRecord, HexvaultError> {
+        self.last_seq = self
+            .last_seq
+            .checked_add(1)
+            .ok_or(HexvaultError::AuditSequenceExhausted)?;
+        record.seq = self.last_seq;
         let hash_hex = compute_record_hash(&self.last_hash, &record);
         record.entry_hash = hash_hex.clone();
         self.last_hash = hash_hex;
 
-        if let Some(ref mut sinks) = self.forward_sinks {
-            for sink in sinks.iter_mut() {
-                sink.append(record.clone());
+        for sink in self.forward_sinks.iter_mut().flatten() {
+            let forwarded = panic::catch_unwind(AssertUnwindSafe(|| sink.append(record.clone())));
+            if forwarded.is_err() {
+                self.sink_failures += 1;
             }
         }
         self.records.push(record);
+        Ok(&self.records[self.records.len() - 1])
+    }
+
+    /// The number of forward sink appends that panicked since the log was
+    /// created. Poll it to notice a sink that has stopped persisting
+    /// records; they are still kept in memory.
+    pub fn sink_failures(&self) -> u64 {
+        self.sink_failures
+    }
+
+    /// Remove and return every record held in memory.
+    ///
+    /// The chain tail is kept, so the next appended record chains from the
+    /// last drained one and `verify_chain()` keeps working on what remains.
+    /// Forward sinks already received the drained records. Use this to bound
+    /// memory in long-running processes.
+    pub fn drain(&mut self) -> Vec<AuditRecord> {
+        self.anchor = self.last_hash.clone();
+        std::mem::take(&mut self.records)
+    }
+
+    /// The hash the first in-memory record chains from: the genesis hash,
+    /// or the `entry_hash` of the last record returned by `drain`.
+    pub fn anchor(&self) -> &str {
+        &self.anchor
     }
 
     /// Return the number of records in the log.
@@ -159,15 +595,60 @@ impl AuditLog {
         self.records.iter()
     }
 
+    /// Iterate over the in-memory records with a `seq` greater than `seq`.
+    ///
+    /// For incremental shipping: remember the `seq` of the last record
+    /// forwarded and pass it to the next poll (0 on the first). A shipper
+    /// that fails before saving its position re-sends, so delivery is
+    /// at-least-once. Records already removed by `drain` are not returned.
+    pub fn iter_since(&self, seq: u64) -> std::slice::Iter<'_, AuditRecord> {
+        let start = self.records.partition_point(|record| record.seq <= seq);
+        self.records[start..].iter()
+    }
+
+    /// Iterate over the last `n` in-memory records, oldest first, without
+    /// walking the rest; e.g. for a dashboard's recent activity. Fewer are
+    /// returned if the log holds fewer. Use `.rev()` for newest first.
+    pub fn tail(&self, n: usize) -> std::slice::Iter<'_, AuditRecord> {
+        self.records[self.records.len().saturating_sub(n)..].iter()
+    }
+
+    /// Roll the in-memory records up into counts in one pass. Records
+    /// already removed by `drain` are not counted.
+    pub fn summarize(&self) -> AuditSummary {
+        let mut summary = AuditSummary {
+            records: self.records.len(),
+            first: self.records.first().map(|record| record.timestamp),
+            last: self.records.last().map(|record| record.timestamp),
+            ..AuditSummary::default()
+        };
+        for record in &self.records {
+            if record.event != AuditEvent::Traverse {
+                continue;
+            }
+            summary.traversals += 1;
+            *summary
+                .by_source
+                .entry(record.source_cell_id.clone())
+                .or_default() += 1;
+            *summary
+                .by_dest
+                .entry(record.dest_cell_id.clone())
+                .or_default() += 1;
+            summary.by_layer[record.layer.index()] += 1;
+        }
+        summary
+    }
+
     /// Verify the integrity of the cryptographic hash chain.
     ///
-    /// Re-computes the hash for every record and checks that it matches
-    /// the stored `entry_hash`. If any record has been tampered with,
-    /// removed, or reordered, this method returns `false`.
+    /// Re-computes the hash for every record, starting from `anchor()`, and
+    /// checks that it matches the stored `entry_hash`. If any record has been
+    /// tampered with, removed, or reordered, this method returns `false`.
     ///
     /// An empty log is always valid.
     pub fn verify_chain(&self) -> bool {
-        let mut expected_prev = String::from(GENESIS_HASH);
+        let mut expected_prev = self.anchor.clone();
 
         for record in &self.records {
             let computed = compute_record_hash(&expected_prev, record);
@@ -180,40 +661,395 @@ impl AuditLog {
     }
 }
 
+/// Aggregate counts over an audit log, returned by `AuditLog::summarize`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditSummary {
+    /// The number of records, of every event kind.
+    pub records: usize,
+    /// The number of traversal records.
+    pub traversals: usize,
+    /// Traversals per source cell ID.
+    pub by_source: BTreeMap<String, usize>,
+    /// Traversals per destination cell ID.
+    pub by_dest: BTreeMap<String, usize>,
+    /// Traversals indexed by the destination `Layer::index()`; see
+    /// `count_at`.
+    pub by_layer: [usize; 3],
+    /// The timestamp of the first record, or `None` if there are none.
+    pub first: Option<DateTime<Utc>>,
+    /// The timestamp of the last record, or `None` if there are none.
+    pub last: Option<DateTime<Utc>>,
+}
+
+impl AuditSummary {
+    /// The number of traversals that sealed at `layer`.
+    pub fn count_at(&self, layer: Layer) -> usize {
+        self.by_layer[layer.index()]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Built-in sink: in-memory ring buffer
+// ---------------------------------------------------------------------------
+
+/// Keeps the most recent records in memory, dropping the oldest once
+/// `capacity` is reached. For debugging and tests.
+///
+/// Clones share one buffer: keep a clone to inspect the records after
+/// handing the sink to `AuditLog::add_forward_sink`.
+#[derive(Debug, Clone)]
+pub struct RingBufferAuditSink {
+    records: Arc<Mutex<VecDeque<AuditRecord>>>,
+    capacity: usize,
+}
+
+impl RingBufferAuditSink {
+    /// Create a sink holding at most `capacity` records. A zero capacity
+    /// holds none.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// A copy of the records currently held, oldest first.
+    pub fn records(&self) -> Vec<AuditRecord> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.iter().cloned().collect()
+    }
+}
+
+impl AuditSink for RingBufferAuditSink {
+    fn append(&mut self, record: AuditRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Record encoders
+// ---------------------------------------------------------------------------
+
+/// Turns an audit record into the bytes a sink writes for it, e.g. to match
+/// the format the rest of an audit pipeline reads.
+///
+/// The encoding of each record must be self-delimiting, or carry its own
+/// terminator, since sinks write the encodings back to back.
+pub trait AuditEncoder: Send {
+    /// Encode one record. An empty result writes nothing.
+    fn encode(&self, record: &AuditRecord) -> Vec<u8>;
+}
+
+/// Encodes each record as one line of JSON. The default for
+/// `FileAuditSink`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonLinesEncoder;
+
+impl AuditEncoder for JsonLinesEncoder {
+    fn encode(&self, record: &AuditRecord) -> Vec<u8> {
+        match serde_json::to_vec(record) {
+            Ok(mut line) => {
+                line.push(b'\n');
+                line
+            }
+            Err(e) => {
+                eprintln!("hexvault: JsonLinesEncoder serialization error: {e}");
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Encodes each record as one CBOR item, so a file of them is a CBOR
+/// sequence (RFC 8742). Requires the `cbor` feature.
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CborEncoder;
+
+#[cfg(feature = "cbor")]
+impl AuditEncoder for CborEncoder {
+    fn encode(&self, record: &AuditRecord) -> Vec<u8> {
+        let mut item = Vec::new();
+        if let Err(e) = ciborium::into_writer(record, &mut item) {
+            eprintln!("hexvault: CborEncoder serialization error: {e}");
+            return Vec::new();
+        }
+        item
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Built-in sink: file
 // ---------------------------------------------------------------------------
 
-/// Writes audit records as JSON lines (one per record) to a file.
-/// Creates the file if it doesn't exist; appends if it does.
+/// Writes audit records to a file, as JSON lines (one per record) unless
+/// given another `AuditEncoder`. Creates the file if it doesn't exist;
+/// appends if it does.
 pub struct FileAuditSink {
     file: std::fs::File,
+    encoder: Box<dyn AuditEncoder>,
 }
 
 impl FileAuditSink {
     /// Open or create a file for append-only audit logging.
     pub fn new(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        Self::with_encoder(path, JsonLinesEncoder)
+    }
+
+    /// Open or create a file for append-only audit logging, writing each
+    /// record as `encoder` encodes it.
+    pub fn with_encoder(
+        path: impl AsRef<Path>,
+        encoder: impl AuditEncoder + 'static,
+    ) -> Result<Self, std::io::Error> {
         let file = OpenOptions::new().create(true).append(true).open(path)?;
-        Ok(Self { file })
+        Ok(Self {
+            file,
+            encoder: Box::new(encoder),
+        })
     }
 }
 
 impl AuditSink for FileAuditSink {
     fn append(&mut self, record: AuditRecord) {
-        match serde_json::to_string(&record) {
-            Ok(line) => {
-                if let Err(e) = writeln!(self.file, "{line}") {
-                    eprintln!("hexvault: FileAuditSink write error: {e}");
+        let bytes = self.encoder.encode(&record);
+        if bytes.is_empty() {
+            return;
+        }
+        if let Err(e) = self.file.write_all(&bytes) {
+            eprintln!("hexvault: FileAuditSink write error: {e}");
+        }
+        if let Err(e) = self.file.flush() {
+            eprintln!("hexvault: FileAuditSink flush error: {e}");
+        }
+    }
+
+    /// Flush and fsync the file.
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.file.flush()?;
+        self.file.sync_data()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Built-in sink: async file
+// ---------------------------------------------------------------------------
+
+/// Writes audit records to a file from a tokio task, as JSON lines (one
+/// per record) unless given another `AuditEncoder`. Requires the `async`
+/// feature.
+///
+/// `append` only queues the record for the task, so forwarding never waits
+/// on I/O, e.g. while an `AsyncVault` holds its vault to append a record.
+/// `flush` blocks until every queued record is written and synced: call it
+/// off the executor, e.g. through `AsyncVault::with_vault`. Closing or
+/// dropping the sink lets the task write what is queued and stop.
+#[cfg(feature = "async")]
+pub struct AsyncFileAuditSink {
+    sender: tokio::sync::mpsc::UnboundedSender<AsyncSinkMessage>,
+}
+
+#[cfg(feature = "async")]
+enum AsyncSinkMessage {
+    Record(AuditRecord),
+    Flush(std::sync::mpsc::Sender<Result<(), std::io::Error>>),
+}
+
+#[cfg(feature = "async")]
+impl AsyncFileAuditSink {
+    /// Open or create a file for append-only audit logging, and spawn the
+    /// task that writes to it on the current tokio runtime.
+    pub async fn new(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        Self::with_encoder(path, JsonLinesEncoder).await
+    }
+
+    /// Like `new`, but the task writes each record as `encoder` encodes it.
+    pub async fn with_encoder(
+        path: impl AsRef<Path>,
+        encoder: impl AuditEncoder + 'static,
+    ) -> Result<Self, std::io::Error> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(write_queued_records(file, Box::new(encoder), receiver));
+        Ok(Self { sender })
+    }
+}
+
+/// The task behind an `AsyncFileAuditSink`: writes each queued record in
+/// order, and runs until the sink is gone.
+#[cfg(feature = "async")]
+async fn write_queued_records(
+    mut file: tokio::fs::File,
+    encoder: Box<dyn AuditEncoder>,
+    mut receiver: tokio::sync::mpsc::UnboundedReceiver<AsyncSinkMessage>,
+) {
+    use tokio::io::AsyncWriteExt;
+
+    while let Some(message) = receiver.recv().await {
+        match message {
+            AsyncSinkMessage::Record(record) => {
+                let bytes = encoder.encode(&record);
+                if bytes.is_empty() {
+                    continue;
                 }
-                if let Err(e) = self.file.flush() {
-                    eprintln!("hexvault: FileAuditSink flush error: {e}");
+                if let Err(e) = file.write_all(&bytes).await {
+                    eprintln!("hexvault: AsyncFileAuditSink write error: {e}");
                 }
+                if let Err(e) = file.flush().await {
+                    eprintln!("hexvault: AsyncFileAuditSink flush error: {e}");
+                }
+            }
+            AsyncSinkMessage::Flush(done) => {
+                let synced = match file.flush().await {
+                    Ok(()) => file.sync_data().await,
+                    Err(e) => Err(e),
+                };
+                // The flusher may have given up waiting.
+                let _ = done.send(synced);
+            }
+        }
+    }
+    if let Err(e) = file.sync_data().await {
+        eprintln!("hexvault: AsyncFileAuditSink sync error: {e}");
+    }
+}
+
+#[cfg(feature = "async")]
+impl AuditSink for AsyncFileAuditSink {
+    fn append(&mut self, record: AuditRecord) {
+        if self.sender.send(AsyncSinkMessage::Record(record)).is_err() {
+            eprintln!("hexvault: AsyncFileAuditSink writer has stopped");
+        }
+    }
+
+    /// Wait for the task to write and fsync every queued record.
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        let stopped = || std::io::Error::other("AsyncFileAuditSink writer has stopped");
+        let (done, synced) = std::sync::mpsc::channel();
+        self.sender
+            .send(AsyncSinkMessage::Flush(done))
+            .map_err(|_| stopped())?;
+        synced.recv().map_err(|_| stopped())?
+    }
+
+    /// Detach without waiting: the task still writes and syncs every
+    /// queued record before it stops. Call `flush` first to wait for that.
+    fn close(self: Box<Self>) {}
+}
+
+// ---------------------------------------------------------------------------
+// Built-in sink: encrypted file
+// ---------------------------------------------------------------------------
+
+/// AAD bound to every encrypted audit line. Keeps audit ciphertext from
+/// being accepted anywhere a cell payload is expected, and vice versa.
+const AUDIT_AAD: &[u8] = b"hexvault:audit";
+
+/// Writes audit records to a file, one encrypted record per line.
+///
+/// Each line is the hex encoding of the record's JSON sealed with
+/// AES-256-GCM under a key derived from the master key with the reserved
+/// `"audit"` tag. The file stays append-only; holders of the master key can
+/// read it back with `EncryptedFileAuditSink::load`.
+pub struct EncryptedFileAuditSink {
+    file: std::fs::File,
+    key: DerivedKey,
+}
+
+impl EncryptedFileAuditSink {
+    /// Open or create a file for encrypted, append-only audit logging.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::Io` if the file cannot be opened, or
+    /// `HexvaultError::KeyDerivationFailure` if the audit key cannot be
+    /// derived.
+    pub fn new(path: impl AsRef<Path>, master: &MasterKey) -> Result<Self, HexvaultError> {
+        let key = keys::derive_audit_key(master)?;
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file, key })
+    }
+
+    /// Decrypt every record in an encrypted audit file into an `AuditLog`.
+    ///
+    /// The records keep their stored `entry_hash` values, so the caller
+    /// should run `verify_chain()` on the result to detect tampering,
+    /// reordering, or removal of all but trailing lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::Io` if the file cannot be read,
+    /// `HexvaultError::MalformedCiphertext` if a line is not a well-formed
+    /// sealed record, or `HexvaultError::DecryptionFailure` if a record was
+    /// sealed under a different master key or has been modified.
+    pub fn load(path: impl AsRef<Path>, master: &MasterKey) -> Result<AuditLog, HexvaultError> {
+        let key = keys::derive_audit_key(master)?;
+        let reader = BufReader::new(std::fs::File::open(path)?);
+
+        let mut log = AuditLog::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
             }
+            let sealed = from_hex(&line).ok_or(HexvaultError::MalformedCiphertext)?;
+            let json = crypto::decrypt(key.as_bytes(), &sealed, AUDIT_AAD)?;
+            let record: AuditRecord =
+                serde_json::from_slice(&json).map_err(|_| HexvaultError::DecryptionFailure)?;
+            log.last_hash = record.entry_hash.clone();
+            log.last_seq = record.seq;
+            log.records.push(record);
+        }
+        Ok(log)
+    }
+}
+
+impl AuditSink for EncryptedFileAuditSink {
+    fn append(&mut self, record: AuditRecord) {
+        let json = match serde_json::to_vec(&record) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("hexvault: EncryptedFileAuditSink serialization error: {e}");
+                return;
+            }
+        };
+        let sealed = match crypto::encrypt(
+            CipherSuite::default(),
+            KdfHash::default(),
+            self.key.as_bytes(),
+            &json,
+            AUDIT_AAD,
+        ) {
+            Ok(sealed) => sealed,
             Err(e) => {
-                eprintln!("hexvault: FileAuditSink serialization error: {e}");
+                eprintln!("hexvault: EncryptedFileAuditSink encryption error: {e}");
+                return;
             }
+        };
+        if let Err(e) = writeln!(self.file, "{}", to_hex(&sealed)) {
+            eprintln!("hexvault: EncryptedFileAuditSink write error: {e}");
+        }
+        if let Err(e) = self.file.flush() {
+            eprintln!("hexvault: EncryptedFileAuditSink flush error: {e}");
         }
     }
+
+    /// Flush and fsync the file.
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.file.flush()?;
+        self.file.sync_data()
+    }
 }
 
 #[cfg(test)]
@@ -226,19 +1062,20 @@ mod tests {
         let mut log = AuditLog::new();
 
         log.append(AuditRecord {
-            source_cell_id: "cell-a".into(),
             dest_cell_id: "cell-b".into(),
-            layer: Layer::AtRest,
-            timestamp: Utc::now(),
-            entry_hash: String::new(),
-        });
+            ..AuditRecord::new(AuditEvent::Traverse, "cell-a", Layer::AtRest, Utc::now())
+        })
+        .unwrap();
         log.append(AuditRecord {
-            source_cell_id: "cell-b".into(),
             dest_cell_id: "cell-c".into(),
-            layer: Layer::SessionBound,
-            timestamp: Utc::now(),
-            entry_hash: String::new(),
-        });
+            ..AuditRecord::new(
+                AuditEvent::Traverse,
+                "cell-b",
+                Layer::SessionBound,
+                Utc::now(),
+            )
+        })
+        .unwrap();
 
         // Serialize
         let json = serde_json::to_string(&log).expect("serialize");
@@ -257,11 +1094,9 @@ mod tests {
     #[test]
     fn test_audit_record_display() {
         let record = AuditRecord {
-            source_cell_id: "cell-a".into(),
             dest_cell_id: "cell-b".into(),
-            layer: Layer::AtRest,
-            timestamp: Utc::now(),
             entry_hash: "abcdef0123456789".into(),
+            ..AuditRecord::new(AuditEvent::Traverse, "cell-a", Layer::AtRest, Utc::now())
         };
 
         let display = format!("{record}");
@@ -274,11 +1109,9 @@ mod tests {
     fn test_audit_record_display_short_hash() {
         // F8: Confirm Display does not panic with a short or empty hash.
         let record = AuditRecord {
-            source_cell_id: "x".into(),
             dest_cell_id: "y".into(),
-            layer: Layer::AtRest,
-            timestamp: Utc::now(),
             entry_hash: "abc".into(),
+            ..AuditRecord::new(AuditEvent::Traverse, "x", Layer::AtRest, Utc::now())
         };
 
         let display = format!("{record}");
@@ -289,19 +1122,15 @@ mod tests {
     fn test_verify_chain_valid() {
         let mut log = AuditLog::new();
         log.append(AuditRecord {
-            source_cell_id: "a".into(),
             dest_cell_id: "b".into(),
-            layer: Layer::AtRest,
-            timestamp: Utc::now(),
-            entry_hash: String::new(),
-        });
+            ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, Utc::now())
+        })
+        .unwrap();
         log.append(AuditRecord {
-            source_cell_id: "b".into(),
             dest_cell_id: "c".into(),
-            layer: Layer::AccessGated,
-            timestamp: Utc::now(),
-            entry_hash: String::new(),
-        });
+            ..AuditRecord::new(AuditEvent::Traverse, "b", Layer::AccessGated, Utc::now())
+        })
+        .unwrap();
         assert!(log.verify_chain());
     }
 
@@ -309,19 +1138,15 @@ mod tests {
     fn test_verify_chain_tampered() {
         let mut log = AuditLog::new();
         log.append(AuditRecord {
-            source_cell_id: "a".into(),
             dest_cell_id: "b".into(),
-            layer: Layer::AtRest,
-            timestamp: Utc::now(),
-            entry_hash: String::new(),
-        });
+            ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, Utc::now())
+        })
+        .unwrap();
         log.append(AuditRecord {
-            source_cell_id: "b".into(),
             dest_cell_id: "c".into(),
-            layer: Layer::AccessGated,
-            timestamp: Utc::now(),
-            entry_hash: String::new(),
-        });
+            ..AuditRecord::new(AuditEvent::Traverse, "b", Layer::AccessGated, Utc::now())
+        })
+        .unwrap();
 
         // Tamper: mutate a record's cell ID after insertion.
         // We need interior access — use serde roundtrip to get mutable records.
@@ -335,6 +1160,172 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_flush_pending_merges_concurrent_stages() {
+        let mut log = AuditLog::new();
+        let appender = log.pending_appender();
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let appender = appender.clone();
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        appender.stage(AuditRecord {
+                            dest_cell_id: format!("r{i}"),
+                            ..AuditRecord::new(
+                                AuditEvent::Traverse,
+                                format!("t{t}"),
+                                Layer::AtRest,
+                                Utc::now(),
+                            )
+                        });
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(
+            log.is_empty(),
+            "staged records must not appear before a flush"
+        );
+        assert_eq!(log.flush_pending().unwrap(), 200);
+        assert_eq!(log.len(), 200);
+        assert!(log.verify_chain());
+
+        // Per-thread order is preserved by the ticket ordering.
+        let t0: Vec<_> = log
+            .iter()
+            .filter(|r| r.source_cell_id == "t0")
+            .map(|r| r.dest_cell_id.clone())
+            .collect();
+        let expected: Vec<_> = (0..50).map(|i| format!("r{i}")).collect();
+        assert_eq!(t0, expected);
+
+        assert_eq!(log.flush_pending().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_flush_pending_waits_for_earlier_tickets() {
+        let record = |dest: &str| AuditRecord {
+            dest_cell_id: dest.into(),
+            ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, Utc::now())
+        };
+        let mut log = AuditLog::new();
+        let appender = log.pending_appender();
+
+        // A stage that has taken ticket 0 but not yet pushed its record.
+        let in_flight = log.pending.next_ticket.fetch_add(1, Ordering::Relaxed);
+        appender.stage(record("second"));
+        assert_eq!(log.flush_pending().unwrap(), 0);
+        assert!(log.is_empty());
+
+        // A clone takes its own copy of the staged records.
+        let mut clone = log.clone();
+        log.pending.shards[PENDING_SHARDS - 1]
+            .lock()
+            .unwrap()
+            .push((in_flight, record("first")));
+        assert_eq!(log.flush_pending().unwrap(), 2);
+        let order: Vec<_> = log.iter().map(|r| r.dest_cell_id.as_str()).collect();
+        assert_eq!(order, ["first", "second"]);
+        assert!(log.verify_chain());
+
+        assert_eq!(clone.flush_pending().unwrap(), 0);
+        clone.pending.shards[0]
+            .lock()
+            .unwrap()
+            .push((in_flight, record("first")));
+        assert_eq!(clone.flush_pending().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_summarize_counts_traversals() {
+        let record = |source: &str, dest: &str, layer, event| AuditRecord {
+            dest_cell_id: dest.into(),
+            ..AuditRecord::new(event, source, layer, Utc::now())
+        };
+        let mut log = AuditLog::new();
+        assert_eq!(log.summarize(), AuditSummary::default());
+
+        log.append(record("a", "b", Layer::AtRest, AuditEvent::Traverse))
+            .unwrap();
+        log.append(record("a", "c", Layer::SessionBound, AuditEvent::Traverse))
+            .unwrap();
+        log.append(record("b", "b", Layer::AtRest, AuditEvent::Remove))
+            .unwrap();
+
+        let summary = log.summarize();
+        assert_eq!(summary.records, 3);
+        assert_eq!(summary.traversals, 2);
+        assert_eq!(summary.by_source, BTreeMap::from([("a".into(), 2)]));
+        assert_eq!(
+            summary.by_dest,
+            BTreeMap::from([("b".into(), 1), ("c".into(), 1)])
+        );
+        assert_eq!(summary.count_at(Layer::AtRest), 1);
+        assert_eq!(summary.count_at(Layer::AccessGated), 0);
+        assert_eq!(summary.first, Some(log.iter().next().unwrap().timestamp));
+        assert_eq!(summary.last, Some(log.iter().last().unwrap().timestamp));
+    }
+
+    #[test]
+    fn test_drain_preserves_chain_tail() {
+        let record = |source: &str| AuditRecord {
+            dest_cell_id: "dest".into(),
+            ..AuditRecord::new(AuditEvent::Traverse, source, Layer::AtRest, Utc::now())
+        };
+        let mut log = AuditLog::new();
+        log.append(record("a")).unwrap();
+        log.append(record("b")).unwrap();
+
+        let drained = log.drain();
+        assert_eq!(drained.len(), 2);
+        assert!(log.is_empty());
+        assert_eq!(log.anchor(), drained[1].entry_hash);
+
+        log.append(record("c")).unwrap();
+        assert!(log.verify_chain());
+
+        // The drained batch plus the remainder form one continuous chain.
+        let mut full = AuditLog::new();
+        for r in drained.into_iter().chain(log.iter().cloned()) {
+            full.append(r).unwrap();
+        }
+        assert_eq!(
+            full.iter().last().unwrap().entry_hash,
+            log.iter().last().unwrap().entry_hash
+        );
+
+        // The anchor survives serialisation.
+        let restored: AuditLog =
+            serde_json::from_str(&serde_json::to_string(&log).unwrap()).unwrap();
+        assert!(restored.verify_chain());
+    }
+
+    #[test]
+    fn test_sequence_exhaustion_is_an_error() {
+        let record = || AuditRecord {
+            dest_cell_id: "b".into(),
+            ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, Utc::now())
+        };
+        let mut log = AuditLog::new();
+        log.last_seq = u64::MAX - 1;
+        assert_eq!(log.append(record()).unwrap().seq, u64::MAX);
+
+        let err = log.append(record()).unwrap_err();
+        assert!(matches!(err, HexvaultError::AuditSequenceExhausted));
+        assert_eq!(log.len(), 1);
+        assert!(log.verify_chain());
+
+        // Staged records stay staged rather than being dropped.
+        log.pending_appender().stage(record());
+        assert!(log.flush_pending().is_err());
+        assert_eq!(log.len(), 1);
+    }
+
     #[test]
     fn test_verify_chain_empty() {
         let log = AuditLog::new();
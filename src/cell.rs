@@ -4,36 +4,345 @@
 //! payloads and ensures that they are only accessible through keys derived
 //! using the cell's unique identity.
 
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{Read, Write};
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::crypto::{self, CipherSuite, KdfHash, TagLength};
 use crate::error::HexvaultError;
-use crate::keys::PartitionKey;
-use crate::stack::{self, Layer, LayerContext};
+use crate::keys::{self, KeyId, PartitionKey};
+use crate::snapshot::{self, Reader};
+use crate::stack::{self, Layer, LayerContext, PeelOptions, SealOptions};
+use crate::stream;
 
 /// A unique identifier for a cell.
 pub type CellId = String;
 
+thread_local! {
+    /// The buffer `Cell::verify_at` peels in, kept so that integrity sweeps
+    /// allocate nothing per payload once it has grown. Zeroised after use.
+    static VERIFY_BUF: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An HMAC-SHA256 of a payload's plaintext under a key derived from its
+/// partition key; see `Payload::content_id`.
+pub type ContentId = [u8; 32];
+
+/// Payload keys starting with this prefix are reserved for the library's
+/// internal use and are rejected by `Cell::store` with
+/// `HexvaultError::ReservedKey`, so caller data can never shadow an
+/// internal name.
+pub const RESERVED_KEY_PREFIX: &str = "__hexvault:";
+
+/// Reject payload keys in the reserved namespace.
+pub(crate) fn check_key(key: &str) -> Result<(), HexvaultError> {
+    if key.starts_with(RESERVED_KEY_PREFIX) {
+        return Err(HexvaultError::ReservedKey);
+    }
+    Ok(())
+}
+
 /// A payload stored within a cell.
 pub struct Payload {
     /// The encrypted bytes.
     pub data: Vec<u8>,
     /// The layer at which this payload was sealed.
     pub sealed_at: Layer,
+    /// When this payload was sealed into the cell.
+    pub created_at: DateTime<Utc>,
+    /// The ID of the master key the payload was sealed under.
+    pub key_id: KeyId,
+    /// When the payload stops opening, if it was sealed with an expiry.
+    /// Bound into the ciphertext's AAD, so changing it breaks decryption.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// A stable ID for the plaintext, if sealed with
+    /// `SealOptions::content_id`: equal plaintexts in the same partition,
+    /// under the same master key, get equal IDs, which are unlinkable
+    /// without that key. Checked against the plaintext whenever the
+    /// payload is opened, so a tampered ID fails with
+    /// `IntegrityCheckFailed`.
+    pub content_id: Option<ContentId>,
+}
+
+impl Payload {
+    /// The cipher suite named in the outermost ciphertext header, or `None`
+    /// if the header is missing or unrecognised.
+    pub fn cipher_suite(&self) -> Option<CipherSuite> {
+        crypto::read_suite(&self.data).ok()
+    }
+
+    /// The KDF hash named in the outermost ciphertext header, or `None` if
+    /// the header is missing or unrecognised.
+    pub fn kdf_hash(&self) -> Option<KdfHash> {
+        crypto::read_header(&self.data).ok().map(|(_, kdf)| kdf)
+    }
+
+    /// Returns true if the payload was sealed with a plaintext checksum
+    /// (`SealOptions::checksum`).
+    pub fn has_checksum(&self) -> bool {
+        crypto::has_checksum(&self.data)
+    }
+
+    /// Returns true if the payload was sealed as a chunked stream by
+    /// `Vault::seal_reader`. Every cell operation handles such payloads,
+    /// buffering the whole plaintext; re-sealing keeps them streams.
+    pub fn is_stream(&self) -> bool {
+        stream::is_stream(&self.data)
+    }
+
+    /// The tag length named in the outermost ciphertext header
+    /// (`SealOptions::tag_length`).
+    pub fn tag_length(&self) -> TagLength {
+        crypto::tag_length(&self.data)
+    }
+
+    /// Bytes the stored ciphertext adds to the plaintext: every layer's
+    /// header, nonce, and tag, plus the checksum if there is one.
+    pub fn overhead(&self) -> usize {
+        let suite = self.cipher_suite().unwrap_or_default();
+        let checksum = if self.has_checksum() {
+            crypto::CHECKSUM_LEN
+        } else {
+            0
+        };
+        stack::overhead_for(self.sealed_at, suite, self.tag_length()) + checksum
+    }
+
+    /// The layers the payload was sealed through, in sealing order: from
+    /// `AtRest` up to `sealed_at`. Peeling runs in the reverse order.
+    pub fn layer_stack(&self) -> Vec<Layer> {
+        self.sealed_at.up_to().to_vec()
+    }
+
+    /// The format version, cipher suite, and layer of this payload, read
+    /// from its outermost header without decrypting anything.
+    pub fn header(&self) -> PayloadHeader {
+        PayloadHeader {
+            format_version: self.data.first().copied(),
+            cipher_suite: self.cipher_suite(),
+            sealed_at: self.sealed_at,
+        }
+    }
+
+    /// Non-secret metadata describing this payload.
+    pub fn info(&self) -> PayloadInfo {
+        PayloadInfo {
+            layer: self.sealed_at,
+            created_at: self.created_at,
+            suite: self.cipher_suite(),
+            len: self.data.len(),
+            key_id: self.key_id,
+            expires_at: self.expires_at,
+            content_id: self.content_id,
+        }
+    }
+}
+
+/// Prints the layer and ciphertext length, never the ciphertext itself.
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Payload")
+            .field("sealed_at", &self.sealed_at)
+            .field("len", &self.data.len())
+            .finish()
+    }
+}
+
+/// What a payload's outermost header says about how it was sealed.
+///
+/// The header is readable without the master key or any context, but is
+/// not authenticated until the payload is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadHeader {
+    /// The header's first byte: the ciphertext format version, 1 for every
+    /// layered payload this release writes. Payloads sealed with
+    /// `Vault::seal_reader` begin with the stream magic instead.
+    pub format_version: Option<u8>,
+    /// The cipher suite of the outermost layer, if recognised.
+    pub cipher_suite: Option<CipherSuite>,
+    /// The layer the payload was sealed at.
+    pub sealed_at: Layer,
+}
+
+/// Non-secret metadata about a stored payload.
+///
+/// Everything here is readable without any layer context, which makes it
+/// suitable for retention and housekeeping policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadInfo {
+    /// The layer the payload was sealed at.
+    pub layer: Layer,
+    /// When the payload was sealed into the cell.
+    pub created_at: DateTime<Utc>,
+    /// The cipher suite of the outermost layer, if recognised.
+    pub suite: Option<CipherSuite>,
+    /// Length of the sealed ciphertext in bytes.
+    pub len: usize,
+    /// The ID of the master key the payload was sealed under.
+    pub key_id: KeyId,
+    /// When the payload stops opening, if it was sealed with an expiry.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The payload's content ID, if it was sealed with one.
+    pub content_id: Option<ContentId>,
+}
+
+impl PayloadInfo {
+    /// The layers the payload was sealed through, in sealing order. See
+    /// `Payload::layer_stack`.
+    pub fn layer_stack(&self) -> Vec<Layer> {
+        self.layer.up_to().to_vec()
+    }
+}
+
+/// Identifies a `SealedBundle`.
+pub const BUNDLE_FORMAT: &str = "hexvault-bundle";
+
+/// The `SealedBundle` version, the only one `Cell::to_bundle` writes and
+/// `Cell::from_bundle` accepts.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// A self-contained, serialisable copy of a cell for handing it to another
+/// process that holds the same master keys.
+///
+/// Holds only ciphertext and non-secret metadata: no plaintext and no keys.
+/// Where `Cell::write_to` is a compact binary file format, a bundle is a
+/// serde value for embedding in whatever interchange format the caller
+/// already uses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedBundle {
+    /// Always `BUNDLE_FORMAT`.
+    pub format: String,
+    /// The bundle version, `BUNDLE_VERSION` when written by this release.
+    pub version: u32,
+    /// The ID of the bundled cell.
+    pub cell_id: CellId,
+    /// The cell's schema version; see `Cell::schema_version`.
+    pub schema_version: u32,
+    /// Whether the schema version is bound into key derivation.
+    pub schema_bound: bool,
+    /// The cell's label; see `Cell::label`.
+    pub label: Option<String>,
+    /// The cell's payloads, ordered by key.
+    pub payloads: Vec<BundledPayload>,
+}
+
+/// One payload in a `SealedBundle`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledPayload {
+    /// The payload key.
+    pub key: String,
+    /// The layer the payload was sealed at.
+    pub layer: Layer,
+    /// The ID of the master key the payload was sealed under.
+    pub key_id: KeyId,
+    /// When the payload was sealed into the cell.
+    pub created_at: DateTime<Utc>,
+    /// When the payload stops opening, if it was sealed with an expiry.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The payload's content ID, if it was sealed with one.
+    pub content_id: Option<ContentId>,
+    /// The sealed ciphertext.
+    pub data: Vec<u8>,
 }
 
 /// An independent encryption domain.
 pub struct Cell {
     id: CellId,
     payloads: HashMap<String, Payload>,
+    /// The application's schema version for the cell's payloads; 0 unless
+    /// set at creation.
+    schema_version: u32,
+    /// Whether `schema_version` is mixed into key derivation.
+    schema_bound: bool,
+    /// A human-readable name for operators. Never used in key derivation.
+    label: Option<String>,
+}
+
+/// Prints the cell ID and each payload's layer and length, sorted by key.
+/// Ciphertext is never printed.
+impl fmt::Debug for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let payloads: BTreeMap<_, _> = self.payloads.iter().collect();
+        f.debug_struct("Cell")
+            .field("id", &self.id)
+            .field("count", &self.payloads.len())
+            .field("payloads", &payloads)
+            .finish()
+    }
 }
 
 impl Cell {
     /// Create a new, empty cell.
+    ///
+    /// The ID is not validated here; an empty ID is only rejected later, at
+    /// key derivation. Prefer `Cell::try_new`, which rejects it up front.
     pub fn new(id: CellId) -> Self {
         Self {
             id,
             payloads: HashMap::new(),
+            schema_version: 0,
+            schema_bound: false,
+            label: None,
+        }
+    }
+
+    /// Create a new, empty cell after validating its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidCellId` if `id` is empty. An empty ID
+    /// would derive the same keys as every other empty-ID cell, collapsing
+    /// isolation. Other characters (including `:`) are safe because the HKDF
+    /// info string is length-prefixed.
+    pub fn try_new(id: CellId) -> Result<Self, HexvaultError> {
+        if id.is_empty() {
+            return Err(HexvaultError::InvalidCellId);
         }
+        Ok(Self::new(id))
+    }
+
+    /// Create a new, empty cell tagged with the application's schema
+    /// version for its payloads, so consumers can check
+    /// `Cell::schema_version` before reading. The version is metadata only;
+    /// see `Cell::with_bound_schema` to enforce it cryptographically.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidCellId` if `id` is empty.
+    pub fn with_schema(id: CellId, schema_version: u32) -> Result<Self, HexvaultError> {
+        let mut cell = Self::try_new(id)?;
+        cell.schema_version = schema_version;
+        Ok(cell)
+    }
+
+    /// Like `Cell::with_schema`, but the version is also mixed into every
+    /// key the cell derives. Payloads sealed in this cell then fail with
+    /// `DecryptionFailure` under any other version, deliberately.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidCellId` if `id` is empty.
+    pub fn with_bound_schema(id: CellId, schema_version: u32) -> Result<Self, HexvaultError> {
+        let mut cell = Self::with_schema(id, schema_version)?;
+        cell.schema_bound = true;
+        Ok(cell)
+    }
+
+    /// Create a new, empty cell with a human-readable label, e.g. for a
+    /// cell whose ID is an opaque UUID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidCellId` if `id` is empty.
+    pub fn with_label(id: CellId, label: impl Into<String>) -> Result<Self, HexvaultError> {
+        let mut cell = Self::try_new(id)?;
+        cell.label = Some(label.into());
+        Ok(cell)
     }
 
     /// Return the cell's ID.
@@ -41,6 +350,33 @@ impl Cell {
         &self.id
     }
 
+    /// The cell's label, if it has one.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Set or clear the cell's label. Labels are metadata only and are not
+    /// mixed into key derivation, so relabelling a cell leaves its payloads
+    /// opening as before.
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    /// The schema version the cell was created with; 0 if none was set.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Returns true if the schema version is mixed into key derivation.
+    pub fn schema_bound(&self) -> bool {
+        self.schema_bound
+    }
+
+    /// The schema version to derive keys with, if the cell is bound to one.
+    pub(crate) fn derivation_schema(&self) -> Option<u32> {
+        self.schema_bound.then_some(self.schema_version)
+    }
+
     /// Seal a plaintext value into the cell.
     ///
     /// The value is encrypted up to the specified layer and stored under the given key.
@@ -52,17 +388,91 @@ impl Cell {
         layer: Layer,
         context: &LayerContext,
     ) -> Result<(), HexvaultError> {
-        let sealed = stack::seal(partition_key, &self.id, layer, context, text)?;
-        self.payloads.insert(
-            key.to_string(),
+        self.store_with_options(
+            partition_key,
+            key,
+            text,
+            layer,
+            context,
+            &SealOptions::default(),
+        )
+    }
+
+    /// Seal a plaintext value into the cell with explicit sealing options.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::ReservedKey` if `key` starts with
+    /// `RESERVED_KEY_PREFIX`.
+    pub fn store_with_options(
+        &mut self,
+        partition_key: &PartitionKey,
+        key: &str,
+        text: &[u8],
+        layer: Layer,
+        context: &LayerContext,
+        options: &SealOptions,
+    ) -> Result<(), HexvaultError> {
+        self.store_at(
+            partition_key,
+            key,
+            text,
+            layer,
+            context,
+            options,
+            Utc::now(),
+        )
+    }
+
+    /// `store_with_options`, recording `now` as the creation time.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn store_at(
+        &mut self,
+        partition_key: &PartitionKey,
+        key: &str,
+        text: &[u8],
+        layer: Layer,
+        context: &LayerContext,
+        options: &SealOptions,
+        now: DateTime<Utc>,
+    ) -> Result<(), HexvaultError> {
+        check_key(key)?;
+        let bound;
+        let options = if options.schema_version == self.derivation_schema() {
+            options
+        } else {
+            bound = SealOptions {
+                schema_version: self.derivation_schema(),
+                ..options.clone()
+            };
+            &bound
+        };
+        let content_id = if options.content_id {
+            Some(keys::content_id(partition_key, text)?)
+        } else {
+            None
+        };
+        let sealed =
+            stack::seal_with_options(partition_key, &self.id, layer, context, text, options)?;
+        self.insert_sealed(
+            key,
             Payload {
                 data: sealed,
                 sealed_at: layer,
+                created_at: now,
+                key_id: partition_key.key_id(),
+                expires_at: options.expires_at,
+                content_id,
             },
         );
         Ok(())
     }
 
+    /// Store a payload already sealed for this cell.
+    pub(crate) fn insert_sealed(&mut self, key: &str, payload: Payload) {
+        self.payloads.insert(key.to_string(), payload);
+    }
+
     /// Retrieve and peel a stored payload.
     ///
     /// Returns the original plaintext if the key exists and the correct context
@@ -73,24 +483,650 @@ impl Cell {
         key: &str,
         context: &LayerContext,
     ) -> Result<Vec<u8>, HexvaultError> {
-        let payload = self
-            .payloads
-            .get(key)
-            .ok_or_else(|| HexvaultError::CellNotFound(key.to_string()))?;
+        self.retrieve_with_options(partition_key, key, context, &PeelOptions::default())
+    }
 
-        stack::peel(
+    /// Retrieve and peel a stored payload with explicit peel options, e.g.
+    /// the application AAD it was sealed with. `options.expires_at` is
+    /// ignored; the payload's own expiry is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::Expired` if the payload's expiry has passed.
+    pub fn retrieve_with_options(
+        &self,
+        partition_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+        options: &PeelOptions,
+    ) -> Result<Vec<u8>, HexvaultError> {
+        self.retrieve_at(partition_key, key, context, options, Utc::now())
+    }
+
+    /// `retrieve_with_options`, checking expiry against `now`.
+    pub(crate) fn retrieve_at(
+        &self,
+        partition_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+        options: &PeelOptions,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<u8>, HexvaultError> {
+        let mut out = Vec::new();
+        self.peel_at(partition_key, key, context, options, now, &mut out)?;
+        Ok(out)
+    }
+
+    /// Retrieve and peel a stored payload into a caller-provided buffer.
+    ///
+    /// Behaves like `retrieve`, but peels in place in `out`. See
+    /// `stack::peel_into`.
+    pub fn retrieve_into(
+        &self,
+        partition_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+        out: &mut Vec<u8>,
+    ) -> Result<(), HexvaultError> {
+        let options = PeelOptions::default();
+        self.peel_at(partition_key, key, context, &options, Utc::now(), out)
+    }
+
+    /// Peel a stored payload into `out` with its own expiry, checked
+    /// against `now`. `out` is zeroised on failure.
+    pub(crate) fn peel_at(
+        &self,
+        partition_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+        options: &PeelOptions,
+        now: DateTime<Utc>,
+        out: &mut Vec<u8>,
+    ) -> Result<(), HexvaultError> {
+        let payload = self.payloads.get(key).ok_or_else(|| {
+            out.zeroize();
+            HexvaultError::KeyNotFound(key.to_string())
+        })?;
+
+        if payload.is_stream() {
+            // The plaintext is shorter than the stream, so reserving that
+            // much up front leaves no reallocated copies behind.
+            out.clear();
+            out.reserve(payload.data.len());
+            let options = PeelOptions {
+                schema_version: self.derivation_schema(),
+                ..options.clone()
+            };
+            let opened = stream::open_stream_with(
+                partition_key,
+                &self.id,
+                context,
+                payload.data.as_slice(),
+                &mut *out,
+                &options,
+            );
+            if opened.is_err() {
+                out.zeroize();
+            }
+            return opened;
+        }
+
+        stack::peel_into_with_options(
             partition_key,
             &self.id,
             payload.sealed_at,
             context,
             &payload.data,
+            &PeelOptions {
+                expires_at: payload.expires_at,
+                schema_version: self.derivation_schema(),
+                ..options.clone()
+            },
+            now,
+            out,
+        )?;
+        if let Some(id) = &payload.content_id {
+            if let Err(e) = keys::check_content_id(partition_key, out, id) {
+                out.zeroize();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Confirm that a stored payload still decrypts under the given context.
+    ///
+    /// The payload is peeled exactly as in `retrieve`, but in place in a
+    /// per-thread buffer that is reused across calls and zeroised before
+    /// returning, so no plaintext is returned or left behind. Use this for
+    /// integrity sweeps that must not expose data.
+    pub fn verify(
+        &self,
+        partition_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+    ) -> Result<(), HexvaultError> {
+        self.verify_at(
+            partition_key,
+            key,
+            context,
+            &PeelOptions::default(),
+            Utc::now(),
+        )
+    }
+
+    /// `verify` with peel options, checking expiry against `now`.
+    pub(crate) fn verify_at(
+        &self,
+        partition_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+        options: &PeelOptions,
+        now: DateTime<Utc>,
+    ) -> Result<(), HexvaultError> {
+        let verify = |buf: &mut Vec<u8>| {
+            let result = self.peel_at(partition_key, key, context, options, now, buf);
+            // Zeroises the whole capacity, then empties the buffer.
+            buf.zeroize();
+            result
+        };
+        // A layer guard that verifies from inside a peel gets its own buffer.
+        VERIFY_BUF.with(|shared| match shared.try_borrow_mut() {
+            Ok(mut buf) => verify(&mut buf),
+            Err(_) => verify(&mut Vec::new()),
+        })
+    }
+
+    /// Iterate over the keys of the stored payloads, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.payloads.keys().map(String::as_str)
+    }
+
+    /// Iterate over the keys and non-secret metadata of the stored payloads,
+    /// in no particular order. No layer context is needed.
+    pub fn infos(&self) -> impl Iterator<Item = (&str, PayloadInfo)> {
+        self.payloads
+            .iter()
+            .map(|(key, payload)| (key.as_str(), payload.info()))
+    }
+
+    /// Non-secret metadata for the payload stored under `key`, e.g. its
+    /// `layer_stack` when diagnosing a failed open. No layer context is
+    /// needed.
+    pub fn metadata(&self, key: &str) -> Option<PayloadInfo> {
+        self.payloads.get(key).map(Payload::info)
+    }
+
+    /// Total bytes the cell's stored ciphertext adds to the plaintext it
+    /// holds. See `Payload::overhead`.
+    pub fn overhead(&self) -> usize {
+        self.payloads.values().map(Payload::overhead).sum()
+    }
+
+    /// Returns true if a payload is stored under `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.payloads.contains_key(key)
+    }
+
+    /// Borrow a stored payload.
+    pub(crate) fn payload(&self, key: &str) -> Option<&Payload> {
+        self.payloads.get(key)
+    }
+
+    /// Re-seal a stored payload at a lower layer, keeping its suite, KDF
+    /// hash, and creation time. Returns the layer it was sealed at before.
+    ///
+    /// The payload is peeled with `context`, so `context` must satisfy its
+    /// current layer. The transient plaintext is zeroised before returning.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidLayer` if `new_layer` is not strictly
+    /// below the current layer, and `HexvaultError::KeyNotFound` if `key`
+    /// does not exist.
+    pub fn downgrade(
+        &mut self,
+        partition_key: &PartitionKey,
+        key: &str,
+        new_layer: Layer,
+        context: &LayerContext,
+    ) -> Result<Layer, HexvaultError> {
+        self.downgrade_with(
+            partition_key,
+            key,
+            new_layer,
+            context,
+            &PeelOptions::default(),
+        )
+    }
+
+    /// `downgrade`, peeling with `options`.
+    pub(crate) fn downgrade_with(
+        &mut self,
+        partition_key: &PartitionKey,
+        key: &str,
+        new_layer: Layer,
+        context: &LayerContext,
+        options: &PeelOptions,
+    ) -> Result<Layer, HexvaultError> {
+        let from = self.layer_of(key)?;
+        if new_layer >= from {
+            return Err(HexvaultError::InvalidLayer);
+        }
+        self.reseal_at(
+            partition_key,
+            partition_key,
+            key,
+            new_layer,
+            None,
+            context,
+            options,
+        )?;
+        Ok(from)
+    }
+
+    /// Re-seal a stored payload at its current layer with fresh nonces,
+    /// keeping its keys, suite, KDF hash, and creation time. Returns the
+    /// layer it is sealed at.
+    ///
+    /// A maintenance primitive for bounding how long any one nonce stays in
+    /// use; it is not a key rotation. `context` must satisfy the payload's
+    /// layer, and the transient plaintext is zeroised before returning.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::KeyNotFound` if `key` does not exist.
+    pub fn reseal(
+        &mut self,
+        partition_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+    ) -> Result<Layer, HexvaultError> {
+        self.migrate(
+            partition_key,
+            partition_key,
+            key,
+            None,
+            context,
+            &PeelOptions::default(),
+        )
+    }
+
+    /// Re-seal a stored payload under `to_key` — e.g. a partition key
+    /// derived from a new primary master key — at its current layer,
+    /// keeping its suite, KDF hash, and creation time. Returns the layer.
+    ///
+    /// `from_key` must be the key the payload was sealed under. The
+    /// transient plaintext is zeroised before returning.
+    pub fn rekey(
+        &mut self,
+        from_key: &PartitionKey,
+        to_key: &PartitionKey,
+        key: &str,
+        context: &LayerContext,
+    ) -> Result<Layer, HexvaultError> {
+        self.migrate(
+            from_key,
+            to_key,
+            key,
+            None,
+            context,
+            &PeelOptions::default(),
         )
     }
 
+    /// `rekey`, additionally moving the payload to `suite` if one is given,
+    /// and peeling with `options`. With the same key and no suite, this is
+    /// `reseal`.
+    pub(crate) fn migrate(
+        &mut self,
+        from_key: &PartitionKey,
+        to_key: &PartitionKey,
+        key: &str,
+        suite: Option<CipherSuite>,
+        context: &LayerContext,
+        options: &PeelOptions,
+    ) -> Result<Layer, HexvaultError> {
+        let layer = self.layer_of(key)?;
+        self.reseal_at(from_key, to_key, key, layer, suite, context, options)?;
+        Ok(layer)
+    }
+
+    fn layer_of(&self, key: &str) -> Result<Layer, HexvaultError> {
+        self.payloads
+            .get(key)
+            .map(|payload| payload.sealed_at)
+            .ok_or_else(|| HexvaultError::KeyNotFound(key.to_string()))
+    }
+
+    /// Peel a payload with `from_key` and seal it again with `to_key` at
+    /// `layer`, under the same KDF hash, checksum setting, expiry, and
+    /// creation time, and under `suite` or else the same suite. The peel
+    /// uses `peel`. A stream payload is sealed as a stream again, under the
+    /// default suite.
+    #[allow(clippy::too_many_arguments)]
+    fn reseal_at(
+        &mut self,
+        from_key: &PartitionKey,
+        to_key: &PartitionKey,
+        key: &str,
+        layer: Layer,
+        suite: Option<CipherSuite>,
+        context: &LayerContext,
+        peel: &PeelOptions,
+    ) -> Result<(), HexvaultError> {
+        let payload = self
+            .payloads
+            .get(key)
+            .ok_or_else(|| HexvaultError::KeyNotFound(key.to_string()))?;
+        let created_at = payload.created_at;
+        let suite = suite.unwrap_or_else(|| payload.cipher_suite().unwrap_or_default());
+        let options = SealOptions {
+            suite,
+            kdf: payload.kdf_hash().unwrap_or_default(),
+            checksum: payload.has_checksum(),
+            tag_length: if suite.allows_truncated_tag() {
+                payload.tag_length()
+            } else {
+                TagLength::Full
+            },
+            expires_at: payload.expires_at,
+            schema_version: self.derivation_schema(),
+            content_id: payload.content_id.is_some(),
+            ..SealOptions::default()
+        };
+
+        let is_stream = payload.is_stream();
+        let mut plaintext = self.retrieve_at(from_key, key, context, peel, Utc::now())?;
+        let sealed = if is_stream {
+            let mut data = Vec::new();
+            stream::seal_stream_with(
+                to_key,
+                &self.id,
+                layer,
+                context,
+                plaintext.as_slice(),
+                &mut data,
+                options.schema_version,
+            )
+            .map(|()| data)
+        } else {
+            stack::seal_with_options(to_key, &self.id, layer, context, &plaintext, &options)
+        };
+        let content_id = if options.content_id && sealed.is_ok() {
+            keys::content_id(to_key, &plaintext).map(Some)
+        } else {
+            Ok(None)
+        };
+        plaintext.zeroize();
+
+        self.payloads.insert(
+            key.to_string(),
+            Payload {
+                data: sealed?,
+                sealed_at: layer,
+                created_at,
+                key_id: to_key.key_id(),
+                expires_at: options.expires_at,
+                content_id: content_id?,
+            },
+        );
+        Ok(())
+    }
+
+    /// Swap a payload's ciphertext for `new_data`, sealed up to
+    /// `new_layer`, in one step, e.g. after re-sealing it outside the cell.
+    /// The old ciphertext is zeroised.
+    ///
+    /// The payload keeps its creation time, master key ID, expiry, and
+    /// content ID, so `new_data` must be sealed for this cell under the same
+    /// master key and expiry to open. Only its framing is checked here.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::KeyNotFound` if `key` does not exist, and
+    /// `HexvaultError::MalformedCiphertext` if `new_data` cannot hold a
+    /// payload sealed up to `new_layer`. The payload is unchanged on error.
+    pub fn replace(
+        &mut self,
+        key: &str,
+        new_data: Vec<u8>,
+        new_layer: Layer,
+    ) -> Result<(), HexvaultError> {
+        let payload = self
+            .payloads
+            .get_mut(key)
+            .ok_or_else(|| HexvaultError::KeyNotFound(key.to_string()))?;
+        stack::check_framing(new_layer, &new_data)?;
+        let mut old = std::mem::replace(&mut payload.data, new_data);
+        payload.sealed_at = new_layer;
+        old.zeroize();
+        Ok(())
+    }
+
     /// Remove a payload from the cell.
     pub fn remove(&mut self, key: &str) {
         self.payloads.remove(key);
     }
+
+    /// Shrink the cell's payload map to fit what it holds, releasing the
+    /// capacity left behind by removals. Only the map is reallocated; its
+    /// entries hold ciphertext and metadata, never plaintext.
+    pub fn compact(&mut self) {
+        self.payloads.shrink_to_fit();
+    }
+
+    /// Write the cell in the canonical snapshot format.
+    ///
+    /// Payloads stay sealed — only ciphertext and non-secret metadata are
+    /// written. Payloads are ordered by key, so equal cells produce equal
+    /// bytes. See the `snapshot` module for the frame layout.
+    pub fn write_to(&self, mut writer: impl Write) -> Result<(), HexvaultError> {
+        let mut keys: Vec<&String> = self.payloads.keys().collect();
+        keys.sort();
+
+        let mut body = Vec::new();
+        snapshot::put_bytes(&mut body, self.id.as_bytes());
+        body.extend_from_slice(&self.schema_version.to_be_bytes());
+        body.push(u8::from(self.schema_bound));
+        match &self.label {
+            Some(label) => {
+                body.push(1);
+                snapshot::put_bytes(&mut body, label.as_bytes());
+            }
+            None => body.push(0),
+        }
+        body.extend_from_slice(&(keys.len() as u32).to_be_bytes());
+        for key in keys {
+            let payload = &self.payloads[key];
+            snapshot::put_bytes(&mut body, key.as_bytes());
+            body.push(payload.sealed_at.index() as u8);
+            body.extend_from_slice(&payload.key_id.to_be_bytes());
+            match payload.expires_at {
+                Some(expires_at) => {
+                    body.push(1);
+                    body.extend_from_slice(&expires_at.timestamp().to_be_bytes());
+                    body.extend_from_slice(&expires_at.timestamp_subsec_nanos().to_be_bytes());
+                }
+                None => body.push(0),
+            }
+            body.extend_from_slice(&payload.created_at.timestamp().to_be_bytes());
+            body.extend_from_slice(&payload.created_at.timestamp_subsec_nanos().to_be_bytes());
+            match &payload.content_id {
+                Some(id) => {
+                    body.push(1);
+                    body.extend_from_slice(id);
+                }
+                None => body.push(0),
+            }
+            snapshot::put_bytes(&mut body, &payload.data);
+        }
+
+        writer.write_all(&snapshot::frame(&body))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a cell written by `write_to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::CorruptSnapshot` if the data is truncated,
+    /// fails its CRC, or is otherwise malformed, and `HexvaultError::Io` if
+    /// the reader fails.
+    pub fn read_from(mut reader: impl Read) -> Result<Self, HexvaultError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let body = snapshot::unframe(&bytes)?;
+        let mut body = Reader::new(body);
+
+        let id = body.string()?;
+        if id.is_empty() {
+            return Err(HexvaultError::CorruptSnapshot);
+        }
+        let schema_version = body.u32()?;
+        let schema_bound = match body.u8()? {
+            0 => false,
+            1 => true,
+            _ => return Err(HexvaultError::CorruptSnapshot),
+        };
+        let label = match body.u8()? {
+            0 => None,
+            1 => Some(body.string()?),
+            _ => return Err(HexvaultError::CorruptSnapshot),
+        };
+        let count = body.u32()?;
+        let mut payloads = HashMap::new();
+        for _ in 0..count {
+            let key = body.string()?;
+            let sealed_at =
+                Layer::from_index(usize::from(body.u8()?)).ok_or(HexvaultError::CorruptSnapshot)?;
+            let key_id = body.u32()?;
+            let expires_at = match body.u8()? {
+                0 => None,
+                1 => Some(
+                    DateTime::from_timestamp(body.i64()?, body.u32()?)
+                        .ok_or(HexvaultError::CorruptSnapshot)?,
+                ),
+                _ => return Err(HexvaultError::CorruptSnapshot),
+            };
+            let created_at = DateTime::from_timestamp(body.i64()?, body.u32()?)
+                .ok_or(HexvaultError::CorruptSnapshot)?;
+            let content_id = match body.u8()? {
+                0 => None,
+                1 => Some(body.array()?),
+                _ => return Err(HexvaultError::CorruptSnapshot),
+            };
+            let data = body.bytes()?.to_vec();
+            let payload = Payload {
+                data,
+                sealed_at,
+                created_at,
+                key_id,
+                expires_at,
+                content_id,
+            };
+            if payloads.insert(key, payload).is_some() {
+                return Err(HexvaultError::CorruptSnapshot);
+            }
+        }
+        body.finish()?;
+
+        Ok(Self {
+            id,
+            payloads,
+            schema_version,
+            schema_bound,
+            label,
+        })
+    }
+
+    /// Copy the cell into a `SealedBundle`. Payloads stay sealed.
+    pub fn to_bundle(&self) -> SealedBundle {
+        let mut keys: Vec<&String> = self.payloads.keys().collect();
+        keys.sort();
+
+        let payloads = keys
+            .into_iter()
+            .map(|key| {
+                let payload = &self.payloads[key];
+                BundledPayload {
+                    key: key.clone(),
+                    layer: payload.sealed_at,
+                    key_id: payload.key_id,
+                    created_at: payload.created_at,
+                    expires_at: payload.expires_at,
+                    content_id: payload.content_id,
+                    data: payload.data.clone(),
+                }
+            })
+            .collect();
+
+        SealedBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            cell_id: self.id.clone(),
+            schema_version: self.schema_version,
+            schema_bound: self.schema_bound,
+            label: self.label.clone(),
+            payloads,
+        }
+    }
+
+    /// Rebuild a cell from a `SealedBundle`.
+    ///
+    /// Only the bundle's structure is checked; payloads are not decrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::CorruptSnapshot` if the format or version is
+    /// not recognised, or a payload key appears twice, and
+    /// `HexvaultError::InvalidCellId` if the cell ID is empty.
+    pub fn from_bundle(bundle: SealedBundle) -> Result<Self, HexvaultError> {
+        if bundle.format != BUNDLE_FORMAT || bundle.version != BUNDLE_VERSION {
+            return Err(HexvaultError::CorruptSnapshot);
+        }
+        let mut cell = Self::try_new(bundle.cell_id)?;
+        cell.schema_version = bundle.schema_version;
+        cell.schema_bound = bundle.schema_bound;
+        cell.label = bundle.label;
+        for bundled in bundle.payloads {
+            let payload = Payload {
+                data: bundled.data,
+                sealed_at: bundled.layer,
+                created_at: bundled.created_at,
+                key_id: bundled.key_id,
+                expires_at: bundled.expires_at,
+                content_id: bundled.content_id,
+            };
+            if cell.payloads.insert(bundled.key, payload).is_some() {
+                return Err(HexvaultError::CorruptSnapshot);
+            }
+        }
+        Ok(cell)
+    }
+
+    /// Keep only the payloads for which `keep` returns `true`.
+    ///
+    /// `keep` sees only non-secret metadata, so no layer context is needed.
+    /// Evicted ciphertext is zeroised before it is dropped. Returns the keys
+    /// and metadata of the removed payloads.
+    pub fn retain(
+        &mut self,
+        mut keep: impl FnMut(&str, &PayloadInfo) -> bool,
+    ) -> Vec<(String, PayloadInfo)> {
+        let mut removed = Vec::new();
+        self.payloads.retain(|key, payload| {
+            let info = payload.info();
+            if keep(key, &info) {
+                return true;
+            }
+            payload.data.zeroize();
+            removed.push((key.clone(), info));
+            false
+        });
+        removed
+    }
 }
 
 #[cfg(test)]
@@ -122,6 +1158,8 @@ mod tests {
             b"hello b"
         );
 
+        assert!(cell_a.verify(&partition, "secret", &context).is_ok());
+
         // Simulate swap/wrong ID by calling stack::peel directly with wrong ID
         let sealed_a = cell_a.payloads.get("secret").unwrap();
         assert!(stack::peel(
@@ -133,4 +1171,418 @@ mod tests {
         )
         .is_err());
     }
+
+    #[test]
+    fn test_try_new_rejects_empty_id() {
+        assert!(matches!(
+            Cell::try_new(String::new()),
+            Err(HexvaultError::InvalidCellId)
+        ));
+        assert_eq!(Cell::try_new("a:b".to_string()).unwrap().id(), "a:b");
+    }
+
+    #[test]
+    fn test_retain_evicts_by_metadata() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let mut cell = Cell::new("cell".into());
+
+        cell.store(&partition, "rest", b"a", Layer::AtRest, &ctx)
+            .unwrap();
+        cell.store(&partition, "gated", b"b", Layer::AccessGated, &ctx)
+            .unwrap();
+
+        let removed = cell.retain(|_, info| info.layer != Layer::AtRest);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].0, "rest");
+        assert!(cell.payload("rest").is_none());
+        assert!(cell.payload("gated").is_some());
+    }
+
+    #[test]
+    fn test_downgrade_only_lowers_layer() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(
+            Some("policy".try_into().unwrap()),
+            Some("session".try_into().unwrap()),
+        )
+        .unwrap();
+        let mut cell = Cell::new("cell".into());
+        cell.store(&partition, "k", b"archive me", Layer::SessionBound, &ctx)
+            .unwrap();
+
+        assert!(matches!(
+            cell.downgrade(&partition, "k", Layer::SessionBound, &ctx),
+            Err(HexvaultError::InvalidLayer)
+        ));
+        assert_eq!(
+            cell.downgrade(&partition, "k", Layer::AtRest, &ctx)
+                .unwrap(),
+            Layer::SessionBound
+        );
+        assert_eq!(cell.payload("k").unwrap().sealed_at, Layer::AtRest);
+
+        // Now readable with no context at all.
+        let plain = cell
+            .retrieve(&partition, "k", &LayerContext::empty())
+            .unwrap();
+        assert_eq!(plain, b"archive me");
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let mut cell = Cell::new("cell".into());
+        cell.store(&partition, "a", b"one", Layer::AtRest, &ctx)
+            .unwrap();
+        cell.store(&partition, "b", b"two", Layer::AccessGated, &ctx)
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        cell.write_to(&mut bytes).unwrap();
+        let restored = Cell::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(restored.id(), "cell");
+        assert_eq!(restored.retrieve(&partition, "b", &ctx).unwrap(), b"two");
+        assert_eq!(
+            restored.payload("a").unwrap().created_at,
+            cell.payload("a").unwrap().created_at
+        );
+        assert_eq!(restored.payload("a").unwrap().key_id, 0);
+
+        // Truncation is caught before decryption.
+        assert!(matches!(
+            Cell::read_from(&bytes[..bytes.len() - 10]),
+            Err(HexvaultError::CorruptSnapshot)
+        ));
+    }
+
+    #[test]
+    fn test_expiry_is_authenticated_and_persisted() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::default();
+        let mut cell = Cell::new("cell".into());
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let options = SealOptions {
+            expires_at: Some(expires_at),
+            ..SealOptions::default()
+        };
+        cell.store_with_options(&partition, "k", b"ttl", Layer::AtRest, &ctx, &options)
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        cell.write_to(&mut bytes).unwrap();
+        let mut restored = Cell::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(restored.payload("k").unwrap().expires_at, Some(expires_at));
+        assert_eq!(restored.retrieve(&partition, "k", &ctx).unwrap(), b"ttl");
+
+        // Extending or stripping the stored expiry breaks authentication.
+        let payload = restored.payloads.get_mut("k").unwrap();
+        payload.expires_at = Some(expires_at + chrono::Duration::days(1));
+        assert!(matches!(
+            restored.retrieve(&partition, "k", &ctx),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+        restored.payloads.get_mut("k").unwrap().expires_at = None;
+        assert!(matches!(
+            restored.retrieve(&partition, "k", &ctx),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+    }
+
+    #[test]
+    fn test_bundle_roundtrip_and_validation() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let mut cell = Cell::new("cell".into());
+        cell.store(&partition, "a", b"one", Layer::AtRest, &ctx)
+            .unwrap();
+        cell.store(&partition, "b", b"two", Layer::AccessGated, &ctx)
+            .unwrap();
+
+        let json = serde_json::to_string(&cell.to_bundle()).unwrap();
+        let restored = Cell::from_bundle(serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(restored.id(), "cell");
+        assert_eq!(restored.retrieve(&partition, "a", &ctx).unwrap(), b"one");
+        assert_eq!(restored.retrieve(&partition, "b", &ctx).unwrap(), b"two");
+        assert_eq!(restored.to_bundle(), cell.to_bundle());
+
+        let mut bundle = cell.to_bundle();
+        bundle.version += 1;
+        assert!(matches!(
+            Cell::from_bundle(bundle),
+            Err(HexvaultError::CorruptSnapshot)
+        ));
+
+        let mut bundle = cell.to_bundle();
+        let duplicate = bundle.payloads[0].clone();
+        bundle.payloads.push(duplicate);
+        assert!(matches!(
+            Cell::from_bundle(bundle),
+            Err(HexvaultError::CorruptSnapshot)
+        ));
+    }
+
+    #[test]
+    fn test_replace_swaps_ciphertext() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+
+        let mut cell = Cell::new("cell".into());
+        cell.store(&partition, "k", b"v1", Layer::AtRest, &ctx)
+            .unwrap();
+        let created_at = cell.payload("k").unwrap().created_at;
+
+        let resealed = stack::seal(&partition, "cell", Layer::AccessGated, &ctx, b"v1").unwrap();
+        cell.replace("k", resealed, Layer::AccessGated).unwrap();
+        let payload = cell.payload("k").unwrap();
+        assert_eq!(payload.sealed_at, Layer::AccessGated);
+        assert_eq!(payload.created_at, created_at);
+        assert_eq!(cell.retrieve(&partition, "k", &ctx).unwrap(), b"v1");
+
+        assert!(matches!(
+            cell.replace("missing", Vec::new(), Layer::AtRest),
+            Err(HexvaultError::KeyNotFound(_))
+        ));
+        // Too short for two layers: rejected, and the payload still opens.
+        let short = stack::seal(&partition, "cell", Layer::AtRest, &ctx, b"").unwrap();
+        assert!(matches!(
+            cell.replace("k", short, Layer::AccessGated),
+            Err(HexvaultError::MalformedCiphertext)
+        ));
+        assert_eq!(cell.retrieve(&partition, "k", &ctx).unwrap(), b"v1");
+    }
+
+    #[test]
+    fn test_label_is_metadata_only() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::default();
+
+        let mut cell = Cell::with_label("8f14e45f".into(), "billing").unwrap();
+        assert_eq!(cell.label(), Some("billing"));
+        cell.store(&partition, "k", b"data", Layer::AtRest, &ctx)
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        cell.write_to(&mut bytes).unwrap();
+        assert_eq!(
+            Cell::read_from(&bytes[..]).unwrap().label(),
+            Some("billing")
+        );
+        let bundled = Cell::from_bundle(cell.to_bundle()).unwrap();
+        assert_eq!(bundled.label(), Some("billing"));
+
+        cell.set_label(Some("invoices".into()));
+        assert_eq!(cell.retrieve(&partition, "k", &ctx).unwrap(), b"data");
+        cell.set_label(None);
+        let mut bytes = Vec::new();
+        cell.write_to(&mut bytes).unwrap();
+        assert_eq!(Cell::read_from(&bytes[..]).unwrap().label(), None);
+    }
+
+    #[test]
+    fn test_bound_schema_separates_versions() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::default();
+
+        let mut v2 = Cell::with_bound_schema("cell".into(), 2).unwrap();
+        assert_eq!((v2.schema_version(), v2.schema_bound()), (2, true));
+        v2.store(&partition, "k", b"v2 data", Layer::AtRest, &ctx)
+            .unwrap();
+        assert_eq!(v2.retrieve(&partition, "k", &ctx).unwrap(), b"v2 data");
+
+        // The same ciphertext in a cell of another version, or an unbound
+        // one, fails to open.
+        let payload = v2.payload("k").unwrap();
+        for mut other in [
+            Cell::with_bound_schema("cell".into(), 3).unwrap(),
+            Cell::with_schema("cell".into(), 2).unwrap(),
+        ] {
+            other.insert_sealed(
+                "k",
+                Payload {
+                    data: payload.data.clone(),
+                    ..*payload
+                },
+            );
+            assert!(matches!(
+                other.retrieve(&partition, "k", &ctx),
+                Err(HexvaultError::DecryptionFailure)
+            ));
+        }
+
+        // The schema survives snapshots and bundles.
+        let mut bytes = Vec::new();
+        v2.write_to(&mut bytes).unwrap();
+        let restored = Cell::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(restored.derivation_schema(), Some(2));
+        assert_eq!(
+            restored.retrieve(&partition, "k", &ctx).unwrap(),
+            b"v2 data"
+        );
+        let restored = Cell::from_bundle(v2.to_bundle()).unwrap();
+        assert_eq!(
+            restored.retrieve(&partition, "k", &ctx).unwrap(),
+            b"v2 data"
+        );
+    }
+
+    #[test]
+    fn test_overhead_reports_stored_bytes() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let mut cell = Cell::new("cell".into());
+        let checked = SealOptions {
+            checksum: true,
+            ..SealOptions::default()
+        };
+        cell.store(&partition, "a", b"one", Layer::AtRest, &ctx)
+            .unwrap();
+        cell.store_with_options(&partition, "b", b"four", Layer::AccessGated, &ctx, &checked)
+            .unwrap();
+
+        let a = cell.payload("a").unwrap();
+        let b = cell.payload("b").unwrap();
+        assert_eq!(a.data.len(), 3 + a.overhead());
+        assert_eq!(b.data.len(), 4 + b.overhead());
+        assert_eq!(cell.overhead(), a.overhead() + b.overhead());
+    }
+
+    #[test]
+    fn test_debug_omits_ciphertext() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let mut cell = Cell::new("cell".to_string());
+        cell.store(
+            &partition,
+            "k",
+            b"secret",
+            Layer::AtRest,
+            &LayerContext::default(),
+        )
+        .unwrap();
+
+        let len = cell.payload("k").unwrap().data.len();
+        assert_eq!(
+            format!("{:?}", cell),
+            format!(
+                "Cell {{ id: \"cell\", count: 1, payloads: {{\"k\": Payload {{ sealed_at: AtRest, len: {} }}}} }}",
+                len
+            )
+        );
+    }
+
+    #[test]
+    fn test_compact_releases_capacity() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::default();
+        let mut cell = Cell::new("cell".to_string());
+        for i in 0..256 {
+            cell.store(&partition, &format!("k{i}"), b"churn", Layer::AtRest, &ctx)
+                .unwrap();
+        }
+        for i in 1..256 {
+            cell.remove(&format!("k{i}"));
+        }
+
+        let before = cell.payloads.capacity();
+        cell.compact();
+        assert!(cell.payloads.capacity() < before);
+        assert_eq!(cell.retrieve(&partition, "k0", &ctx).unwrap(), b"churn");
+    }
+
+    #[test]
+    fn test_metadata_reports_layer_stack() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let mut cell = Cell::new("cell".to_string());
+        cell.store(&partition, "k", b"stacked", Layer::AccessGated, &ctx)
+            .unwrap();
+
+        let info = cell.metadata("k").unwrap();
+        assert_eq!(info.layer_stack(), [Layer::AtRest, Layer::AccessGated]);
+        assert_eq!(cell.payload("k").unwrap().layer_stack(), info.layer_stack());
+        assert!(cell.metadata("missing").is_none());
+    }
+
+    #[test]
+    fn test_header_reads_without_key() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let mut cell = Cell::new("cell".to_string());
+        let options = SealOptions {
+            suite: CipherSuite::ChaCha20Poly1305,
+            ..SealOptions::default()
+        };
+        cell.store_with_options(
+            &partition,
+            "k",
+            b"peek",
+            Layer::AtRest,
+            &LayerContext::default(),
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            cell.payload("k").unwrap().header(),
+            PayloadHeader {
+                format_version: Some(crypto::FORMAT_VERSION),
+                cipher_suite: Some(CipherSuite::ChaCha20Poly1305),
+                sealed_at: Layer::AtRest,
+            }
+        );
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        use crate::keys::MasterKey;
+        let master = MasterKey::from_bytes([1u8; 32]);
+        let partition = crate::keys::derive_partition_key(&master, "p1").unwrap();
+        let mut cell = Cell::new("cell".to_string());
+        let context = LayerContext::default();
+
+        cell.store(&partition, "k", b"intact", Layer::AtRest, &context)
+            .unwrap();
+        assert!(cell.verify(&partition, "k", &context).is_ok());
+        // The plaintext is gone, but the buffer is kept for the next call.
+        let capacity = VERIFY_BUF.with_borrow(|buf| {
+            assert!(buf.is_empty());
+            buf.capacity()
+        });
+        assert!(capacity > 0);
+        assert!(cell.verify(&partition, "k", &context).is_ok());
+        assert_eq!(VERIFY_BUF.with_borrow(Vec::capacity), capacity);
+
+        // Flip the final byte of the GCM tag.
+        let payload = cell.payloads.get_mut("k").unwrap();
+        let last = payload.data.len() - 1;
+        payload.data[last] ^= 0x01;
+        assert!(cell.verify(&partition, "k", &context).is_err());
+        assert!(cell.verify(&partition, "missing", &context).is_err());
+    }
 }
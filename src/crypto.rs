@@ -5,117 +5,1194 @@
 //! decryption exclusively through the functions exposed here.
 //!
 //! Primitive choices:
-//! - **Cipher**: AES-256-GCM (authenticated encryption)
+//! - **Cipher**: selected per payload via `CipherSuite` — AES-256-GCM by
+//!   default, ChaCha20-Poly1305 for hardware without AES acceleration, or a
+//!   custom `CipherImpl` registered with `register_global_cipher`
 //! - **Nonce**: 96-bit (12 bytes), generated fresh per operation via `SystemRandom`
-//! - **Key size**: 256 bits (32 bytes)
+//! - **Key size**: per suite (`CipherSuite::key_len`) — 256 bits for
+//!   AES-256-GCM and ChaCha20-Poly1305, 128 bits for AES-128-GCM. Master and
+//!   partition keys are always 256 bits
 //! - **AAD**: Additional authenticated data is bound to every seal/open call,
 //!   preventing cross-cell ciphertext replay.
+//!
+//! Every ciphertext starts with a short header naming the format version and
+//! cipher suite, so decryption never has to guess the algorithm. The header
+//! is authenticated as a prefix of the AAD, so editing it to coerce a
+//! weaker algorithm fails decryption.
+//!
+//! `encrypt_framed` splits large payloads into separately sealed frames.
+//! Streams (see `stream`) seal every layer in the same framed format.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
 
-use ring::aead::{self, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::aead::{
+    self, LessSafeKey, Nonce, UnboundKey, AES_128_GCM, AES_256_GCM, CHACHA20_POLY1305,
+};
 use ring::rand::{SecureRandom, SystemRandom};
+use ring::{digest, hmac};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
 
 use crate::error::HexvaultError;
 
-/// The AEAD algorithm used throughout hexvault.
-const ALGORITHM: &aead::Algorithm = &AES_256_GCM;
-
 /// Size of the nonce in bytes (96 bits).
 pub const NONCE_LEN: usize = 12;
 
-/// Size of a master or derived key in bytes (256 bits).
+/// Size of a master or partition key in bytes (256 bits). Also the largest
+/// derived key any suite uses; see `CipherSuite::key_len`.
 pub const KEY_LEN: usize = 32;
 
+/// The ciphertext format version written into every header.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Size of the ciphertext header in bytes: format version + suite ID.
+pub const HEADER_LEN: usize = 2;
+
+/// Size of the authentication tag appended by every suite, built-in or
+/// custom.
+pub const TAG_LEN: usize = 16;
+
+/// Size of the header and nonce that precede the sealed bytes.
+pub(crate) const PREFIX_LEN: usize = HEADER_LEN + NONCE_LEN;
+
+/// The shortest well-formed ciphertext: header, nonce, and tag around an
+/// empty plaintext.
+pub const MIN_CIPHERTEXT_LEN: usize = HEADER_LEN + NONCE_LEN + TAG_LEN;
+
+/// Size of the plaintext checksum (SHA-256) sealed by `SealOptions::checksum`.
+pub const CHECKSUM_LEN: usize = 32;
+
+/// Set in the suite byte of every layer sealed with a plaintext checksum.
+const CHECKSUM_FLAG: u8 = 0x80;
+
+/// Set in the suite byte of every layer sealed with a truncated tag; see
+/// `TagLength::Truncated96`.
+const TRUNCATED_TAG_FLAG: u8 = 0x40;
+
+// ---------------------------------------------------------------------------
+// Cipher suites
+// ---------------------------------------------------------------------------
+
+/// The authenticated cipher used to seal a payload.
+///
+/// The suite is recorded in every ciphertext header, so payloads sealed
+/// under different suites can live side by side and be opened without the
+/// caller naming the suite again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum CipherSuite {
+    /// AES-256-GCM. The default, and the right choice wherever AES-NI (or an
+    /// equivalent) is available.
+    #[default]
+    Aes256Gcm,
+    /// ChaCha20-Poly1305. Constant-time in software; preferred on hardware
+    /// without AES acceleration.
+    ChaCha20Poly1305,
+    /// AES-128-GCM with 16-byte derived keys, for environments whose policy
+    /// mandates 128-bit keys. The master key is still 256 bits.
+    Aes128Gcm,
+    /// A cipher registered with `register_global_cipher` under this ID
+    /// (4-15). Sealing or opening under an unregistered ID, or one outside
+    /// that range, fails.
+    Custom(u8),
+}
+
+impl CipherSuite {
+    /// The identifier stored in the ciphertext header.
+    pub(crate) fn id(self) -> u8 {
+        match self {
+            Self::Aes256Gcm => 1,
+            Self::ChaCha20Poly1305 => 2,
+            Self::Aes128Gcm => 3,
+            Self::Custom(id) => id,
+        }
+    }
+
+    /// Look up a suite by its header identifier. Custom IDs are only
+    /// recognised while a cipher is registered for them.
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Aes256Gcm),
+            2 => Some(Self::ChaCha20Poly1305),
+            3 => Some(Self::Aes128Gcm),
+            _ => {
+                let suite = Self::Custom(id);
+                suite.cipher().ok().map(|_| suite)
+            }
+        }
+    }
+
+    /// The label mixed into key derivation for this suite, if any: the
+    /// name of its `CipherImpl`.
+    ///
+    /// The default suite has no label so that keys derived before cipher
+    /// agility existed stay valid. Every other suite gets its own label, so
+    /// the same cell + layer + context never yields the same key under two
+    /// different algorithms.
+    pub(crate) fn derivation_label(self) -> Result<Option<&'static str>, HexvaultError> {
+        match self {
+            Self::Aes256Gcm => Ok(None),
+            _ => self.cipher().map(|cipher| Some(cipher.name())),
+        }
+    }
+
+    /// Length in bytes of the keys this suite derives, or 0 for a custom
+    /// suite with no registered cipher.
+    pub fn key_len(self) -> usize {
+        self.cipher().map_or(0, |cipher| cipher.key_len())
+    }
+
+    /// Bytes one layer sealed under this suite with `tag` adds to its
+    /// input: the header, the nonce, and the authentication tag. Suites
+    /// that do not allow truncated tags always carry the full tag.
+    pub fn layer_overhead(self, tag: TagLength) -> usize {
+        let tag_len = if self.allows_truncated_tag() {
+            tag.bytes()
+        } else {
+            TAG_LEN
+        };
+        HEADER_LEN + NONCE_LEN + tag_len
+    }
+
+    /// Whether layers sealed under this suite may use
+    /// `TagLength::Truncated96`. Only the GCM suites may, as GCM defines
+    /// truncated tags and Poly1305 does not.
+    pub fn allows_truncated_tag(self) -> bool {
+        matches!(self, Self::Aes256Gcm | Self::Aes128Gcm)
+    }
+
+    /// The implementation registered for this suite.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidCipherSuite` if no cipher is
+    /// registered under the suite's ID, or if a custom suite names an ID
+    /// outside `MIN_CUSTOM_SUITE_ID..=MAX_CUSTOM_SUITE_ID`: the built-in
+    /// suites' IDs must not be reached through `Custom`, which would seal
+    /// under one suite's key label and read back as another.
+    pub(crate) fn cipher(self) -> Result<Arc<dyn CipherImpl>, HexvaultError> {
+        let id = self.id();
+        if matches!(self, Self::Custom(_))
+            && !(MIN_CUSTOM_SUITE_ID..=MAX_CUSTOM_SUITE_ID).contains(&id)
+        {
+            return Err(HexvaultError::InvalidCipherSuite(id));
+        }
+        registry()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&id)
+            .cloned()
+            .ok_or(HexvaultError::InvalidCipherSuite(id))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Cipher registry
+// ---------------------------------------------------------------------------
+
+/// The smallest ID a custom cipher may be registered under. IDs below it
+/// belong to the built-in suites.
+pub const MIN_CUSTOM_SUITE_ID: u8 = 4;
+
+/// The largest ID a custom cipher may be registered under: the suite ID is
+/// the low nibble of the header suite byte.
+pub const MAX_CUSTOM_SUITE_ID: u8 = 0x0f;
+
+/// An AEAD the vault can seal layers with.
+///
+/// The built-in suites are implemented on top of this trait too; a custom
+/// implementation is registered with `register_global_cipher` and selected
+/// with `CipherSuite::Custom`. The vault supplies keys derived to
+/// `key_len` bytes and fresh `nonce_len`-byte nonces, and binds the
+/// ciphertext header into `aad`.
+pub trait CipherImpl: Send + Sync {
+    /// A name unique among registered ciphers. It is mixed into key
+    /// derivation, so changing it makes existing ciphertext unreadable.
+    fn name(&self) -> &'static str;
+
+    /// Length in bytes of the keys this cipher takes: 16 to `KEY_LEN`.
+    fn key_len(&self) -> usize;
+
+    /// Length in bytes of the nonces this cipher takes. Only `NONCE_LEN`
+    /// is supported.
+    fn nonce_len(&self) -> usize {
+        NONCE_LEN
+    }
+
+    /// Encrypt `buf` in place and append a `TAG_LEN`-byte authentication
+    /// tag over it and `aad`.
+    fn encrypt(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> Result<(), HexvaultError>;
+
+    /// Authenticate the ciphertext and tag in `buf` against `aad`, then
+    /// decrypt it in place. Returns the plaintext length, which must be
+    /// `buf.len() - TAG_LEN`; on failure `buf` must hold no plaintext.
+    fn decrypt(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, HexvaultError>;
+}
+
+/// A built-in suite, backed by `ring`.
+struct RingCipher {
+    name: &'static str,
+    algorithm: &'static aead::Algorithm,
+}
+
+impl RingCipher {
+    fn key(&self, key: &[u8]) -> Result<LessSafeKey, HexvaultError> {
+        UnboundKey::new(self.algorithm, key)
+            .map(LessSafeKey::new)
+            .map_err(|_| HexvaultError::InvalidKey)
+    }
+}
+
+impl CipherImpl for RingCipher {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn key_len(&self) -> usize {
+        self.algorithm.key_len()
+    }
+
+    fn encrypt(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> Result<(), HexvaultError> {
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| HexvaultError::EncryptionFailure)?;
+        self.key(key)?
+            .seal_in_place_append_tag(nonce, aead::Aad::from(aad), buf)
+            .map_err(|_| HexvaultError::EncryptionFailure)
+    }
+
+    fn decrypt(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, HexvaultError> {
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| HexvaultError::MalformedCiphertext)?;
+        self.key(key)?
+            .open_in_place(nonce, aead::Aad::from(aad), buf)
+            .map(|plaintext| plaintext.len())
+            .map_err(|_| HexvaultError::DecryptionFailure)
+    }
+}
+
+/// The ciphers shared by every vault in the process, keyed by suite ID.
+/// Seeded with the built-in suites on first use.
+fn registry() -> &'static RwLock<HashMap<u8, Arc<dyn CipherImpl>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<u8, Arc<dyn CipherImpl>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let builtins: [(CipherSuite, &'static str, &'static aead::Algorithm); 3] = [
+            (CipherSuite::Aes256Gcm, "aes-256-gcm", &AES_256_GCM),
+            (
+                CipherSuite::ChaCha20Poly1305,
+                "chacha20-poly1305",
+                &CHACHA20_POLY1305,
+            ),
+            (CipherSuite::Aes128Gcm, "aes-128-gcm", &AES_128_GCM),
+        ];
+        let ciphers = builtins
+            .into_iter()
+            .map(|(suite, name, algorithm)| {
+                let cipher: Arc<dyn CipherImpl> = Arc::new(RingCipher { name, algorithm });
+                (suite.id(), cipher)
+            })
+            .collect();
+        RwLock::new(ciphers)
+    })
+}
+
+/// Register `cipher` under suite ID `id`, for every vault in the process.
+///
+/// # Errors
+///
+/// Returns `HexvaultError::InvalidCipherSuite` if `id` is outside
+/// `MIN_CUSTOM_SUITE_ID..=MAX_CUSTOM_SUITE_ID` or already registered, if
+/// another cipher has the same name, or if the cipher's key or nonce
+/// length is unsupported.
+pub(crate) fn register_cipher(id: u8, cipher: Arc<dyn CipherImpl>) -> Result<(), HexvaultError> {
+    let supported = (MIN_CUSTOM_SUITE_ID..=MAX_CUSTOM_SUITE_ID).contains(&id)
+        && (16..=KEY_LEN).contains(&cipher.key_len())
+        && cipher.nonce_len() == NONCE_LEN;
+    let mut ciphers = registry().write().unwrap_or_else(PoisonError::into_inner);
+    if !supported
+        || ciphers.contains_key(&id)
+        || ciphers.values().any(|c| c.name() == cipher.name())
+    {
+        return Err(HexvaultError::InvalidCipherSuite(id));
+    }
+    ciphers.insert(id, cipher);
+    Ok(())
+}
+
+/// Seal `buf` in place under `cipher`, checking it appended exactly one tag.
+fn seal_with(
+    cipher: &dyn CipherImpl,
+    key_bytes: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    buf: &mut Vec<u8>,
+) -> Result<(), HexvaultError> {
+    let plaintext_len = buf.len();
+    cipher.encrypt(key_bytes, nonce, aad, buf)?;
+    if buf.len() != plaintext_len + TAG_LEN {
+        return Err(HexvaultError::EncryptionFailure);
+    }
+    Ok(())
+}
+
+/// Open `buf` in place under `cipher`, returning the plaintext length.
+fn open_with(
+    cipher: &dyn CipherImpl,
+    key_bytes: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    buf: &mut [u8],
+) -> Result<usize, HexvaultError> {
+    let sealed_len = buf.len();
+    let len = cipher.decrypt(key_bytes, nonce, aad, buf)?;
+    if len + TAG_LEN != sealed_len {
+        buf.zeroize();
+        return Err(HexvaultError::DecryptionFailure);
+    }
+    Ok(len)
+}
+
+// ---------------------------------------------------------------------------
+// Tag lengths
+// ---------------------------------------------------------------------------
+
+/// The length of the authentication tag stored with each layer.
+///
+/// Recorded in every ciphertext header, so opening checks the same length
+/// without the caller naming it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TagLength {
+    /// The full 128-bit tag. The default.
+    #[default]
+    Full,
+    /// The first 96 bits of the GCM tag, for interop with peers that only
+    /// send truncated tags. GCM suites only; see
+    /// `CipherSuite::allows_truncated_tag`.
+    ///
+    /// Truncation weakens authentication: each forgery attempt succeeds
+    /// with probability about 2^-96 rather than 2^-128, and for GCM the
+    /// odds grow with message length and with every failed attempt an
+    /// attacker can observe. NIST SP 800-38D permits 96-bit tags; use them
+    /// only where a peer requires it. Opening also costs two extra passes
+    /// over the ciphertext, and decrypts before checking the tag.
+    Truncated96,
+}
+
+impl TagLength {
+    /// The tag length in bytes.
+    pub fn bytes(self) -> usize {
+        match self {
+            Self::Full => TAG_LEN,
+            Self::Truncated96 => 12,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// KDF hashes
+// ---------------------------------------------------------------------------
+
+/// The hash HKDF uses to derive a payload's per-layer keys.
+///
+/// Recorded in every ciphertext header next to the `CipherSuite`, so opening
+/// re-derives with the same hash without the caller naming it. The derived
+/// key length is still set by the cipher suite. Partition keys are always
+/// derived with HKDF-SHA256.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KdfHash {
+    /// HKDF-SHA256. The default.
+    #[default]
+    Sha256,
+    /// HKDF-SHA384, for regimes that mandate SHA-384 key derivation.
+    Sha384,
+    /// HKDF-SHA512.
+    Sha512,
+}
+
+impl KdfHash {
+    /// The identifier stored in bits 4-5 of the header suite byte.
+    pub(crate) fn id(self) -> u8 {
+        match self {
+            Self::Sha256 => 0,
+            Self::Sha384 => 1,
+            Self::Sha512 => 2,
+        }
+    }
+
+    /// Look up a KDF hash by its header identifier.
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::Sha256),
+            1 => Some(Self::Sha384),
+            2 => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+}
+
+/// Read the cipher suite from a ciphertext header.
+///
+/// # Errors
+///
+/// Returns `HexvaultError::MalformedCiphertext` if the input is too short to
+/// hold a header, the format version is unknown, or the suite ID is not
+/// recognised.
+pub fn read_suite(ciphertext: &[u8]) -> Result<CipherSuite, HexvaultError> {
+    read_header(ciphertext).map(|(suite, _)| suite)
+}
+
+/// Read the cipher suite and KDF hash from a ciphertext header.
+///
+/// The suite byte holds the `KdfHash` ID in bits 4-5 and the
+/// `CipherSuite` ID in its low nibble; the top bit is the checksum flag (see
+/// `has_checksum`), and bit 6 the truncated-tag flag (see `tag_length`).
+/// HKDF-SHA256 is 0, so ciphertext written before the KDF hash was
+/// selectable reads back unchanged. A truncated tag under a suite that
+/// does not allow one is malformed.
+pub fn read_header(ciphertext: &[u8]) -> Result<(CipherSuite, KdfHash), HexvaultError> {
+    match ciphertext {
+        [FORMAT_VERSION, suite_id, ..] => {
+            let suite = CipherSuite::from_id(suite_id & 0x0f)
+                .filter(|suite| suite_id & TRUNCATED_TAG_FLAG == 0 || suite.allows_truncated_tag());
+            let kdf = KdfHash::from_id((suite_id >> 4) & 0x03);
+            suite.zip(kdf).ok_or(HexvaultError::MalformedCiphertext)
+        }
+        _ => Err(HexvaultError::MalformedCiphertext),
+    }
+}
+
+/// The tag length named in a ciphertext header. `Full` if the input does
+/// not start with a layered header, e.g. a stream.
+pub(crate) fn tag_length(ciphertext: &[u8]) -> TagLength {
+    match ciphertext {
+        [FORMAT_VERSION, id, ..] if id & TRUNCATED_TAG_FLAG != 0 => TagLength::Truncated96,
+        _ => TagLength::Full,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Nonce generation
 // ---------------------------------------------------------------------------
 
 /// Generate a cryptographically secure random nonce.
 ///
-/// Uses `ring::rand::SystemRandom` — the only source of randomness in the crate.
+/// Drawn through `fill_random` — the only source of randomness in the crate.
 /// A fresh nonce is generated for every encryption call. There is no nonce
 /// caching or counter-based generation.
-fn generate_nonce() -> Result<([u8; NONCE_LEN], Nonce), HexvaultError> {
-    let rng = SystemRandom::new();
+fn generate_nonce() -> Result<[u8; NONCE_LEN], HexvaultError> {
+    #[cfg(feature = "insecure-deterministic")]
+    if DETERMINISTIC_NONCES.get() {
+        return Ok([0u8; NONCE_LEN]);
+    }
     let mut buf = [0u8; NONCE_LEN];
-    rng.fill(&mut buf)
-        .map_err(|_| HexvaultError::RandomnessFailure)?;
-    Ok((buf, Nonce::assume_unique_for_key(buf)))
+    fill_random(&mut buf)?;
+    Ok(buf)
 }
 
-/// Encrypt a plaintext payload using AES-256-GCM.
+#[cfg(feature = "insecure-deterministic")]
+thread_local! {
+    /// Whether `generate_nonce` returns the all-zero nonce on this thread.
+    static DETERMINISTIC_NONCES: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Make every nonce generated on the calling thread all zeros, or restore
+/// random nonces. For golden-file tests only; see
+/// `Vault::set_deterministic_nonces`.
+#[cfg(feature = "insecure-deterministic")]
+pub(crate) fn set_deterministic_nonces(enabled: bool) {
+    DETERMINISTIC_NONCES.set(enabled);
+}
+
+/// Encrypt a plaintext payload under the given cipher suite.
+///
+/// `kdf` only names the hash `key_bytes` were derived with, for the header;
+/// it plays no part in the encryption itself.
 ///
-/// `aad_bytes` is bound to the ciphertext via the GCM authentication tag.
-/// Callers must pass the same AAD during decryption — typically the cell ID
-/// and layer tag — so that ciphertext from one cell cannot be replayed into
-/// another.
+/// `aad_bytes` is bound to the ciphertext via the GCM authentication tag,
+/// after the header. Callers must pass the same AAD during decryption —
+/// typically the cell ID and layer tag — so that ciphertext from one cell
+/// cannot be replayed into another.
 ///
-/// Returns the nonce prepended to the ciphertext. The caller does not need to
-/// manage the nonce separately — it is bundled with the output and extracted
-/// automatically during decryption.
+/// `key_bytes` must be `suite.key_len()` bytes long, otherwise this returns
+/// `HexvaultError::InvalidKey`. A custom suite with no registered cipher
+/// fails with `HexvaultError::InvalidCipherSuite`.
+///
+/// Returns the header and nonce prepended to the ciphertext. The caller does
+/// not need to manage the nonce separately — it is bundled with the output
+/// and extracted automatically during decryption.
 ///
 /// # Layout of returned bytes
 /// ```text
-/// [ nonce (12 bytes) ][ ciphertext + GCM tag ]
+/// [ version (1) ][ checksum (1 bit) | truncated tag (1 bit) | kdf (2 bits) | suite (4 bits) ][ nonce (12 bytes) ][ ciphertext + tag ]
 /// ```
 pub fn encrypt(
-    key_bytes: &[u8; KEY_LEN],
+    suite: CipherSuite,
+    kdf: KdfHash,
+    key_bytes: &[u8],
     plaintext: &[u8],
     aad_bytes: &[u8],
 ) -> Result<Vec<u8>, HexvaultError> {
-    let unbound = UnboundKey::new(ALGORITHM, key_bytes).map_err(|_| HexvaultError::InvalidKey)?;
-    let key = LessSafeKey::new(unbound);
+    let mut output = Vec::with_capacity(plaintext.len() + MIN_CIPHERTEXT_LEN);
+    output.extend_from_slice(plaintext);
+    if let Err(e) = encrypt_in_place(
+        suite,
+        kdf,
+        key_bytes,
+        &mut output,
+        aad_bytes,
+        false,
+        TagLength::Full,
+    ) {
+        output.zeroize();
+        return Err(e);
+    }
+    Ok(output)
+}
 
-    let (nonce_bytes, nonce) = generate_nonce()?;
-    let aad = aead::Aad::from(aad_bytes);
+/// `encrypt`, under a caller-chosen `nonce` instead of a fresh random one,
+/// for byte-compatible interop with a peer that picks its own nonces.
+///
+/// **This is dangerous.** Sealing two plaintexts under the same key and
+/// nonce leaks their XOR and the GCM authentication key; ensuring every
+/// nonce is used once per key is entirely the caller's job. Only available
+/// with the `insecure-deterministic` feature.
+#[cfg(any(test, feature = "insecure-deterministic"))]
+pub fn encrypt_with_nonce(
+    suite: CipherSuite,
+    kdf: KdfHash,
+    key_bytes: &[u8],
+    nonce: [u8; NONCE_LEN],
+    plaintext: &[u8],
+    aad_bytes: &[u8],
+) -> Result<Vec<u8>, HexvaultError> {
+    let mut output = Vec::with_capacity(plaintext.len() + MIN_CIPHERTEXT_LEN);
+    output.extend_from_slice(plaintext);
+    if let Err(e) = seal_in_place(
+        suite,
+        kdf,
+        key_bytes,
+        &mut output,
+        aad_bytes,
+        false,
+        TagLength::Full,
+        NonceSource::Fixed(nonce),
+    ) {
+        output.zeroize();
+        return Err(e);
+    }
+    Ok(output)
+}
+
+/// Encrypt the plaintext in `buf` in place, leaving the same bytes `encrypt`
+/// would return.
+///
+/// Grows `buf` by `MIN_CIPHERTEXT_LEN`. Reserve that much spare capacity
+/// first: a reallocation would leave a copy of the plaintext in freed
+/// memory. On error `buf` may hold plaintext; the caller must zeroise it.
+///
+/// `checksum` sets the header flag saying a plaintext checksum follows the
+/// plaintext; the caller appends the checksum itself. `tag` sets the
+/// length of the appended tag.
+pub(crate) fn encrypt_in_place(
+    suite: CipherSuite,
+    kdf: KdfHash,
+    key_bytes: &[u8],
+    buf: &mut Vec<u8>,
+    aad_bytes: &[u8],
+    checksum: bool,
+    tag: TagLength,
+) -> Result<(), HexvaultError> {
+    seal_in_place(
+        suite,
+        kdf,
+        key_bytes,
+        buf,
+        aad_bytes,
+        checksum,
+        tag,
+        NonceSource::Random,
+    )
+}
 
-    let mut buffer = plaintext.to_vec();
+/// `encrypt_in_place`, under a nonce derived from `key_bytes`, `seed`, the
+/// AAD, and the plaintext in `buf` instead of a random one. See
+/// `SealOptions::seed`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn encrypt_in_place_seeded(
+    suite: CipherSuite,
+    kdf: KdfHash,
+    key_bytes: &[u8],
+    buf: &mut Vec<u8>,
+    aad_bytes: &[u8],
+    checksum: bool,
+    tag: TagLength,
+    seed: &[u8],
+) -> Result<(), HexvaultError> {
+    seal_in_place(
+        suite,
+        kdf,
+        key_bytes,
+        buf,
+        aad_bytes,
+        checksum,
+        tag,
+        NonceSource::Seeded(seed),
+    )
+}
 
-    // `seal_in_place_append_tag` encrypts `buffer` in place and
-    // appends the GCM authentication tag.
-    key.seal_in_place_append_tag(nonce, aad, &mut buffer)
-        .map_err(|_| HexvaultError::EncryptionFailure)?;
+/// Where `seal_in_place` gets its nonce.
+enum NonceSource<'a> {
+    /// A fresh random nonce.
+    Random,
+    /// A caller-chosen nonce; see `encrypt_with_nonce`.
+    #[cfg(any(test, feature = "insecure-deterministic"))]
+    Fixed([u8; NONCE_LEN]),
+    /// Derived from the key, this seed, the AAD, and the plaintext; see
+    /// `seeded_nonce`.
+    Seeded(&'a [u8]),
+}
 
-    let mut output = Vec::with_capacity(NONCE_LEN + buffer.len());
-    output.extend_from_slice(&nonce_bytes);
-    output.extend_from_slice(&buffer);
+/// Prefixes the HMAC input of a seeded nonce.
+const SEEDED_NONCE_LABEL: &[u8] = b"hexvault:seeded-nonce";
 
-    Ok(output)
+/// The first 12 bytes of HMAC-SHA256 under `key_bytes` over the label, the
+/// length-prefixed seed, the length-prefixed AEAD AAD (header and caller
+/// AAD), and the plaintext.
+///
+/// Everything the cipher authenticates is mixed in, so a nonce only repeats
+/// under one key for a byte-identical seal — never for two messages, which
+/// would reveal the GCM authentication key. Equal seals remain visibly
+/// equal.
+fn seeded_nonce(key_bytes: &[u8], seed: &[u8], aad: &[u8], plaintext: &[u8]) -> [u8; NONCE_LEN] {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key_bytes);
+    let mut ctx = hmac::Context::with_key(&key);
+    ctx.update(SEEDED_NONCE_LABEL);
+    ctx.update(&(seed.len() as u32).to_be_bytes());
+    ctx.update(seed);
+    ctx.update(&(aad.len() as u32).to_be_bytes());
+    ctx.update(aad);
+    ctx.update(plaintext);
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&ctx.sign().as_ref()[..NONCE_LEN]);
+    nonce
 }
 
-/// Decrypt a ciphertext payload using AES-256-GCM.
+/// `encrypt_in_place`, with the nonce taken from `nonce`.
+#[allow(clippy::too_many_arguments)]
+fn seal_in_place(
+    suite: CipherSuite,
+    kdf: KdfHash,
+    key_bytes: &[u8],
+    buf: &mut Vec<u8>,
+    aad_bytes: &[u8],
+    checksum: bool,
+    tag: TagLength,
+    nonce: NonceSource<'_>,
+) -> Result<(), HexvaultError> {
+    let cipher = suite.cipher()?;
+    if key_bytes.len() != cipher.key_len() {
+        return Err(HexvaultError::InvalidKey);
+    }
+    if tag != TagLength::Full && !suite.allows_truncated_tag() {
+        return Err(HexvaultError::InvalidCipherSuite(suite.id()));
+    }
+
+    let mut header = [FORMAT_VERSION, kdf.id() << 4 | suite.id()];
+    if checksum {
+        header[1] |= CHECKSUM_FLAG;
+    }
+    if tag == TagLength::Truncated96 {
+        header[1] |= TRUNCATED_TAG_FLAG;
+    }
+
+    let aad = header_aad(&header, aad_bytes);
+    let nonce_bytes = match nonce {
+        NonceSource::Random => generate_nonce()?,
+        #[cfg(any(test, feature = "insecure-deterministic"))]
+        NonceSource::Fixed(nonce) => nonce,
+        NonceSource::Seeded(seed) => seeded_nonce(key_bytes, seed, &aad, buf),
+    };
+
+    // The cipher encrypts `buf` in place and appends the authentication tag.
+    // A truncated GCM tag is the leading bytes of the full one.
+    seal_with(&*cipher, key_bytes, &nonce_bytes, &aad, buf)?;
+    buf.truncate(buf.len() - (TAG_LEN - tag.bytes()));
+
+    // Shift the ciphertext up to make room for the header and nonce.
+    let sealed_len = buf.len();
+    buf.resize(PREFIX_LEN + sealed_len, 0);
+    buf.copy_within(..sealed_len, PREFIX_LEN);
+    buf[..HEADER_LEN].copy_from_slice(&header);
+    buf[HEADER_LEN..PREFIX_LEN].copy_from_slice(&nonce_bytes);
+    Ok(())
+}
+
+/// Decrypt a ciphertext payload under the suite named in its header.
 ///
 /// Expects the input to be in the layout produced by `encrypt`:
-/// nonce (12 bytes) followed by ciphertext and GCM tag.
+/// header, nonce (12 bytes), then ciphertext and tag.
 ///
 /// `aad_bytes` must match the AAD that was provided during encryption.
-/// If the AAD, key, or ciphertext has been tampered with, the GCM
+/// If the AAD, key, header, or ciphertext has been tampered with, the GCM
 /// authentication check fails and this function returns an error. The caller
 /// receives no partial plaintext.
+///
+/// Structurally invalid input — too short, or an unknown header — fails with
+/// `HexvaultError::MalformedCiphertext` before any cryptographic work. This
+/// function never panics, whatever bytes it is given.
+///
+/// An empty plaintext is valid and authenticated: its ciphertext is exactly
+/// `MIN_CIPHERTEXT_LEN` bytes, while anything shorter is malformed.
 pub fn decrypt(
-    key_bytes: &[u8; KEY_LEN],
+    key_bytes: &[u8],
     ciphertext: &[u8],
     aad_bytes: &[u8],
 ) -> Result<Vec<u8>, HexvaultError> {
-    if ciphertext.len() < NONCE_LEN {
+    let mut out = Vec::new();
+    decrypt_into(key_bytes, ciphertext, aad_bytes, &mut out)?;
+    Ok(out)
+}
+
+/// Decrypt a ciphertext payload into a caller-provided buffer.
+///
+/// Behaves like `decrypt`, but writes the plaintext into `out` so that hot
+/// paths can reuse one allocation across calls. `out` is cleared, not
+/// zeroised, before it is overwritten — callers reusing a buffer that held
+/// plaintext must wipe it first. `out` is zeroised if decryption fails.
+pub fn decrypt_into(
+    key_bytes: &[u8],
+    ciphertext: &[u8],
+    aad_bytes: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), HexvaultError> {
+    out.clear();
+    out.extend_from_slice(ciphertext);
+    decrypt_in_place(key_bytes, out, aad_bytes)
+}
+
+/// Decrypt the ciphertext in `buf` in place, leaving only the plaintext.
+///
+/// Behaves like `decrypt`; `buf` is zeroised on any failure.
+pub(crate) fn decrypt_in_place(
+    key_bytes: &[u8],
+    buf: &mut Vec<u8>,
+    aad_bytes: &[u8],
+) -> Result<(), HexvaultError> {
+    match open_in_place(key_bytes, buf, aad_bytes) {
+        Ok(plaintext_len) => {
+            // Shift the plaintext down over the header and nonce, and trim
+            // the tag, rather than copying it out — a copy would leave a
+            // second, unzeroised plaintext buffer behind.
+            buf.copy_within(PREFIX_LEN..PREFIX_LEN + plaintext_len, 0);
+            buf[plaintext_len..].zeroize();
+            buf.truncate(plaintext_len);
+            Ok(())
+        }
+        Err(e) => {
+            buf.zeroize();
+            Err(e)
+        }
+    }
+}
+
+/// Authenticate and decrypt `buf[PREFIX_LEN..]` in place, returning the
+/// plaintext length.
+fn open_in_place(
+    key_bytes: &[u8],
+    buf: &mut [u8],
+    aad_bytes: &[u8],
+) -> Result<usize, HexvaultError> {
+    // Every structural check happens before any slicing, so arbitrary input
+    // yields an error rather than a panic. In order: a known header, a
+    // whole nonce after it, then room for the tag.
+    let (suite, _) = read_header(buf)?;
+    let tag = tag_length(buf);
+    if buf.len() < PREFIX_LEN {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+    if buf.len() - PREFIX_LEN < tag.bytes() {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+    let cipher = suite.cipher()?;
+    if key_bytes.len() != cipher.key_len() {
+        return Err(HexvaultError::InvalidKey);
+    }
+
+    let (prefix, sealed) = buf.split_at_mut(PREFIX_LEN);
+    let (header, nonce) = prefix.split_at(HEADER_LEN);
+    let aad = header_aad(header, aad_bytes);
+    match tag {
+        TagLength::Full => open_with(&*cipher, key_bytes, nonce, &aad, sealed),
+        TagLength::Truncated96 => open_truncated(&*cipher, key_bytes, nonce, &aad, sealed),
+    }
+}
+
+/// Open `buf` in place under a truncated tag, returning the plaintext
+/// length.
+///
+/// The cipher only checks full tags, so the tag is recomputed instead: GCM
+/// is a stream cipher, so sealing zeros under the same key and nonce yields
+/// the keystream, the keystream recovers the plaintext, and re-sealing
+/// that yields the full tag, whose leading bytes must match. The outputs
+/// of both seals reuse the nonce and never leave this function.
+fn open_truncated(
+    cipher: &dyn CipherImpl,
+    key_bytes: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    buf: &mut [u8],
+) -> Result<usize, HexvaultError> {
+    let len = buf.len() - TagLength::Truncated96.bytes();
+    let (ciphertext, expected) = buf.split_at_mut(len);
+
+    let mut plaintext = Zeroizing::new(Vec::with_capacity(len + TAG_LEN));
+    plaintext.resize(len, 0);
+    seal_with(cipher, key_bytes, nonce, aad, &mut plaintext)?;
+    plaintext.truncate(len);
+    for (byte, c) in plaintext.iter_mut().zip(ciphertext.iter()) {
+        *byte ^= c;
+    }
+
+    let mut resealed = Zeroizing::new(Vec::with_capacity(len + TAG_LEN));
+    resealed.extend_from_slice(&plaintext);
+    seal_with(cipher, key_bytes, nonce, aad, &mut resealed)?;
+    let diff = resealed[len..]
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    if diff != 0 {
         return Err(HexvaultError::DecryptionFailure);
     }
 
-    let nonce_bytes: [u8; NONCE_LEN] = ciphertext[..NONCE_LEN]
-        .try_into()
-        .map_err(|_| HexvaultError::DecryptionFailure)?;
-    let nonce = Nonce::assume_unique_for_key(nonce_bytes);
+    ciphertext.copy_from_slice(&plaintext);
+    Ok(len)
+}
+
+/// The AEAD AAD for a layer: its header, then the caller's AAD. Binding the
+/// header stops an attacker from rewriting the suite, KDF hash, or checksum
+/// flag without failing authentication.
+fn header_aad(header: &[u8], aad_bytes: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + aad_bytes.len());
+    aad.extend_from_slice(header);
+    aad.extend_from_slice(aad_bytes);
+    aad
+}
+
+// ---------------------------------------------------------------------------
+// Framed encryption
+// ---------------------------------------------------------------------------
+
+/// Plaintext bytes in every frame of a framed ciphertext but the last.
+pub const FRAME_LEN: usize = 64 * 1024;
+
+/// The format version in the header of framed ciphertext. Distinct from
+/// `FORMAT_VERSION`, so neither kind is mistaken for the other.
+pub const FRAMED_FORMAT_VERSION: u8 = 2;
+
+/// The nonce for frame `index`: the base nonce with the index XORed into
+/// bytes 3-10, big-endian, and the final flag into byte 11. Every
+/// (index, final) pair gets a distinct nonce, so frames cannot be
+/// reordered, and a stream cut at a frame boundary ends on a frame not
+/// sealed as final.
+fn frame_nonce(base: &[u8; NONCE_LEN], index: u64, last: bool) -> [u8; NONCE_LEN] {
+    let mut nonce = *base;
+    for (byte, i) in nonce[3..11].iter_mut().zip(index.to_be_bytes()) {
+        *byte ^= i;
+    }
+    nonce[11] ^= u8::from(last);
+    nonce
+}
+
+/// Seals a framed ciphertext one frame at a time, so the plaintext never
+/// has to be in memory at once.
+///
+/// The output is the prefix from `new` followed by each sealed frame, in
+/// order. Every frame but the last must hold the same number of plaintext
+/// bytes, the frame length the `FrameOpener` is given; the last holds at
+/// most that many, and may be empty.
+pub(crate) struct FrameSealer {
+    cipher: Arc<dyn CipherImpl>,
+    key: Zeroizing<Vec<u8>>,
+    base: [u8; NONCE_LEN],
+    aad: Vec<u8>,
+    index: u64,
+}
+
+impl FrameSealer {
+    /// Start a framed ciphertext, returning the sealer and the header and
+    /// base nonce that open it.
+    pub(crate) fn new(
+        suite: CipherSuite,
+        kdf: KdfHash,
+        key_bytes: &[u8],
+        aad_bytes: &[u8],
+    ) -> Result<(Self, [u8; PREFIX_LEN]), HexvaultError> {
+        let cipher = suite.cipher()?;
+        if key_bytes.len() != cipher.key_len() {
+            return Err(HexvaultError::InvalidKey);
+        }
+        let header = [FRAMED_FORMAT_VERSION, kdf.id() << 4 | suite.id()];
+        let base = generate_nonce()?;
+
+        let mut prefix = [0u8; PREFIX_LEN];
+        prefix[..HEADER_LEN].copy_from_slice(&header);
+        prefix[HEADER_LEN..].copy_from_slice(&base);
+        let sealer = Self {
+            cipher,
+            key: Zeroizing::new(key_bytes.to_vec()),
+            base,
+            aad: header_aad(&header, aad_bytes),
+            index: 0,
+        };
+        Ok((sealer, prefix))
+    }
+
+    /// Seal the next frame in place in `frame`, appending its tag. `last`
+    /// must be set on the final frame, and only there.
+    pub(crate) fn seal_frame(
+        &mut self,
+        frame: &mut Vec<u8>,
+        last: bool,
+    ) -> Result<(), HexvaultError> {
+        let nonce_bytes = frame_nonce(&self.base, self.index, last);
+        seal_with(&*self.cipher, &self.key, &nonce_bytes, &self.aad, frame)?;
+        self.index += 1;
+        Ok(())
+    }
+}
+
+/// Opens a framed ciphertext one frame at a time.
+pub(crate) struct FrameOpener {
+    cipher: Arc<dyn CipherImpl>,
+    key: Zeroizing<Vec<u8>>,
+    base: [u8; NONCE_LEN],
+    aad: Vec<u8>,
+    frame_len: usize,
+    index: u64,
+}
+
+impl FrameOpener {
+    /// Start opening the framed ciphertext whose first `PREFIX_LEN` bytes
+    /// are `prefix` and whose full frames hold `frame_len` plaintext bytes.
+    pub(crate) fn new(
+        key_bytes: &[u8],
+        prefix: &[u8],
+        aad_bytes: &[u8],
+        frame_len: usize,
+    ) -> Result<Self, HexvaultError> {
+        let (suite, _) = read_framed_header(prefix)?;
+        let header = [FRAMED_FORMAT_VERSION, prefix[1]];
+        let cipher = suite.cipher()?;
+        if key_bytes.len() != cipher.key_len() {
+            return Err(HexvaultError::InvalidKey);
+        }
+
+        let mut base = [0u8; NONCE_LEN];
+        base.copy_from_slice(&prefix[HEADER_LEN..PREFIX_LEN]);
+        Ok(Self {
+            cipher,
+            key: Zeroizing::new(key_bytes.to_vec()),
+            base,
+            aad: header_aad(&header, aad_bytes),
+            frame_len,
+            index: 0,
+        })
+    }
+
+    /// The sealed size of a full frame: the frame length plus the tag.
+    pub(crate) fn sealed_frame_len(&self) -> usize {
+        self.frame_len + TAG_LEN
+    }
+
+    /// Authenticate and decrypt the next frame in place, returning its
+    /// plaintext length. On failure the frame holds no plaintext.
+    pub(crate) fn open_frame(
+        &mut self,
+        frame: &mut [u8],
+        last: bool,
+    ) -> Result<usize, HexvaultError> {
+        if frame.len() > self.sealed_frame_len() {
+            return Err(HexvaultError::MalformedCiphertext);
+        }
+        let nonce = frame_nonce(&self.base, self.index, last);
+        let len = open_with(&*self.cipher, &self.key, &nonce, &self.aad, frame)?;
+        self.index += 1;
+        Ok(len)
+    }
+}
+
+/// Read the suite and KDF hash from the header of a framed ciphertext,
+/// whose first `PREFIX_LEN` bytes are `prefix`.
+///
+/// # Errors
+///
+/// Returns `HexvaultError::MalformedCiphertext` if `prefix` is too short or
+/// not a framed header.
+pub(crate) fn read_framed_header(prefix: &[u8]) -> Result<(CipherSuite, KdfHash), HexvaultError> {
+    if prefix.len() < PREFIX_LEN || prefix[0] != FRAMED_FORMAT_VERSION {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+    // Framed headers share the layered suite byte, without a truncated tag.
+    let header = [FORMAT_VERSION, prefix[1]];
+    if tag_length(&header) != TagLength::Full {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+    read_header(&header)
+}
+
+/// Encrypt a plaintext as a sequence of `FRAME_LEN` frames, each sealed
+/// separately, so it can be produced and consumed a frame at a time.
+///
+/// Frame nonces are derived from one random base nonce, the frame index,
+/// and a flag on the final frame, so reordered, dropped, or truncated
+/// frames fail authentication. `aad_bytes` and the header are bound into
+/// every frame, as in `encrypt`.
+///
+/// # Layout of returned bytes
+/// ```text
+/// [ version 2 (1) ][ 0 (2 bits) | kdf (2 bits) | suite (4 bits) ][ base nonce (12) ][ frame ]...
+/// frame = [ ciphertext (FRAME_LEN, or less for the last) + tag (16) ]
+/// ```
+///
+/// An empty plaintext is a single, empty final frame.
+pub fn encrypt_framed(
+    suite: CipherSuite,
+    kdf: KdfHash,
+    key_bytes: &[u8],
+    plaintext: &[u8],
+    aad_bytes: &[u8],
+) -> Result<Vec<u8>, HexvaultError> {
+    let (mut sealer, prefix) = FrameSealer::new(suite, kdf, key_bytes, aad_bytes)?;
+    let frames = plaintext.len().div_ceil(FRAME_LEN).max(1);
+    let mut output = Vec::with_capacity(PREFIX_LEN + plaintext.len() + frames * TAG_LEN);
+    output.extend_from_slice(&prefix);
+
+    let mut frame = Vec::with_capacity(FRAME_LEN + TAG_LEN);
+    for index in 0..frames {
+        let start = index * FRAME_LEN;
+        let end = plaintext.len().min(start + FRAME_LEN);
+        frame.clear();
+        frame.extend_from_slice(&plaintext[start..end]);
+        if let Err(e) = sealer.seal_frame(&mut frame, index + 1 == frames) {
+            frame.zeroize();
+            return Err(e);
+        }
+        output.extend_from_slice(&frame);
+    }
+    Ok(output)
+}
+
+/// Decrypt a ciphertext produced by `encrypt_framed`.
+///
+/// `aad_bytes` must match the AAD given at encryption. The last frame is
+/// whatever follows the full frames, so a ciphertext cut anywhere fails:
+/// mid-frame as a short or unauthenticated frame, and at a frame boundary
+/// because the new last frame was not sealed as final.
+///
+/// # Errors
+///
+/// Returns `HexvaultError::MalformedCiphertext` if the header is not a
+/// framed header or the input is too short to hold a frame, and
+/// `HexvaultError::DecryptionFailure` if any frame fails authentication.
+pub fn decrypt_framed(
+    key_bytes: &[u8],
+    ciphertext: &[u8],
+    aad_bytes: &[u8],
+) -> Result<Vec<u8>, HexvaultError> {
+    let mut opener = FrameOpener::new(key_bytes, ciphertext, aad_bytes, FRAME_LEN)?;
+    let sealed_frame_len = opener.sealed_frame_len();
+
+    let mut out = ciphertext[PREFIX_LEN..].to_vec();
+    let mut read = 0;
+    let mut written = 0;
+    loop {
+        let remaining = out.len() - read;
+        let last = remaining <= sealed_frame_len;
+        let frame_len = remaining.min(sealed_frame_len);
+        if frame_len < TAG_LEN {
+            out.zeroize();
+            return Err(HexvaultError::MalformedCiphertext);
+        }
+        let len = match opener.open_frame(&mut out[read..read + frame_len], last) {
+            Ok(len) => len,
+            Err(e) => {
+                out.zeroize();
+                return Err(e);
+            }
+        };
+        // Compact the plaintext down over the tags of earlier frames.
+        out.copy_within(read..read + len, written);
+        read += frame_len;
+        written += len;
+        if last {
+            break;
+        }
+    }
+    out[written..].zeroize();
+    out.truncate(written);
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// Plaintext checksums
+// ---------------------------------------------------------------------------
 
-    let unbound = UnboundKey::new(ALGORITHM, key_bytes).map_err(|_| HexvaultError::InvalidKey)?;
-    let key = LessSafeKey::new(unbound);
+/// Returns true if the ciphertext header carries the checksum flag.
+pub(crate) fn has_checksum(ciphertext: &[u8]) -> bool {
+    matches!(ciphertext, [FORMAT_VERSION, id, ..] if id & CHECKSUM_FLAG != 0)
+}
 
-    let aad = aead::Aad::from(aad_bytes);
-    let mut payload = ciphertext[NONCE_LEN..].to_vec();
+/// Compute the SHA-256 checksum of a plaintext.
+pub(crate) fn checksum(plaintext: &[u8]) -> [u8; CHECKSUM_LEN] {
+    sha256(plaintext)
+}
 
-    let plaintext = key
-        .open_in_place(nonce, aad, &mut payload)
-        .map_err(|_| HexvaultError::DecryptionFailure)?;
+/// The SHA-256 digest of `bytes`.
+pub(crate) fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(digest::digest(&digest::SHA256, bytes).as_ref());
+    out
+}
 
-    Ok(plaintext.to_vec())
+/// Split `data` into plaintext and trailing checksum, and check it matches.
+/// The comparison does not short-circuit. Returns the plaintext length.
+pub(crate) fn verify_checksum(data: &[u8]) -> Result<usize, HexvaultError> {
+    let len = data
+        .len()
+        .checked_sub(CHECKSUM_LEN)
+        .ok_or(HexvaultError::IntegrityCheckFailed)?;
+    let (plaintext, expected) = data.split_at(len);
+    let diff = checksum(plaintext)
+        .iter()
+        .zip(expected)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    if diff != 0 {
+        return Err(HexvaultError::IntegrityCheckFailed);
+    }
+    Ok(len)
 }
 
 /// Generate a cryptographically secure random key.
@@ -123,9 +1200,229 @@ pub fn decrypt(
 /// This is the only function in the crate that produces raw key material from
 /// scratch. It is used by `generate_master_key()` in the public API.
 pub fn generate_random_key() -> Result<[u8; KEY_LEN], HexvaultError> {
-    let rng = SystemRandom::new();
     let mut key = [0u8; KEY_LEN];
-    rng.fill(&mut key)
-        .map_err(|_| HexvaultError::RandomnessFailure)?;
+    fill_random(&mut key)?;
     Ok(key)
 }
+
+/// Fill `buf` from `SystemRandom`.
+///
+/// Every random byte the crate uses — keys and nonces — is drawn here.
+pub fn fill_random(buf: &mut [u8]) -> Result<(), HexvaultError> {
+    #[cfg(test)]
+    if FAILING_RANDOM.get() {
+        return Err(HexvaultError::RandomnessFailure);
+    }
+    SystemRandom::new()
+        .fill(buf)
+        .map_err(|_| HexvaultError::RandomnessFailure)
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Whether `fill_random` fails on this thread; see `FailingRandom`.
+    static FAILING_RANDOM: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Makes every `fill_random` call on the current thread fail with
+/// `RandomnessFailure` until dropped, so tests can reach an error path that
+/// `SystemRandom` never takes.
+#[cfg(test)]
+pub(crate) struct FailingRandom(());
+
+#[cfg(test)]
+impl FailingRandom {
+    pub(crate) fn new() -> Self {
+        FAILING_RANDOM.set(true);
+        Self(())
+    }
+}
+
+#[cfg(test)]
+impl Drop for FailingRandom {
+    fn drop(&mut self) {
+        FAILING_RANDOM.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::keys::{self, MasterKey};
+    use crate::stack::{Layer, LayerContext};
+
+    #[test]
+    fn test_decrypt_rejects_short_input_without_panicking() {
+        let key = [5u8; KEY_LEN];
+        let sealed = encrypt(CipherSuite::Aes256Gcm, KdfHash::default(), &key, b"", b"").unwrap();
+        assert_eq!(sealed.len(), MIN_CIPHERTEXT_LEN);
+
+        // Empty, one byte, header only, header and part of the nonce,
+        // header and nonce, and one byte short of the tag.
+        for len in [
+            0,
+            1,
+            HEADER_LEN,
+            HEADER_LEN + 5,
+            PREFIX_LEN,
+            MIN_CIPHERTEXT_LEN - 1,
+        ] {
+            assert!(matches!(
+                decrypt(&key, &sealed[..len], b""),
+                Err(HexvaultError::MalformedCiphertext)
+            ));
+        }
+        // An unknown header is caught before the length.
+        assert!(matches!(
+            decrypt(&key, &[0xff; MIN_CIPHERTEXT_LEN], b""),
+            Err(HexvaultError::MalformedCiphertext)
+        ));
+        assert_eq!(decrypt(&key, &sealed, b"").unwrap(), b"");
+    }
+
+    #[test]
+    fn test_seeded_nonce_depends_on_aad() {
+        let key = [5u8; KEY_LEN];
+        let seal = |aad: &[u8], tag: TagLength| {
+            let mut buf = b"retry".to_vec();
+            encrypt_in_place_seeded(
+                CipherSuite::Aes256Gcm,
+                KdfHash::default(),
+                &key,
+                &mut buf,
+                aad,
+                false,
+                tag,
+                b"request-1",
+            )
+            .unwrap();
+            buf[HEADER_LEN..PREFIX_LEN].to_vec()
+        };
+
+        let nonce = seal(b"aad-1", TagLength::Full);
+        assert_eq!(seal(b"aad-1", TagLength::Full), nonce);
+        // Same seed and plaintext, different AAD or header: a fresh nonce.
+        assert_ne!(seal(b"aad-2", TagLength::Full), nonce);
+        assert_ne!(seal(b"aad-1", TagLength::Truncated96), nonce);
+    }
+
+    #[test]
+    fn test_encrypt_with_nonce_uses_given_nonce() {
+        let key = [5u8; KEY_LEN];
+        let nonce = [9u8; NONCE_LEN];
+        let seal = |plaintext: &[u8]| {
+            encrypt_with_nonce(
+                CipherSuite::Aes256Gcm,
+                KdfHash::default(),
+                &key,
+                nonce,
+                plaintext,
+                b"aad",
+            )
+            .unwrap()
+        };
+
+        let sealed = seal(b"interop");
+        assert_eq!(&sealed[HEADER_LEN..PREFIX_LEN], &nonce);
+        assert_eq!(decrypt(&key, &sealed, b"aad").unwrap(), b"interop");
+        // Reusing the nonce reproduces the ciphertext — the danger it
+        // documents.
+        assert_eq!(seal(b"interop"), sealed);
+    }
+
+    #[test]
+    fn test_truncated_tag_header_is_validated() {
+        let key = [5u8; KEY_LEN];
+        let mut sealed = Vec::new();
+        encrypt_in_place(
+            CipherSuite::Aes256Gcm,
+            KdfHash::Sha384,
+            &key,
+            &mut sealed,
+            b"aad",
+            false,
+            TagLength::Truncated96,
+        )
+        .unwrap();
+        assert_eq!(sealed.len(), PREFIX_LEN + 12);
+        assert_eq!(tag_length(&sealed), TagLength::Truncated96);
+        assert_eq!(
+            read_header(&sealed).unwrap(),
+            (CipherSuite::Aes256Gcm, KdfHash::Sha384)
+        );
+        assert_eq!(decrypt(&key, &sealed, b"aad").unwrap(), b"");
+        assert!(matches!(
+            decrypt(&key, &sealed[..PREFIX_LEN + 11], b"aad"),
+            Err(HexvaultError::MalformedCiphertext)
+        ));
+
+        // The flag is only valid on a GCM suite.
+        let chacha = [
+            FORMAT_VERSION,
+            TRUNCATED_TAG_FLAG | CipherSuite::ChaCha20Poly1305.id(),
+        ];
+        assert!(matches!(
+            read_header(&chacha),
+            Err(HexvaultError::MalformedCiphertext)
+        ));
+    }
+
+    #[test]
+    fn test_frame_nonce_index_is_big_endian() {
+        let base = [0u8; NONCE_LEN];
+        assert_eq!(
+            frame_nonce(&base, 0x0102_0304_0506_0708, true),
+            [0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 1]
+        );
+        assert_eq!(frame_nonce(&base, 1, false)[10], 1);
+    }
+
+    #[test]
+    fn test_randomness_failure_surfaces_cleanly() {
+        let master = MasterKey::from_bytes([4u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::default();
+        let mut cell = Cell::new("cell".to_string());
+        let mut stream = Vec::new();
+
+        {
+            let _failing = FailingRandom::new();
+            assert!(matches!(
+                crate::generate_master_key(),
+                Err(HexvaultError::RandomnessFailure)
+            ));
+            assert!(matches!(
+                encrypt(
+                    CipherSuite::Aes256Gcm,
+                    KdfHash::default(),
+                    &[0u8; KEY_LEN],
+                    b"x",
+                    b""
+                ),
+                Err(HexvaultError::RandomnessFailure)
+            ));
+            assert!(matches!(
+                cell.store(&partition, "k", b"never stored", Layer::AtRest, &ctx),
+                Err(HexvaultError::RandomnessFailure)
+            ));
+            assert!(matches!(
+                crate::stream::seal_stream(
+                    &partition,
+                    "cell",
+                    Layer::AtRest,
+                    &ctx,
+                    &b"never sealed"[..],
+                    &mut stream
+                ),
+                Err(HexvaultError::RandomnessFailure)
+            ));
+        }
+
+        // Nothing was left half-written, and randomness recovers on drop.
+        assert!(!cell.contains_key("k"));
+        assert!(stream.is_empty());
+        cell.store(&partition, "k", b"stored", Layer::AtRest, &ctx)
+            .unwrap();
+    }
+}
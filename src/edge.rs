@@ -13,14 +13,18 @@
 //! function. It is explicitly zeroised (via `zeroize`) after re-encryption,
 //! before the function returns — whether the operation succeeds or fails.
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use zeroize::Zeroize;
 
-use crate::audit::{AuditLog, AuditRecord};
+use crate::audit::{AuditEvent, AuditLog, AuditRecord};
 use crate::cell::Cell;
+use crate::crypto::CipherSuite;
 use crate::error::HexvaultError;
 use crate::keys::PartitionKey;
-use crate::stack::{Layer, LayerContext};
+use crate::partition::Partition;
+use crate::stack::{Layer, LayerContext, LayerGuard, PeelOptions, SealOptions};
+
+use std::sync::Arc;
 
 /// Configuration arguments for a traversal operation.
 ///
@@ -32,36 +36,182 @@ pub struct TraversalRequest<'a> {
     pub source: &'a Cell,
     pub dest: &'a mut Cell,
     pub key: &'a str,
+    /// The key to store under in the destination. `None` reuses `key`.
+    pub dest_key: Option<&'a str>,
     pub target_layer: Layer,
     pub source_ctx: &'a LayerContext,
     pub dest_ctx: &'a LayerContext,
+    /// The cipher suite for the destination seal. `None` inherits the
+    /// suite the source payload was sealed with.
+    pub dest_suite: Option<CipherSuite>,
+    /// Application AAD the source payload was sealed with.
+    pub source_aad: &'a [u8],
+    /// Checked before each source layer is peeled. `None` allows every
+    /// layer.
+    pub source_guard: Option<Arc<dyn LayerGuard>>,
+    /// Application AAD to bind into the destination seal.
+    pub dest_aad: &'a [u8],
+    /// Who is performing the traversal, recorded on the audit record.
+    pub actor: Option<&'a str>,
+    /// The time of the traversal: the audit timestamp, the destination
+    /// payload's creation time, and what the source's expiry is checked
+    /// against.
+    pub now: DateTime<Utc>,
+}
+
+/// How `Vault::merge_cell` and `Vault::gather` handle a payload key that
+/// already exists in the destination cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Leave the destination's payload in place and do not move this one.
+    Skip,
+    /// Replace the destination's payload.
+    Overwrite,
+    /// Move the payload under `"{key}.{n}"`, with the smallest `n >= 1`
+    /// that is free in the destination.
+    Rename,
+}
+
+impl ConflictPolicy {
+    /// The key to move a payload under, given that it wants `key` in
+    /// `dest`, or `None` to skip it.
+    pub(crate) fn resolve(self, dest: &Cell, key: &str) -> Option<String> {
+        if !dest.contains_key(key) {
+            return Some(key.to_string());
+        }
+        match self {
+            Self::Skip => None,
+            Self::Overwrite => Some(key.to_string()),
+            Self::Rename => (1..)
+                .map(|n| format!("{key}.{n}"))
+                .find(|candidate| !dest.contains_key(candidate)),
+        }
+    }
+}
+
+/// One payload for `Vault::gather` to move into the destination cell.
+#[derive(Clone, Copy)]
+pub struct GatherSource<'a> {
+    /// The partition the source cell belongs to.
+    pub partition: &'a Partition,
+    pub cell: &'a Cell,
+    pub key: &'a str,
+    /// Resolves to the context that opens the payload.
+    pub token: &'a str,
+    /// The key to seal the payload under in the destination.
+    pub dest_key: &'a str,
+}
+
+/// Optional traversal parameters accepted by `Vault::traverse_with_options`.
+#[derive(Debug, Clone, Default)]
+pub struct TraversalOptions {
+    /// Re-seal the destination under this suite instead of the source's.
+    pub dest_suite: Option<CipherSuite>,
+    /// Application AAD required to open the source payload.
+    pub source_aad: Vec<u8>,
+    /// Application AAD to bind into the destination payload.
+    pub dest_aad: Vec<u8>,
+    /// Who is performing the traversal. `None` falls back to the vault's
+    /// actor (see `Vault::set_actor`).
+    pub actor: Option<String>,
+    /// The lowest layer the destination may be sealed at, e.g. to keep a
+    /// migration into a sensitive cell from weakening its protection. A
+    /// lower `target_layer` fails with `LayerPolicyViolation` before the
+    /// source is peeled.
+    pub min_dest_layer: Option<Layer>,
 }
 
 /// Move a payload from one cell to another.
 ///
 /// The payload is decrypted from the source cell using `source_ctx` and
 /// immediately re-encrypted into the destination cell at `target_layer`
-/// using `dest_ctx`.
+/// using `dest_ctx`. The destination uses `dest_suite` if given, otherwise
+/// the suite the source payload was sealed with, and always keeps the
+/// source's KDF hash and expiry.
 ///
 /// The plaintext exists only within the scope of this function and is
-/// explicitly zeroised before return.
-pub fn traverse(audit: &mut AuditLog, req: TraversalRequest) -> Result<(), HexvaultError> {
+/// explicitly zeroised before return. If `dest_ctx` lacks an ID that
+/// `target_layer` needs, this fails with `MissingOrInvalidContext` before
+/// the source is peeled.
+///
+/// Returns a copy of the audit record appended for the traversal, so the
+/// caller can forward or correlate it without looking it up in the log.
+/// If the log's sequence is exhausted, the payload has still moved but
+/// `AuditSequenceExhausted` is returned.
+///
+/// A source and destination with the same cell ID under the same partition
+/// key are the same cell, and are rejected with `InvalidTraversal`;
+/// re-sealing a payload in place is what `reseal` is for. Cell IDs are only
+/// unique within a partition, so cells in different partitions may share
+/// one.
+pub fn traverse(audit: &mut AuditLog, req: TraversalRequest) -> Result<AuditRecord, HexvaultError> {
+    if req.source.id() == req.dest.id() && req.source_partition_key.same_as(req.dest_partition_key)
+    {
+        return Err(HexvaultError::InvalidTraversal(
+            "source and destination are the same cell".to_string(),
+        ));
+    }
+
+    // Phase 0: Probe
+    // A destination context that cannot seal at the target layer dooms the
+    // traversal. Fail before the source is decrypted, so no plaintext ever
+    // exists for it.
+    if !req.dest_ctx.missing_for(req.target_layer).is_empty() {
+        return Err(HexvaultError::MissingOrInvalidContext);
+    }
+
     // Phase 1: Peel
     // We retrieve the plaintext from the source.
     // If the key doesn't exist or contexts are wrong, this fails early.
-    let mut plaintext = req
-        .source
-        .retrieve(req.source_partition_key, req.key, req.source_ctx)?;
+    let mut plaintext = req.source.retrieve_at(
+        req.source_partition_key,
+        req.key,
+        req.source_ctx,
+        &PeelOptions {
+            aad: req.source_aad.to_vec(),
+            guard: req.source_guard.clone(),
+            ..PeelOptions::default()
+        },
+        req.now,
+    )?;
+
+    let source_payload = req.source.payload(req.key);
+    let suite = req.dest_suite.unwrap_or_else(|| {
+        source_payload
+            .and_then(|payload| payload.cipher_suite())
+            .unwrap_or_default()
+    });
+    let kdf = source_payload
+        .and_then(|payload| payload.kdf_hash())
+        .unwrap_or_default();
+    let checksum = source_payload.is_some_and(|payload| payload.has_checksum());
+    let tag_length = source_payload
+        .map(|payload| payload.tag_length())
+        .filter(|_| suite.allows_truncated_tag())
+        .unwrap_or_default();
+    let expires_at = source_payload.and_then(|payload| payload.expires_at);
+    let content_id = source_payload.is_some_and(|payload| payload.content_id.is_some());
 
     // Phase 2: Seal
     // We store the plaintext into the destination cell.
     // Capture the result BEFORE zeroising plaintext so we can still report errors.
-    let seal_result = req.dest.store(
+    let seal_result = req.dest.store_at(
         req.dest_partition_key,
-        req.key,
+        req.dest_key.unwrap_or(req.key),
         &plaintext,
         req.target_layer,
         req.dest_ctx,
+        &SealOptions {
+            suite,
+            kdf,
+            aad: req.dest_aad.to_vec(),
+            checksum,
+            tag_length,
+            expires_at,
+            content_id,
+            ..SealOptions::default()
+        },
+        req.now,
     );
 
     // Zeroize plaintext IMMEDIATELY — regardless of seal success or failure.
@@ -74,16 +224,25 @@ pub fn traverse(audit: &mut AuditLog, req: TraversalRequest) -> Result<(), Hexva
 
     // Phase 3: Audit
     // Log the successful traversal.
+    // Log the parameters the destination was actually sealed with, read
+    // back from its header rather than from the request.
+    let header = req
+        .dest
+        .payload(req.dest_key.unwrap_or(req.key))
+        .map(|payload| payload.header());
     let record = AuditRecord {
-        source_cell_id: req.source.id().to_string(),
         dest_cell_id: req.dest.id().to_string(),
-        layer: req.target_layer,
-        timestamp: Utc::now(),
-        entry_hash: String::new(),
+        actor: req.actor.map(str::to_string),
+        cipher_suite: header.and_then(|header| header.cipher_suite),
+        format_version: header.and_then(|header| header.format_version),
+        ..AuditRecord::new(
+            AuditEvent::Traverse,
+            req.source.id(),
+            req.target_layer,
+            req.now,
+        )
     };
-    audit.append(record);
-
-    Ok(())
+    Ok(audit.push(record)?.clone())
 }
 
 #[cfg(test)]
@@ -115,9 +274,16 @@ mod tests {
                 source: &cell_a,
                 dest: &mut cell_b,
                 key: "secret",
+                dest_key: None,
                 target_layer: Layer::AtRest,
                 source_ctx: &ctx,
                 dest_ctx: &ctx,
+                dest_suite: None,
+                source_aad: &[],
+                source_guard: None,
+                dest_aad: &[],
+                actor: None,
+                now: Utc::now(),
             },
         )
         .unwrap();
@@ -132,4 +298,140 @@ mod tests {
         assert_eq!(record.source_cell_id, "cell-a");
         assert_eq!(record.dest_cell_id, "cell-b");
     }
+
+    #[test]
+    fn test_traverse_probes_dest_context_before_peeling() {
+        let master = MasterKey::from_bytes([2u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let cell_a = Cell::new("cell-a".to_string());
+        let mut cell_b = Cell::new("cell-b".to_string());
+        let mut audit = AuditLog::new();
+        let ctx = LayerContext::default();
+
+        // The source key does not exist, so reaching the peel would report
+        // `KeyNotFound`; the probe rejects the destination context first.
+        let result = traverse(
+            &mut audit,
+            TraversalRequest {
+                source_partition_key: &partition,
+                dest_partition_key: &partition,
+                source: &cell_a,
+                dest: &mut cell_b,
+                key: "missing",
+                dest_key: None,
+                target_layer: Layer::AccessGated,
+                source_ctx: &ctx,
+                dest_ctx: &ctx,
+                dest_suite: None,
+                source_aad: &[],
+                source_guard: None,
+                dest_aad: &[],
+                actor: None,
+                now: Utc::now(),
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(HexvaultError::MissingOrInvalidContext)
+        ));
+        assert!(audit.is_empty());
+    }
+
+    #[test]
+    fn test_traverse_rejects_same_cell() {
+        let master = MasterKey::from_bytes([2u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::default();
+        let mut source = Cell::new("cell-a".to_string());
+        source
+            .store(&partition, "k", b"stay put", Layer::AtRest, &ctx)
+            .unwrap();
+        // A distinct `Cell` value that shares the source's ID.
+        let mut cell = Cell::new("cell-a".to_string());
+        let mut audit = AuditLog::new();
+
+        let result = traverse(
+            &mut audit,
+            TraversalRequest {
+                source_partition_key: &partition,
+                dest_partition_key: &partition,
+                source: &source,
+                dest: &mut cell,
+                key: "k",
+                dest_key: Some("k2"),
+                target_layer: Layer::AtRest,
+                source_ctx: &ctx,
+                dest_ctx: &ctx,
+                dest_suite: None,
+                source_aad: &[],
+                source_guard: None,
+                dest_aad: &[],
+                actor: None,
+                now: Utc::now(),
+            },
+        );
+        assert!(matches!(result, Err(HexvaultError::InvalidTraversal(_))));
+        assert!(cell.payload("k2").is_none());
+        assert!(audit.is_empty());
+    }
+
+    #[test]
+    fn test_traverse_changes_suite() {
+        let master = MasterKey::from_bytes([3u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let mut aes_cell = Cell::new("aes".to_string());
+        let mut chacha_cell = Cell::new("chacha".to_string());
+        let mut inherit_cell = Cell::new("inherit".to_string());
+        let mut audit = AuditLog::new();
+        let ctx = LayerContext::default();
+
+        aes_cell
+            .store(&partition, "k", b"migrate me", Layer::AtRest, &ctx)
+            .unwrap();
+
+        let mut request = |dest: &mut Cell, source: &Cell, dest_suite| {
+            traverse(
+                &mut audit,
+                TraversalRequest {
+                    source_partition_key: &partition,
+                    dest_partition_key: &partition,
+                    source,
+                    dest,
+                    key: "k",
+                    dest_key: None,
+                    target_layer: Layer::AtRest,
+                    source_ctx: &ctx,
+                    dest_ctx: &ctx,
+                    dest_suite,
+                    source_aad: &[],
+                    source_guard: None,
+                    dest_aad: &[],
+                    actor: None,
+                    now: Utc::now(),
+                },
+            )
+        };
+
+        request(
+            &mut chacha_cell,
+            &aes_cell,
+            Some(CipherSuite::ChaCha20Poly1305),
+        )
+        .unwrap();
+        assert_eq!(
+            chacha_cell.payload("k").unwrap().cipher_suite(),
+            Some(CipherSuite::ChaCha20Poly1305)
+        );
+        assert_eq!(
+            chacha_cell.retrieve(&partition, "k", &ctx).unwrap(),
+            b"migrate me"
+        );
+
+        // Without an explicit suite the destination inherits the source's.
+        request(&mut inherit_cell, &chacha_cell, None).unwrap();
+        assert_eq!(
+            inherit_cell.payload("k").unwrap().cipher_suite(),
+            Some(CipherSuite::ChaCha20Poly1305)
+        );
+    }
 }
@@ -7,12 +7,22 @@
 
 use std::fmt;
 
+use crate::stack::Layer;
+
 /// The single error type for all hexvault operations.
+///
+/// New variants may be added in minor releases, so matches need a
+/// wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum HexvaultError {
     /// A cryptographic key was invalid (wrong length, malformed, etc.).
     InvalidKey,
 
+    /// A master key was rejected as too weak to use: all zeros, or made of
+    /// so few distinct bytes that it cannot have come from a random source.
+    WeakKey,
+
     /// Encryption failed. The underlying `ring` operation returned an error.
     EncryptionFailure,
 
@@ -20,14 +30,40 @@ pub enum HexvaultError {
     /// or corrupted GCM authentication tag.
     DecryptionFailure,
 
+    /// A ciphertext is structurally invalid: too short to hold a header,
+    /// nonce, and tag, or its header names an unknown version or suite.
+    /// Detected before any decryption is attempted.
+    MalformedCiphertext,
+
+    /// Every layer authenticated, but the plaintext did not match the
+    /// SHA-256 checksum sealed with it. Indicates a layering or logic error
+    /// rather than tampering.
+    IntegrityCheckFailed,
+
+    /// The payload was sealed with an expiry (`SealOptions::expires_at`)
+    /// that has passed. Checked before any decryption is attempted.
+    Expired,
+
+    /// A cipher suite ID has no registered cipher, or a custom cipher could
+    /// not be registered under it (see `register_global_cipher`).
+    InvalidCipherSuite(u8),
+
     /// Key derivation (HKDF) failed.
     KeyDerivationFailure,
 
+    /// This many audit forward sinks panicked while receiving a record.
+    /// The record was still appended to the log and the other sinks.
+    AuditSinkFailed(usize),
+
+    /// An audit log's sequence number reached `u64::MAX`. No further record
+    /// can be appended without breaking its total order.
+    AuditSequenceExhausted,
+
     /// The system's random number generator failed to produce bytes.
     RandomnessFailure,
 
-    /// A cell with the given ID does not exist in the vault.
-    CellNotFound(String),
+    /// A cell holds no payload under the given key.
+    KeyNotFound(String),
 
     /// A cell with the given ID already exists in the vault.
     CellAlreadyExists(String),
@@ -39,6 +75,19 @@ pub enum HexvaultError {
     /// provided, or the provided context does not match the expected value.
     MissingOrInvalidContext,
 
+    /// An item in a planned batch lacks the context IDs for these layers.
+    /// `index` is the item's position in the batch.
+    IncompleteContext { index: usize, missing: Vec<Layer> },
+
+    /// A seal targeted a layer below the vault's minimum (see
+    /// `Vault::set_min_layer`), or a traversal targeted a layer below its
+    /// `TraversalOptions::min_dest_layer`.
+    LayerPolicyViolation { layer: Layer, min: Layer },
+
+    /// A strict seal was given context IDs for these layers, which lie above
+    /// the target layer and would have been ignored.
+    UnexpectedContext(Vec<Layer>),
+
     /// An edge traversal was attempted but the source or destination cell
     /// is not valid for the operation.
     InvalidTraversal(String),
@@ -49,25 +98,132 @@ pub enum HexvaultError {
 
     /// A partition ID was empty.
     InvalidPartitionId,
+
+    /// A payload key starts with the reserved `__hexvault:` prefix.
+    ReservedKey,
+
+    /// A key ring has no master key with this ID, or the operation is not
+    /// allowed on it (removing the primary).
+    UnknownKeyId(u32),
+
+    /// Reading or writing persisted state (e.g. an audit file) failed.
+    Io(std::io::Error),
+
+    /// A snapshot is truncated, corrupted, or not in a recognised format.
+    /// Detected before any decryption is attempted.
+    CorruptSnapshot,
+}
+
+impl HexvaultError {
+    /// A stable, machine-readable code for the variant, e.g. for mapping
+    /// errors to HTTP statuses. Codes never change between releases, even
+    /// when the `Display` wording does; new variants get new codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidKey => "INVALID_KEY",
+            Self::WeakKey => "WEAK_KEY",
+            Self::EncryptionFailure => "ENCRYPTION_FAILED",
+            Self::DecryptionFailure => "DECRYPTION_FAILED",
+            Self::MalformedCiphertext => "MALFORMED_CIPHERTEXT",
+            Self::IntegrityCheckFailed => "INTEGRITY_CHECK_FAILED",
+            Self::Expired => "EXPIRED",
+            Self::InvalidCipherSuite(_) => "INVALID_CIPHER_SUITE",
+            Self::KeyDerivationFailure => "KEY_DERIVATION_FAILED",
+            Self::AuditSinkFailed(_) => "AUDIT_SINK_FAILED",
+            Self::AuditSequenceExhausted => "AUDIT_SEQUENCE_EXHAUSTED",
+            Self::RandomnessFailure => "RANDOMNESS_FAILED",
+            Self::KeyNotFound(_) => "KEY_NOT_FOUND",
+            Self::CellAlreadyExists(_) => "CELL_ALREADY_EXISTS",
+            Self::InvalidLayer => "INVALID_LAYER",
+            Self::MissingOrInvalidContext => "BAD_CONTEXT",
+            Self::IncompleteContext { .. } => "INCOMPLETE_CONTEXT",
+            Self::LayerPolicyViolation { .. } => "LAYER_POLICY_VIOLATION",
+            Self::UnexpectedContext(_) => "UNEXPECTED_CONTEXT",
+            Self::InvalidTraversal(_) => "INVALID_TRAVERSAL",
+            Self::InvalidCellId => "INVALID_CELL_ID",
+            Self::InvalidPartitionId => "INVALID_PARTITION_ID",
+            Self::ReservedKey => "RESERVED_KEY",
+            Self::UnknownKeyId(_) => "UNKNOWN_KEY_ID",
+            Self::Io(_) => "IO",
+            Self::CorruptSnapshot => "CORRUPT_SNAPSHOT",
+        }
+    }
 }
 
 impl fmt::Display for HexvaultError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InvalidKey => write!(f, "invalid key"),
+            Self::WeakKey => write!(f, "weak key"),
             Self::EncryptionFailure => write!(f, "encryption failed"),
             Self::DecryptionFailure => write!(f, "decryption failed"),
+            Self::MalformedCiphertext => write!(f, "malformed ciphertext"),
+            Self::IntegrityCheckFailed => write!(f, "plaintext integrity check failed"),
+            Self::Expired => write!(f, "payload has expired"),
+            Self::InvalidCipherSuite(id) => write!(f, "invalid cipher suite: {}", id),
             Self::KeyDerivationFailure => write!(f, "key derivation failed"),
+            Self::AuditSinkFailed(n) => write!(f, "audit sinks failed: {}", n),
+            Self::AuditSequenceExhausted => write!(f, "audit sequence exhausted"),
             Self::RandomnessFailure => write!(f, "randomness source failed"),
-            Self::CellNotFound(id) => write!(f, "cell not found: {}", id),
+            Self::KeyNotFound(key) => write!(f, "payload key not found: {}", key),
             Self::CellAlreadyExists(id) => write!(f, "cell already exists: {}", id),
             Self::InvalidLayer => write!(f, "invalid layer"),
             Self::MissingOrInvalidContext => write!(f, "missing or invalid layer context"),
+            Self::IncompleteContext { index, missing } => {
+                write!(
+                    f,
+                    "batch item {} is missing context for {:?}",
+                    index, missing
+                )
+            }
+            Self::LayerPolicyViolation { layer, min } => {
+                write!(f, "layer {:?} is below the minimum {:?}", layer, min)
+            }
+            Self::UnexpectedContext(layers) => {
+                write!(f, "context supplied for unused layers {:?}", layers)
+            }
             Self::InvalidTraversal(reason) => write!(f, "invalid traversal: {}", reason),
             Self::InvalidCellId => write!(f, "cell ID must not be empty"),
             Self::InvalidPartitionId => write!(f, "partition ID must not be empty"),
+            Self::ReservedKey => write!(f, "payload key uses a reserved prefix"),
+            Self::UnknownKeyId(id) => write!(f, "unknown master key ID: {}", id),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::CorruptSnapshot => write!(f, "snapshot is truncated or corrupt"),
         }
     }
 }
 
-impl std::error::Error for HexvaultError {}
+impl std::error::Error for HexvaultError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for HexvaultError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_stable() {
+        // Codes are a public contract; changing one is a breaking change.
+        assert_eq!(
+            HexvaultError::KeyNotFound("x".into()).code(),
+            "KEY_NOT_FOUND"
+        );
+        assert_eq!(HexvaultError::MissingOrInvalidContext.code(), "BAD_CONTEXT");
+        assert_eq!(HexvaultError::DecryptionFailure.code(), "DECRYPTION_FAILED");
+        assert_eq!(
+            HexvaultError::from(std::io::Error::other("disk")).code(),
+            "IO"
+        );
+    }
+}
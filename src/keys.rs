@@ -1,7 +1,8 @@
 //! Key derivation and ownership.
 //!
 //! This module owns two responsibilities:
-//! 1. Deriving per-cell, per-layer keys from a master key using HKDF-SHA256.
+//! 1. Deriving per-cell, per-layer keys from a master key using HKDF-SHA256
+//!    (or the `KdfHash` a payload was sealed with).
 //! 2. Holding derived key material in types that are opaque, non-cloneable,
 //!    and zeroised on drop.
 //!
@@ -23,17 +24,32 @@
 //! statistically independent key. The info string uses length-prefixed segments
 //! to prevent delimiter collisions. Knowing one derived key reveals nothing
 //! about the master key or any other derived key.
+//!
+//! A `KeyRing` built with `KeyRing::with_domain` also mixes a domain into
+//! every partition key: the HKDF salt is a SHA-256 of the domain, and the
+//! info string gains a `len("domain") || "domain" || len(domain) || domain`
+//! prefix. Rings over the same master key with different domains share no
+//! keys.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
 
-use ring::hkdf;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use ring::{digest, hkdf, hmac};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-use crate::crypto::KEY_LEN;
+use crate::cell::ContentId;
+use crate::crypto::{CipherSuite, KdfHash, KEY_LEN};
 use crate::error::HexvaultError;
 
 // ---------------------------------------------------------------------------
 // Master key
 // ---------------------------------------------------------------------------
 
+/// Fewest distinct byte values `MasterKey::from_bytes_checked` accepts.
+const MIN_DISTINCT_KEY_BYTES: usize = 8;
+
 /// A master key. This is the single secret that must be managed by the caller.
 /// All per-cell and per-layer keys are derived from it.
 ///
@@ -52,17 +68,86 @@ impl MasterKey {
     /// In production, the caller should source these bytes from a KMS.
     /// For the PoC, use `crate::generate_master_key()` which calls
     /// `crypto::generate_random_key()` internally.
+    ///
+    /// The bytes are not checked, so fixed keys such as `[0u8; 32]` work in
+    /// tests. Prefer `from_bytes_checked` for keys loaded from configuration.
     #[must_use]
     pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
         Self { bytes }
     }
 
+    /// Construct a `MasterKey` from raw bytes, rejecting the all-zero key
+    /// and other keys too regular to have come from a random source.
+    ///
+    /// A key is rejected if it has fewer than 8 distinct byte values. A
+    /// uniformly random key fails this with probability below 2^-100, so
+    /// it only catches placeholders and misconfigured loaders.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::WeakKey` if the key is rejected.
+    pub fn from_bytes_checked(bytes: [u8; KEY_LEN]) -> Result<Self, HexvaultError> {
+        let mut seen = [false; 256];
+        for &b in &bytes {
+            seen[usize::from(b)] = true;
+        }
+        if seen.iter().filter(|&&s| s).count() < MIN_DISTINCT_KEY_BYTES {
+            return Err(HexvaultError::WeakKey);
+        }
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Read a master key from a file holding exactly 32 raw bytes, e.g. a
+    /// secret mounted on tmpfs by a container orchestrator. The bytes are
+    /// read into a buffer that is zeroised before returning.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::Io` if the file cannot be read, or
+    /// `HexvaultError::InvalidKey` if it is not exactly 32 bytes long.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, HexvaultError> {
+        // Read into a fixed buffer, one byte longer than a key so a long file
+        // is detected; a growing `Vec` could leave unzeroised copies behind.
+        let mut buf = Zeroizing::new([0u8; KEY_LEN + 1]);
+        let mut file = File::open(path)?;
+        let mut len = 0;
+        while len < buf.len() {
+            match file.read(&mut buf[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        if len != KEY_LEN {
+            return Err(HexvaultError::InvalidKey);
+        }
+        let bytes: [u8; KEY_LEN] = buf[..KEY_LEN]
+            .try_into()
+            .map_err(|_| HexvaultError::InvalidKey)?;
+        Ok(Self::from_bytes(bytes))
+    }
+
     /// Borrow the raw key bytes for use in HKDF derivation.
     ///
     /// This method is `pub(crate)` — raw bytes never leave the crate.
     pub(crate) fn as_bytes(&self) -> &[u8; KEY_LEN] {
         &self.bytes
     }
+
+    /// Copy the raw key bytes out, e.g. to back up a locally generated
+    /// development key. The copy is zeroised when dropped.
+    ///
+    /// **This defeats the crate's key-confinement guarantee.** Everything
+    /// hexvault protects can be decrypted by whoever holds these bytes, and
+    /// once they leave this wrapper nothing stops them being logged, copied,
+    /// or written to disk in the clear. Only available with the
+    /// `unsafe-key-export` feature, which is off by default; production keys
+    /// belong in a KMS and should never need it.
+    #[cfg(feature = "unsafe-key-export")]
+    pub fn expose_secret(&self) -> Zeroizing<[u8; KEY_LEN]> {
+        Zeroizing::new(self.bytes)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -73,9 +158,16 @@ impl MasterKey {
 ///
 /// - Not `Clone`.
 /// - Zeroised on drop via `ZeroizeOnDrop`.
+/// - Remembers the `KeyId` of the master key it was derived from, which is
+///   recorded on every payload sealed under it.
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct PartitionKey {
     bytes: [u8; KEY_LEN],
+    key_id: KeyId,
+    /// The deriving vault's nonce-reuse detector, if any.
+    #[cfg(debug_assertions)]
+    #[zeroize(skip)]
+    nonce_detector: Option<std::sync::Arc<crate::nonce_check::NonceDetector>>,
 }
 
 impl PartitionKey {
@@ -83,6 +175,146 @@ impl PartitionKey {
     pub(crate) fn as_bytes(&self) -> &[u8; KEY_LEN] {
         &self.bytes
     }
+
+    /// The ID of the master key this key was derived from.
+    pub fn key_id(&self) -> KeyId {
+        self.key_id
+    }
+
+    /// Report seals under this key to `detector`.
+    #[cfg(debug_assertions)]
+    pub(crate) fn with_nonce_detector(
+        mut self,
+        detector: std::sync::Arc<crate::nonce_check::NonceDetector>,
+    ) -> Self {
+        self.nonce_detector = Some(detector);
+        self
+    }
+
+    /// The nonce-reuse detector of the vault that derived this key, if any.
+    #[cfg(debug_assertions)]
+    pub(crate) fn nonce_detector(&self) -> Option<&crate::nonce_check::NonceDetector> {
+        self.nonce_detector.as_deref()
+    }
+
+    /// Whether `other` is the same key, i.e. for the same partition under
+    /// the same master key. Compared in constant time.
+    pub(crate) fn same_as(&self, other: &PartitionKey) -> bool {
+        let diff = self
+            .bytes
+            .iter()
+            .zip(other.bytes.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        diff == 0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Key ring
+// ---------------------------------------------------------------------------
+
+/// Identifies a master key within a `KeyRing`. A lone master key has ID 0.
+pub type KeyId = u32;
+
+/// A set of master keys by ID, one of which is the primary.
+///
+/// New payloads are always sealed under the primary key; payloads record
+/// the ID they were sealed under, so opening picks the matching key. This
+/// allows gradual rotation: add the new key, make it primary, re-seal the
+/// payloads still under the old key, then remove the old key.
+pub struct KeyRing {
+    keys: BTreeMap<KeyId, MasterKey>,
+    primary: KeyId,
+    domain: Option<String>,
+}
+
+impl KeyRing {
+    /// Create a ring holding `key` under `id`, as the primary.
+    pub fn new(id: KeyId, key: MasterKey) -> Self {
+        let mut keys = BTreeMap::new();
+        keys.insert(id, key);
+        Self {
+            keys,
+            primary: id,
+            domain: None,
+        }
+    }
+
+    /// Create a ring whose partition keys are all derived within `domain`,
+    /// e.g. a deployment or tenant name.
+    ///
+    /// The domain is part of every key's derivation: payloads only open
+    /// under a ring with the same domain, so it must not change for the
+    /// life of the data.
+    pub fn with_domain(id: KeyId, key: MasterKey, domain: &str) -> Self {
+        Self {
+            domain: Some(domain.to_string()),
+            ..Self::new(id, key)
+        }
+    }
+
+    /// The derivation domain, if the ring has one.
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    /// Add a key under `id`, replacing (and zeroising) any key already
+    /// there. The primary is unchanged.
+    pub fn insert(&mut self, id: KeyId, key: MasterKey) {
+        self.keys.insert(id, key);
+    }
+
+    /// Make the key under `id` the primary.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::UnknownKeyId` if no key has that ID.
+    pub fn set_primary(&mut self, id: KeyId) -> Result<(), HexvaultError> {
+        if !self.keys.contains_key(&id) {
+            return Err(HexvaultError::UnknownKeyId(id));
+        }
+        self.primary = id;
+        Ok(())
+    }
+
+    /// Remove a retired key. Payloads still sealed under it can no longer
+    /// be opened, so re-seal them first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::UnknownKeyId` if no key has that ID or it is
+    /// the primary.
+    pub fn remove(&mut self, id: KeyId) -> Result<(), HexvaultError> {
+        if id == self.primary || self.keys.remove(&id).is_none() {
+            return Err(HexvaultError::UnknownKeyId(id));
+        }
+        Ok(())
+    }
+
+    /// The ID of the primary key.
+    pub fn primary_id(&self) -> KeyId {
+        self.primary
+    }
+
+    /// The IDs of every key in the ring, in ascending order.
+    pub fn ids(&self) -> impl Iterator<Item = KeyId> + '_ {
+        self.keys.keys().copied()
+    }
+
+    /// Derive the partition key for `partition_id` under every key in the
+    /// ring, primary first.
+    pub(crate) fn derive_partition_keys(
+        &self,
+        partition_id: &str,
+    ) -> Result<Vec<PartitionKey>, HexvaultError> {
+        let mut ids = vec![self.primary];
+        ids.extend(self.ids().filter(|&id| id != self.primary));
+        ids.into_iter()
+            .map(|id| {
+                derive_partition_key_for(&self.keys[&id], id, self.domain.as_deref(), partition_id)
+            })
+            .collect()
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -93,20 +325,32 @@ impl PartitionKey {
 ///
 /// - Not `Clone`. Each derived key is a single-use value scoped to one
 ///   cell + layer + context combination.
+/// - Its length is set by the cipher suite it was derived for (32 bytes,
+///   or 16 for AES-128-GCM).
 /// - Zeroised on drop via `ZeroizeOnDrop`.
 /// - Raw bytes are never exposed outside this module. Other modules
 ///   access derived keys only through `as_bytes()`, which is `pub(crate)`.
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct DerivedKey {
     bytes: [u8; KEY_LEN],
+    len: usize,
 }
 
 impl DerivedKey {
     /// Borrow the raw key bytes for use in encrypt/decrypt operations.
     ///
     /// `pub(crate)` — raw bytes never leave the crate.
-    pub(crate) fn as_bytes(&self) -> &[u8; KEY_LEN] {
-        &self.bytes
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// An HKDF output length, for expanding keys shorter than the hash output.
+struct OkmLen(usize);
+
+impl hkdf::KeyType for OkmLen {
+    fn len(&self) -> usize {
+        self.0
     }
 }
 
@@ -150,12 +394,56 @@ fn build_info(segments: &[&str]) -> Vec<u8> {
 pub fn derive_partition_key(
     master: &MasterKey,
     partition_id: &str,
+) -> Result<PartitionKey, HexvaultError> {
+    derive_partition_key_for(master, 0, None, partition_id)
+}
+
+/// Derive a partition key, tagging it with the ID of `master` in its ring.
+/// The ID is not mixed into the derivation; the domain, if any, is.
+fn derive_partition_key_for(
+    master: &MasterKey,
+    key_id: KeyId,
+    domain: Option<&str>,
+    partition_id: &str,
 ) -> Result<PartitionKey, HexvaultError> {
     if partition_id.is_empty() {
         return Err(HexvaultError::InvalidPartitionId);
     }
 
-    let info = build_info(&["partition", partition_id]);
+    let info = match domain {
+        Some(domain) => build_info(&["domain", domain, "partition", partition_id]),
+        None => build_info(&["partition", partition_id]),
+    };
+    let domain_salt = domain.map(|d| digest::digest(&digest::SHA256, &build_info(&["domain", d])));
+    let salt = hkdf::Salt::new(
+        hkdf::HKDF_SHA256,
+        domain_salt.as_ref().map_or(&[][..], |d| d.as_ref()),
+    );
+    let prk = salt.extract(master.as_bytes());
+
+    let info_slices = [info.as_slice()];
+    let okm = prk
+        .expand(&info_slices, hkdf::HKDF_SHA256)
+        .map_err(|_| HexvaultError::KeyDerivationFailure)?;
+
+    let mut derived = [0u8; KEY_LEN];
+    okm.fill(&mut derived)
+        .map_err(|_| HexvaultError::KeyDerivationFailure)?;
+
+    Ok(PartitionKey {
+        bytes: derived,
+        key_id,
+        #[cfg(debug_assertions)]
+        nonce_detector: None,
+    })
+}
+
+/// Derive the key used to encrypt persisted audit records.
+///
+/// The info string is `len("audit") || "audit"`. It has a different segment
+/// count from partition and cell derivations, so it cannot collide with them.
+pub(crate) fn derive_audit_key(master: &MasterKey) -> Result<DerivedKey, HexvaultError> {
+    let info = build_info(&["audit"]);
     let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, &[]);
     let prk = salt.extract(master.as_bytes());
 
@@ -168,10 +456,75 @@ pub fn derive_partition_key(
     okm.fill(&mut derived)
         .map_err(|_| HexvaultError::KeyDerivationFailure)?;
 
-    Ok(PartitionKey { bytes: derived })
+    Ok(DerivedKey {
+        bytes: derived,
+        len: KEY_LEN,
+    })
 }
 
-/// Derive a key for a specific cell, layer, and context.
+/// Derive the HMAC key content IDs are computed with (see
+/// `Payload::content_id`).
+///
+/// The info string is `len("content-id") || "content-id"`, expanded from the
+/// partition key rather than the master key, so content IDs are only
+/// comparable within one partition under one master key.
+pub(crate) fn derive_content_id_key(
+    partition_key: &PartitionKey,
+) -> Result<DerivedKey, HexvaultError> {
+    let info = build_info(&["content-id"]);
+    let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, &[]);
+    let prk = salt.extract(partition_key.as_bytes());
+
+    let info_slices = [info.as_slice()];
+    let okm = prk
+        .expand(&info_slices, hkdf::HKDF_SHA256)
+        .map_err(|_| HexvaultError::KeyDerivationFailure)?;
+
+    let mut derived = [0u8; KEY_LEN];
+    okm.fill(&mut derived)
+        .map_err(|_| HexvaultError::KeyDerivationFailure)?;
+
+    Ok(DerivedKey {
+        bytes: derived,
+        len: KEY_LEN,
+    })
+}
+
+/// The content ID of `text` under `partition_key`: an HMAC-SHA256 keyed
+/// with `derive_content_id_key`.
+pub(crate) fn content_id(
+    partition_key: &PartitionKey,
+    text: &[u8],
+) -> Result<ContentId, HexvaultError> {
+    let key = derive_content_id_key(partition_key)?;
+    let hmac_key = hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes());
+    let mut id = [0u8; 32];
+    id.copy_from_slice(hmac::sign(&hmac_key, text).as_ref());
+    Ok(id)
+}
+
+/// Fail with `HexvaultError::IntegrityCheckFailed` unless `id` is the
+/// content ID of `text` under `partition_key`. Compares in constant time.
+pub(crate) fn check_content_id(
+    partition_key: &PartitionKey,
+    text: &[u8],
+    id: &ContentId,
+) -> Result<(), HexvaultError> {
+    let key = derive_content_id_key(partition_key)?;
+    let hmac_key = hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes());
+    hmac::verify(&hmac_key, text, id).map_err(|_| HexvaultError::IntegrityCheckFailed)
+}
+
+/// The `ring` HKDF algorithm for a `KdfHash`.
+fn hkdf_algorithm(kdf: KdfHash) -> hkdf::Algorithm {
+    match kdf {
+        KdfHash::Sha256 => hkdf::HKDF_SHA256,
+        KdfHash::Sha384 => hkdf::HKDF_SHA384,
+        KdfHash::Sha512 => hkdf::HKDF_SHA512,
+    }
+}
+
+/// Derive a key for a specific cell, layer, context, and cipher suite.
 ///
 /// The info string is length-prefixed:
 /// ```text
@@ -179,46 +532,261 @@ pub fn derive_partition_key(
 /// ```
 ///
 /// `context_id` is empty for Layer 0 (at-rest), an access policy ID for
-/// Layer 1, and a session ID for Layer 2.
+/// Layer 1, and a session ID for Layer 2. Non-default suites append
+/// `len(suite_label) || suite_label`, and a cell bound to a schema version
+/// then appends `len("schema") || "schema" || len(version) || version`, the
+/// version in decimal. HKDF runs over `kdf`, which is HKDF-SHA256 unless
+/// the payload was sealed with another `KdfHash`.
 ///
 /// # Security properties
 /// - HKDF is one-way: the derived key reveals nothing about the master key.
 /// - Length-prefixed info strings prevent delimiter collisions.
 /// - Different info strings produce statistically independent outputs.
-/// - The output length is fixed at 256 bits (32 bytes).
+/// - The output length is the suite's key length: 256 bits, or 128 bits
+///   for AES-128-GCM.
 ///
 /// # Errors
 ///
-/// Returns `HexvaultError::InvalidCellId` if `cell_id` is empty.
+/// Returns `HexvaultError::InvalidCellId` if `cell_id` is empty, and
+/// `HexvaultError::InvalidCipherSuite` if `suite` has no registered cipher.
 pub(crate) fn derive_key(
     partition_key: &PartitionKey,
     cell_id: &str,
     layer_tag: &str,
     context_id: &str,
+    suite: CipherSuite,
+    kdf: KdfHash,
+    schema_version: Option<u32>,
 ) -> Result<DerivedKey, HexvaultError> {
     if cell_id.is_empty() {
         return Err(HexvaultError::InvalidCellId);
     }
 
-    let info = build_info(&[cell_id, layer_tag, context_id]);
+    let mut segments = vec![cell_id, layer_tag, context_id];
+    if let Some(label) = suite.derivation_label()? {
+        segments.push(label);
+    }
+    let version = schema_version.map(|v| v.to_string());
+    if let Some(version) = &version {
+        segments.extend(["schema", version.as_str()]);
+    }
+    let info = build_info(&segments);
 
     // Extract phase: derive a pseudorandom key (PRK) from the partition key.
     // An empty salt is provided — HKDF internally treats this as a
     // zero-filled salt of the hash output length, which is standard.
-    let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, &[]);
+    let salt = hkdf::Salt::new(hkdf_algorithm(kdf), &[]);
     let prk = salt.extract(partition_key.as_bytes());
 
     // Expand phase: derive the final key from the PRK and the info string.
     // The info string encodes the cell, layer, and context — ensuring every
     // derived key is unique and scoped.
+    // Expand to exactly the suite's key length. The suite label in the info
+    // string keeps a 16-byte key from being a prefix of any 32-byte one.
+    let len = suite.cipher()?.key_len();
     let info_slices = [info.as_slice()];
     let okm = prk
-        .expand(&info_slices, hkdf::HKDF_SHA256)
+        .expand(&info_slices, OkmLen(len))
         .map_err(|_| HexvaultError::KeyDerivationFailure)?;
 
     let mut derived = [0u8; KEY_LEN];
-    okm.fill(&mut derived)
+    okm.fill(&mut derived[..len])
         .map_err(|_| HexvaultError::KeyDerivationFailure)?;
 
-    Ok(DerivedKey { bytes: derived })
+    Ok(DerivedKey {
+        bytes: derived,
+        len,
+    })
+}
+
+/// The message each key signs in `fingerprint_keys`.
+const FINGERPRINT_LABEL: &[u8] = b"hexvault key fingerprint v1";
+
+/// A one-way fingerprint of `keys`, in order: each signs
+/// `FINGERPRINT_LABEL` with HMAC-SHA256, and the tags are hashed together
+/// with SHA-256.
+pub(crate) fn fingerprint_keys(keys: &[DerivedKey]) -> [u8; 32] {
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    for key in keys {
+        let hmac_key = hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes());
+        ctx.update(hmac::sign(&hmac_key, FINGERPRINT_LABEL).as_ref());
+    }
+    let mut fingerprint = [0u8; 32];
+    fingerprint.copy_from_slice(ctx.finish().as_ref());
+    fingerprint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes_checked_rejects_weak_keys() {
+        for weak in [
+            [0u8; KEY_LEN],
+            [0xff; KEY_LEN],
+            *b"abcdefgabcdefgabcdefgabcdefgabcd",
+        ] {
+            assert!(matches!(
+                MasterKey::from_bytes_checked(weak),
+                Err(HexvaultError::WeakKey)
+            ));
+        }
+        let vector = vector_master();
+        let checked = MasterKey::from_bytes_checked(*vector.as_bytes()).unwrap();
+        assert_eq!(checked.as_bytes(), vector.as_bytes());
+    }
+
+    /// Fixed master key for the vectors: bytes 0x00..=0x1f.
+    fn vector_master() -> MasterKey {
+        let mut bytes = [0u8; KEY_LEN];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        MasterKey::from_bytes(bytes)
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// Known-answer vectors for cross-implementation checks. These are also
+    /// listed in `docs/test-vectors.md`; a change here is a breaking change
+    /// to every stored ciphertext.
+    #[test]
+    fn test_derivation_vectors() {
+        let master = vector_master();
+        let partition = derive_partition_key(&master, "p1").unwrap();
+        assert_eq!(
+            hex(partition.as_bytes()),
+            "41d9d5501e1d78a8ac0b59a9ee0429c89f191a712d22c7fa994cba379fe1e766"
+        );
+
+        let cases = [
+            (
+                layer_tag::AT_REST,
+                "",
+                CipherSuite::Aes256Gcm,
+                "20645b56f10c13702c8b7fe5c1b3f29070626e0041d10245fe45020ba115ea35",
+            ),
+            (
+                layer_tag::ACCESS_GATED,
+                "policy-1",
+                CipherSuite::Aes256Gcm,
+                "6a2a7c0b9630fc5d3285bac45526bd2396c6d3653d50f7239a44bf8f00011c5e",
+            ),
+            (
+                layer_tag::SESSION_BOUND,
+                "session-1",
+                CipherSuite::Aes256Gcm,
+                "dddf5103887388bd9438b06cb483762bb01c3f2be683b36838e92c6948006d22",
+            ),
+            (
+                layer_tag::AT_REST,
+                "",
+                CipherSuite::ChaCha20Poly1305,
+                "c9b5f1c1d8d45fbeb3cd38107b313747f4df053f120e68041d8b50978a2bf35c",
+            ),
+            (
+                layer_tag::AT_REST,
+                "",
+                CipherSuite::Aes128Gcm,
+                "70b42920fd314d8edf05a2eab73af29d",
+            ),
+        ];
+        for (tag, context_id, suite, expected) in cases {
+            let key = derive_key(
+                &partition,
+                "cell-a",
+                tag,
+                context_id,
+                suite,
+                KdfHash::Sha256,
+                None,
+            )
+            .unwrap();
+            assert_eq!(
+                hex(key.as_bytes()),
+                expected,
+                "{tag}/{context_id}/{suite:?}"
+            );
+        }
+
+        // HKDF-SHA384 and HKDF-SHA512 over the same inputs.
+        for (kdf, expected) in [
+            (
+                KdfHash::Sha384,
+                "0a9cf0ad9292ff2dcb81ab8cf763184c68141688146d7b8c0f2b4c35741033f1",
+            ),
+            (
+                KdfHash::Sha512,
+                "b5baca1cb58659bf74ba9b5a3689dd5c113ccc46e3c567ac2a313277b69e008c",
+            ),
+        ] {
+            let key = derive_key(
+                &partition,
+                "cell-a",
+                layer_tag::AT_REST,
+                "",
+                CipherSuite::Aes256Gcm,
+                kdf,
+                None,
+            )
+            .unwrap();
+            assert_eq!(hex(key.as_bytes()), expected, "{kdf:?}");
+        }
+
+        let audit = derive_audit_key(&master).unwrap();
+        assert_eq!(
+            hex(audit.as_bytes()),
+            "b0a72df024a311101d303774ba8444c9e10e06c23677b25c6c84f4490866d70b"
+        );
+    }
+
+    #[test]
+    fn test_key_ring_primary_and_removal() {
+        let mut ring = KeyRing::new(0, MasterKey::from_bytes([1u8; 32]));
+        ring.insert(1, MasterKey::from_bytes([2u8; 32]));
+        assert!(matches!(
+            ring.set_primary(7),
+            Err(HexvaultError::UnknownKeyId(7))
+        ));
+        ring.set_primary(1).unwrap();
+
+        // Primary first, each tagged with its ID; the ID is not mixed in.
+        let keys = ring.derive_partition_keys("p1").unwrap();
+        assert_eq!(keys.iter().map(|k| k.key_id()).collect::<Vec<_>>(), [1, 0]);
+        let lone = derive_partition_key(&MasterKey::from_bytes([1u8; 32]), "p1").unwrap();
+        assert_eq!(keys[1].as_bytes(), lone.as_bytes());
+
+        assert!(ring.remove(1).is_err());
+        ring.remove(0).unwrap();
+        assert_eq!(ring.ids().collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn test_domain_separates_partition_keys() {
+        let master = || MasterKey::from_bytes([3u8; 32]);
+        let derive = |ring: KeyRing| ring.derive_partition_keys("p1").unwrap().remove(0);
+
+        let plain = derive(KeyRing::new(0, master()));
+        let lone = derive_partition_key(&master(), "p1").unwrap();
+        assert_eq!(plain.as_bytes(), lone.as_bytes());
+
+        let a = derive(KeyRing::with_domain(0, master(), "tenant-a"));
+        let again = derive(KeyRing::with_domain(0, master(), "tenant-a"));
+        let b = derive(KeyRing::with_domain(0, master(), "tenant-b"));
+        assert_eq!(a.as_bytes(), again.as_bytes());
+        assert_ne!(a.as_bytes(), b.as_bytes());
+        assert_ne!(a.as_bytes(), plain.as_bytes());
+    }
+
+    #[cfg(feature = "unsafe-key-export")]
+    #[test]
+    fn test_expose_secret_roundtrips() {
+        let master = vector_master();
+        let exported = master.expose_secret();
+        let restored = MasterKey::from_bytes(*exported);
+        assert_eq!(restored.as_bytes(), master.as_bytes());
+    }
 }
@@ -14,20 +14,41 @@
 //! is `pub(crate)` at most.
 
 // Module declarations.
+#[cfg(feature = "async")]
+pub mod async_vault;
 pub mod audit;
 pub mod cell;
+pub mod clock;
 pub(crate) mod crypto;
 pub mod edge;
 pub mod error;
 pub mod keys;
+pub mod migration;
+#[cfg(debug_assertions)]
+pub(crate) mod nonce_check;
 pub mod partition;
+pub mod secret;
+pub(crate) mod snapshot;
 pub mod stack;
+pub mod stream;
 
 // ---------------------------------------------------------------------------
 // Public API — Phase 2 surface
 // ---------------------------------------------------------------------------
 
-use keys::MasterKey;
+#[cfg(feature = "insecure-deterministic")]
+pub use crypto::encrypt_with_nonce;
+pub use crypto::{
+    decrypt_framed, encrypt_framed, CipherImpl, CipherSuite, KdfHash, TagLength, FRAME_LEN,
+    MAX_CUSTOM_SUITE_ID, MIN_CUSTOM_SUITE_ID,
+};
+pub use secret::SecretBytes;
+
+#[cfg(feature = "async")]
+pub use async_vault::AsyncVault;
+
+use clock::{Clock, SystemClock};
+use keys::{KeyRing, MasterKey};
 
 /// Generate a cryptographically secure master key.
 ///
@@ -41,47 +62,261 @@ pub fn generate_master_key() -> Result<MasterKey, error::HexvaultError> {
     Ok(MasterKey::from_bytes(bytes))
 }
 
+/// Register a custom cipher under suite ID `id` for the whole process, so
+/// payloads can be sealed with `CipherSuite::Custom(id)` and opened from
+/// their header by every vault.
+///
+/// The registry is process-wide, like the built-in suites, which are
+/// entries in it: cells encrypt without a reference to any vault.
+/// Registrations are permanent, and the cipher's name is mixed into key
+/// derivation, so register the same cipher under the same ID and name in
+/// every process that opens its ciphertext.
+///
+/// # Errors
+///
+/// Returns `HexvaultError::InvalidCipherSuite` if `id` is outside
+/// `MIN_CUSTOM_SUITE_ID..=MAX_CUSTOM_SUITE_ID` or already registered, if a
+/// registered cipher has the same name, or if the cipher takes keys shorter
+/// than 16 or longer than 32 bytes, or nonces other than 12.
+pub fn register_global_cipher(
+    id: u8,
+    cipher: std::sync::Arc<dyn CipherImpl>,
+) -> Result<(), error::HexvaultError> {
+    crypto::register_cipher(id, cipher)
+}
+
 // ---------------------------------------------------------------------------
 // Phase 4 API — Vault Wrapper
 // ---------------------------------------------------------------------------
 
-use audit::AuditLog;
-use cell::Cell;
+use audit::{AuditEvent, AuditLog, AuditRecord};
+use cell::{Cell, CellId, PayloadInfo};
 use partition::Partition;
-use stack::{Layer, TokenResolver};
+use stack::{Layer, LayerGuard, TokenResolver};
 
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
 use std::sync::Arc;
 
+use zeroize::Zeroize;
+
 /// The high-level entry point for managing cells and traversals.
 ///
-/// Holds the master key, the central audit log, and token resolver.
+/// Holds the master key ring, the central audit log, any per-cell audit
+/// logs, the token resolver, and the clock.
 pub struct Vault {
-    master_key: MasterKey,
+    key_ring: KeyRing,
     audit_log: AuditLog,
+    cell_logs: HashMap<CellId, AuditLog>,
     token_resolver: Arc<dyn TokenResolver>,
+    actor: Option<String>,
+    clock: Arc<dyn Clock>,
+    min_layer: Layer,
+    layer_guard: Option<Arc<dyn LayerGuard>>,
+    /// Shared with the partition keys this vault derives.
+    #[cfg(debug_assertions)]
+    nonce_detector: Arc<nonce_check::NonceDetector>,
 }
 
 impl Vault {
     /// Create a new Vault with the provided master key and token resolver.
+    ///
+    /// The key becomes the primary of a one-key ring, with ID 0.
     pub fn new(master_key: MasterKey, token_resolver: Arc<dyn TokenResolver>) -> Self {
+        Self::with_key_ring(KeyRing::new(0, master_key), token_resolver)
+    }
+
+    /// Create a new Vault whose keys are derived within `domain`; see
+    /// `KeyRing::with_domain`. Vaults with different domains share no keys,
+    /// even over the same master key and cell IDs. The domain must stay the
+    /// same for the life of the data.
+    pub fn new_with_domain(
+        master_key: MasterKey,
+        domain: &str,
+        token_resolver: Arc<dyn TokenResolver>,
+    ) -> Self {
+        Self::with_key_ring(KeyRing::with_domain(0, master_key, domain), token_resolver)
+    }
+
+    /// Create a new Vault that reads the current time from `clock` instead
+    /// of the system clock, e.g. a `clock::ManualClock` in tests.
+    pub fn with_clock(
+        master_key: MasterKey,
+        token_resolver: Arc<dyn TokenResolver>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let mut vault = Self::new(master_key, token_resolver);
+        vault.set_clock(clock);
+        vault
+    }
+
+    /// Create a new Vault over a ring of master keys. New payloads are
+    /// sealed under the ring's primary key.
+    pub fn with_key_ring(key_ring: KeyRing, token_resolver: Arc<dyn TokenResolver>) -> Self {
         Self {
-            master_key,
+            key_ring,
             audit_log: AuditLog::new(),
+            cell_logs: HashMap::new(),
             token_resolver,
+            actor: None,
+            clock: Arc::new(SystemClock),
+            min_layer: Layer::AtRest,
+            layer_guard: None,
+            #[cfg(debug_assertions)]
+            nonce_detector: Arc::default(),
         }
     }
 
+    /// Borrow the master key ring.
+    pub fn key_ring(&self) -> &KeyRing {
+        &self.key_ring
+    }
+
+    /// Mutably borrow the master key ring, e.g. to add a key and make it
+    /// primary. Partitions hold keys derived when they were created, so get
+    /// them again after changing the ring.
+    pub fn key_ring_mut(&mut self) -> &mut KeyRing {
+        &mut self.key_ring
+    }
+
+    /// Replace the clock the vault reads the current time from: for audit
+    /// timestamps, payload creation times, and expiry checks. Partitions
+    /// share the clock they were created with, so get them again after
+    /// changing it.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Refuse to seal anything below `layer`, e.g. `Layer::SessionBound`
+    /// for a deployment that only handles session-bound secrets. Seals,
+    /// traversals, merges, splits, and downgrades that would store a
+    /// payload below it fail with `HexvaultError::LayerPolicyViolation`.
+    ///
+    /// The default, `Layer::AtRest`, allows every layer. Payloads already
+    /// stored are not checked. Partitions keep the minimum they were
+    /// created with, so get them again after changing it.
+    pub fn set_min_layer(&mut self, layer: Layer) {
+        self.min_layer = layer;
+    }
+
+    /// The lowest layer the vault will seal at.
+    pub fn min_layer(&self) -> Layer {
+        self.min_layer
+    }
+
+    /// Consult `guard` before every layer the vault peels — opens,
+    /// traversals, re-seals, migrations, and streams alike — to enforce
+    /// per-layer authorisation beyond the layer keys. Partitions take the
+    /// guard when they are created, so get them again after setting it.
+    pub fn set_layer_guard(&mut self, guard: Arc<dyn LayerGuard>) {
+        self.layer_guard = Some(guard);
+    }
+
     /// Create or get a partition.
     pub fn get_partition(&self, id: &str) -> Result<Partition, error::HexvaultError> {
-        let key = keys::derive_partition_key(&self.master_key, id)?;
+        let keys = self.key_ring.derive_partition_keys(id)?;
+        #[cfg(debug_assertions)]
+        let keys = keys
+            .into_iter()
+            .map(|key| key.with_nonce_detector(Arc::clone(&self.nonce_detector)))
+            .collect();
         Ok(Partition::new(
             id.to_string(),
-            key,
+            keys,
             Arc::clone(&self.token_resolver),
+            Arc::clone(&self.clock),
+            self.min_layer,
+            self.layer_guard.clone(),
         ))
     }
 
+    /// Check, e.g. at startup, that every key in the ring seals and peels.
+    ///
+    /// A fixed test vector is sealed up to each layer under a throwaway
+    /// partition, cell, and context, then peeled and compared; nothing is
+    /// stored or audited, and the peeled copies are zeroised. Returns the
+    /// first error, or `HexvaultError::IntegrityCheckFailed` if a roundtrip
+    /// returns different bytes.
+    pub fn self_test(&self) -> Result<(), error::HexvaultError> {
+        const VECTOR: &[u8] = b"hexvault self-test vector";
+        let context =
+            stack::LayerContext::new(Some("self-test".try_into()?), Some("self-test".try_into()?))?;
+        for key in self.key_ring.derive_partition_keys("hexvault-self-test")? {
+            for &layer in Layer::all() {
+                let sealed = stack::seal(&key, "self-test", layer, &context, VECTOR)?;
+                let mut peeled = stack::peel(&key, "self-test", layer, &context, &sealed)?;
+                let matches = peeled == VECTOR;
+                peeled.zeroize();
+                if !matches {
+                    return Err(error::HexvaultError::IntegrityCheckFailed);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if `cell` holds a payload under `key`. Reading a missing
+    /// key fails with `HexvaultError::KeyNotFound`.
+    pub fn exists(&self, cell: &Cell, key: &str) -> bool {
+        cell.contains_key(key)
+    }
+
+    /// Read the format version, cipher suite, and layer of the payload
+    /// under `key`, without any key or context, e.g. to plan which payloads
+    /// a migration must upgrade. Returns `None` if `cell` has no such key.
+    pub fn peek(&self, cell: &Cell, key: &str) -> Option<cell::PayloadHeader> {
+        cell.payload(key).map(cell::Payload::header)
+    }
+
+    /// Create a cell with its own audit log.
+    ///
+    /// Besides the central log, every event touching the cell — as source
+    /// or destination of a traversal, or a downgrade or removal — is also
+    /// appended to a log scoped to that cell, readable via `cell_audit`.
+    /// Per-cell logs are keyed by cell ID, so audited cells should have IDs
+    /// that are unique across partitions.
+    pub fn create_audited_cell(
+        &mut self,
+        partition: &Partition,
+        id: CellId,
+    ) -> Result<Cell, error::HexvaultError> {
+        let cell = partition.create_cell(id)?;
+        self.cell_logs.entry(cell.id().to_string()).or_default();
+        Ok(cell)
+    }
+
+    /// The audit log scoped to a cell created with `create_audited_cell`,
+    /// containing only events that touched it.
+    pub fn cell_audit(&self, id: &str) -> Option<&AuditLog> {
+        self.cell_logs.get(id)
+    }
+
+    /// Set who is performing the vault's audited operations from now on,
+    /// e.g. the authenticated user or service ID. Recorded as `actor` on
+    /// every audit record the vault appends, unless a traversal names its
+    /// own in `TraversalOptions::actor`. `None` records no actor.
+    pub fn set_actor(&mut self, actor: Option<String>) {
+        self.actor = actor;
+    }
+
+    /// Append a record to the central log and to the logs of the cells it
+    /// touches, stamped with the vault's actor.
+    fn record(&mut self, mut record: AuditRecord) -> Result<(), error::HexvaultError> {
+        record.actor = self.actor.clone();
+        self.mirror_to_cells(&record)?;
+        self.audit_log.push(record)?;
+        Ok(())
+    }
+
+    fn mirror_to_cells(&mut self, record: &AuditRecord) -> Result<(), error::HexvaultError> {
+        mirror_record(&mut self.cell_logs, record)
+    }
+
     /// Traverse data from one cell to another.
+    ///
+    /// Returns a copy of the audit record the traversal appended.
     #[allow(clippy::too_many_arguments)]
     pub fn traverse(
         &mut self,
@@ -93,36 +328,1061 @@ impl Vault {
         target_layer: Layer,
         source_token: &str,
         dest_token: &str,
-    ) -> Result<(), error::HexvaultError> {
+    ) -> Result<AuditRecord, error::HexvaultError> {
+        self.traverse_with_options(
+            source_partition,
+            source,
+            dest_partition,
+            dest,
+            key,
+            target_layer,
+            source_token,
+            dest_token,
+            edge::TraversalOptions::default(),
+        )
+    }
+
+    /// Traverse data from one cell to another with explicit options, e.g. to
+    /// re-seal the destination under a different cipher suite or to supply
+    /// application AAD for either side.
+    #[allow(clippy::too_many_arguments)]
+    pub fn traverse_with_options(
+        &mut self,
+        source_partition: &Partition,
+        source: &Cell,
+        dest_partition: &Partition,
+        dest: &mut Cell,
+        key: &str,
+        target_layer: Layer,
+        source_token: &str,
+        dest_token: &str,
+        options: edge::TraversalOptions,
+    ) -> Result<AuditRecord, error::HexvaultError> {
+        target_layer.check_min(self.min_layer)?;
+        if let Some(min) = options.min_dest_layer {
+            target_layer.check_min(min)?;
+        }
         let source_ctx = self.token_resolver.resolve(source_token)?;
         let dest_ctx = self.token_resolver.resolve(dest_token)?;
 
-        edge::traverse(
+        let record = edge::traverse(
             &mut self.audit_log,
             edge::TraversalRequest {
-                source_partition_key: source_partition.key(),
+                source_partition_key: source_partition.key_for(source, key)?,
+                dest_partition_key: dest_partition.key(),
+                source,
+                dest,
+                key,
+                dest_key: None,
+                target_layer,
+                source_ctx: &source_ctx,
+                dest_ctx: &dest_ctx,
+                dest_suite: options.dest_suite,
+                source_aad: &options.source_aad,
+                source_guard: source_partition.peel_options().guard,
+                dest_aad: &options.dest_aad,
+                actor: options.actor.as_deref().or(self.actor.as_deref()),
+                now: self.clock.now(),
+            },
+        )?;
+
+        self.mirror_to_cells(&record)?;
+        Ok(record)
+    }
+
+    /// Append a traversal record that `edge::traverse` built against a
+    /// scratch log, as `AsyncVault` does so that it can peel and re-seal
+    /// without holding the vault. Returns the stored copy, with its `seq`
+    /// and `entry_hash`.
+    #[cfg(feature = "async")]
+    pub(crate) fn append_traversal(
+        &mut self,
+        record: AuditRecord,
+    ) -> Result<AuditRecord, error::HexvaultError> {
+        let record = self.audit_log.push(record)?.clone();
+        self.mirror_to_cells(&record)?;
+        Ok(record)
+    }
+
+    /// Move a payload between two vaults with different master keys, e.g.
+    /// to hand data from one service's vault to another's.
+    ///
+    /// The payload is peeled with `source_token`, resolved by the source
+    /// vault, and re-sealed with `dest_token`, resolved by the destination
+    /// vault. `source_partition` must come from `source_vault` and
+    /// `dest_partition` from `dest_vault`; each side only ever uses keys
+    /// derived from its own master key, so neither key reaches the other
+    /// vault. The plaintext is transient, as in `traverse`, and
+    /// `target_layer` is checked against the destination's minimum layer.
+    ///
+    /// The traversal is recorded in both vaults' audit logs (and per-cell
+    /// logs), timestamped by the source vault's clock. Returns the source
+    /// and destination records, in that order.
+    #[allow(clippy::too_many_arguments)]
+    pub fn traverse_between_vaults(
+        source_vault: &mut Vault,
+        source_partition: &Partition,
+        source: &Cell,
+        dest_vault: &mut Vault,
+        dest_partition: &Partition,
+        dest: &mut Cell,
+        key: &str,
+        target_layer: Layer,
+        source_token: &str,
+        dest_token: &str,
+    ) -> Result<(AuditRecord, AuditRecord), error::HexvaultError> {
+        target_layer.check_min(dest_vault.min_layer)?;
+        let source_ctx = source_vault.token_resolver.resolve(source_token)?;
+        let dest_ctx = dest_vault.token_resolver.resolve(dest_token)?;
+
+        let record = edge::traverse(
+            &mut source_vault.audit_log,
+            edge::TraversalRequest {
+                source_partition_key: source_partition.key_for(source, key)?,
                 dest_partition_key: dest_partition.key(),
                 source,
                 dest,
                 key,
+                dest_key: None,
                 target_layer,
                 source_ctx: &source_ctx,
                 dest_ctx: &dest_ctx,
+                dest_suite: None,
+                source_aad: &[],
+                source_guard: source_partition.peel_options().guard,
+                dest_aad: &[],
+                actor: source_vault.actor.as_deref(),
+                now: source_vault.clock.now(),
+            },
+        )?;
+        source_vault.mirror_to_cells(&record)?;
+
+        let dest_record = dest_vault.audit_log.push(record.clone())?.clone();
+        dest_vault.mirror_to_cells(&dest_record)?;
+        Ok((record, dest_record))
+    }
+
+    /// Fold every payload of `from` into `into`, e.g. to consolidate tenants.
+    ///
+    /// Each payload is peeled with `from_token` and re-sealed into `into`
+    /// with `into_token` at the layer and suite it had before — keys are
+    /// cell-scoped, so nothing can be copied as ciphertext. The plaintext is
+    /// transient per payload, and every moved payload gets its own traversal
+    /// record. Key collisions are resolved by `on_conflict`.
+    ///
+    /// `from` is left untouched; drop it once the merge succeeds. Payloads
+    /// are moved in key order and the merge stops at the first error, with
+    /// the payloads already moved staying in `into`. Returns the number of
+    /// payloads moved.
+    #[allow(clippy::too_many_arguments)]
+    pub fn merge_cell(
+        &mut self,
+        from_partition: &Partition,
+        from: &Cell,
+        into_partition: &Partition,
+        into: &mut Cell,
+        from_token: &str,
+        into_token: &str,
+        on_conflict: edge::ConflictPolicy,
+    ) -> Result<usize, error::HexvaultError> {
+        let source_ctx = self.token_resolver.resolve(from_token)?;
+        let dest_ctx = self.token_resolver.resolve(into_token)?;
+
+        let mut keys: Vec<&str> = from.keys().collect();
+        keys.sort_unstable();
+
+        let mut moved = 0;
+        for key in keys {
+            let Some(dest_key) = on_conflict.resolve(into, key) else {
+                continue;
+            };
+            let Some(target_layer) = from.payload(key).map(|payload| payload.sealed_at) else {
+                continue;
+            };
+            target_layer.check_min(self.min_layer)?;
+
+            let record = edge::traverse(
+                &mut self.audit_log,
+                edge::TraversalRequest {
+                    source_partition_key: from_partition.key_for(from, key)?,
+                    dest_partition_key: into_partition.key(),
+                    source: from,
+                    dest: into,
+                    key,
+                    dest_key: Some(&dest_key),
+                    target_layer,
+                    source_ctx: &source_ctx,
+                    dest_ctx: &dest_ctx,
+                    dest_suite: None,
+                    source_aad: &[],
+                    source_guard: from_partition.peel_options().guard,
+                    dest_aad: &[],
+                    actor: self.actor.as_deref(),
+                    now: self.clock.now(),
+                },
+            )?;
+            self.mirror_to_cells(&record)?;
+            moved += 1;
+        }
+        Ok(moved)
+    }
+
+    /// Gather payloads from several cells into `dest`, each under the
+    /// destination key its source names, e.g. to aggregate per-tenant
+    /// records into a reporting cell.
+    ///
+    /// Each source payload is peeled with its own token and re-sealed at
+    /// `target_layer` with `dest_token`, with one traversal record per
+    /// payload. Keys already taken in `dest`, including by an earlier
+    /// source in the same call, are resolved by `on_conflict`. Sources are
+    /// gathered in order and the gather stops at the first error, with the
+    /// payloads already gathered staying in `dest`. Returns the number of
+    /// payloads gathered.
+    pub fn gather<'a>(
+        &mut self,
+        sources: impl IntoIterator<Item = edge::GatherSource<'a>>,
+        dest_partition: &Partition,
+        dest: &mut Cell,
+        target_layer: Layer,
+        dest_token: &str,
+        on_conflict: edge::ConflictPolicy,
+    ) -> Result<usize, error::HexvaultError> {
+        target_layer.check_min(self.min_layer)?;
+        let dest_ctx = self.token_resolver.resolve(dest_token)?;
+
+        let mut gathered = 0;
+        for source in sources {
+            let Some(dest_key) = on_conflict.resolve(dest, source.dest_key) else {
+                continue;
+            };
+            let source_ctx = self.token_resolver.resolve(source.token)?;
+            let record = edge::traverse(
+                &mut self.audit_log,
+                edge::TraversalRequest {
+                    source_partition_key: source.partition.key_for(source.cell, source.key)?,
+                    dest_partition_key: dest_partition.key(),
+                    source: source.cell,
+                    dest,
+                    key: source.key,
+                    dest_key: Some(&dest_key),
+                    target_layer,
+                    source_ctx: &source_ctx,
+                    dest_ctx: &dest_ctx,
+                    dest_suite: None,
+                    source_aad: &[],
+                    source_guard: source.partition.peel_options().guard,
+                    dest_aad: &[],
+                    actor: self.actor.as_deref(),
+                    now: self.clock.now(),
+                },
+            )?;
+            self.mirror_to_cells(&record)?;
+            gathered += 1;
+        }
+        Ok(gathered)
+    }
+
+    /// Move the payloads of `from` that `select` picks into a new cell
+    /// `into`, e.g. to carve a data domain out along a new trust boundary.
+    ///
+    /// `select(key, info)` sees only non-secret metadata. Each selected
+    /// payload is peeled with `from_token` and re-sealed into the new cell
+    /// with `into_token` at the layer it had before, with one traversal
+    /// record per payload. Only once every selected payload has been moved
+    /// are they removed from `from`, and their ciphertext zeroised — if any
+    /// move fails, the error is returned and `from` is left untouched.
+    #[allow(clippy::too_many_arguments)]
+    pub fn split_cell(
+        &mut self,
+        partition: &Partition,
+        from: &mut Cell,
+        into: CellId,
+        select: impl Fn(&str, &PayloadInfo) -> bool,
+        from_token: &str,
+        into_token: &str,
+    ) -> Result<Cell, error::HexvaultError> {
+        let source_ctx = self.token_resolver.resolve(from_token)?;
+        let dest_ctx = self.token_resolver.resolve(into_token)?;
+        let mut dest = partition.create_cell(into)?;
+
+        let mut selected: Vec<(String, Layer)> = from
+            .keys()
+            .filter_map(|key| {
+                let info = from.payload(key)?.info();
+                select(key, &info).then(|| (key.to_string(), info.layer))
+            })
+            .collect();
+        selected.sort_unstable();
+        for (_, layer) in &selected {
+            layer.check_min(self.min_layer)?;
+        }
+
+        for (key, target_layer) in &selected {
+            let record = edge::traverse(
+                &mut self.audit_log,
+                edge::TraversalRequest {
+                    source_partition_key: partition.key_for(from, key)?,
+                    dest_partition_key: partition.key(),
+                    source: from,
+                    dest: &mut dest,
+                    key,
+                    dest_key: None,
+                    target_layer: *target_layer,
+                    source_ctx: &source_ctx,
+                    dest_ctx: &dest_ctx,
+                    dest_suite: None,
+                    source_aad: &[],
+                    source_guard: partition.peel_options().guard,
+                    dest_aad: &[],
+                    actor: self.actor.as_deref(),
+                    now: self.clock.now(),
+                },
+            )?;
+            self.mirror_to_cells(&record)?;
+        }
+
+        from.retain(|key, _| !selected.iter().any(|(moved, _)| moved == key));
+        Ok(dest)
+    }
+
+    /// Copy every payload of `src` into a new cell `new_id`, e.g. to stage a
+    /// test copy of production data under the same master key.
+    ///
+    /// Keys are cell-scoped, so each payload is peeled with `src_token` and
+    /// re-sealed into the new cell with `dest_token` at the layer and suite
+    /// it had before, with one traversal record per payload. The label and
+    /// schema version are copied too. `src` is left untouched; unlike
+    /// `split_cell`, both cells stay in use. The clone is only returned once
+    /// every payload has been copied.
+    pub fn clone_cell(
+        &mut self,
+        partition: &Partition,
+        src: &Cell,
+        new_id: CellId,
+        src_token: &str,
+        dest_token: &str,
+    ) -> Result<Cell, error::HexvaultError> {
+        let source_ctx = self.token_resolver.resolve(src_token)?;
+        let dest_ctx = self.token_resolver.resolve(dest_token)?;
+        let mut dest = if src.schema_bound() {
+            Cell::with_bound_schema(new_id, src.schema_version())?
+        } else {
+            Cell::with_schema(new_id, src.schema_version())?
+        };
+        dest.set_label(src.label().map(str::to_string));
+
+        let mut keys: Vec<&str> = src.keys().collect();
+        keys.sort_unstable();
+
+        for key in keys {
+            let Some(target_layer) = src.payload(key).map(|payload| payload.sealed_at) else {
+                continue;
+            };
+            target_layer.check_min(self.min_layer)?;
+
+            let record = edge::traverse(
+                &mut self.audit_log,
+                edge::TraversalRequest {
+                    source_partition_key: partition.key_for(src, key)?,
+                    dest_partition_key: partition.key(),
+                    source: src,
+                    dest: &mut dest,
+                    key,
+                    dest_key: None,
+                    target_layer,
+                    source_ctx: &source_ctx,
+                    dest_ctx: &dest_ctx,
+                    dest_suite: None,
+                    source_aad: &[],
+                    source_guard: partition.peel_options().guard,
+                    dest_aad: &[],
+                    actor: self.actor.as_deref(),
+                    now: self.clock.now(),
+                },
+            )?;
+            self.mirror_to_cells(&record)?;
+        }
+        Ok(dest)
+    }
+
+    /// Seal the same plaintext under `key` into several cells, all or
+    /// nothing — e.g. to distribute a shared configuration secret.
+    ///
+    /// Each target names the cell's partition, the cell, the layer, and the
+    /// token for that layer. The plaintext is sealed independently for each
+    /// cell, under that cell's keys. Every seal is completed before any cell
+    /// is modified, so if one fails the error is returned and no cell has
+    /// changed.
+    pub fn seal_multi<'a>(
+        &self,
+        targets: impl IntoIterator<Item = (&'a Partition, &'a mut Cell, Layer, &'a str)>,
+        key: &str,
+        plaintext: &[u8],
+    ) -> Result<(), error::HexvaultError> {
+        cell::check_key(key)?;
+        let now = self.clock.now();
+        let mut sealed = Vec::new();
+        for (partition, cell, layer, token) in targets {
+            layer.check_min(self.min_layer)?;
+            let context = self.token_resolver.resolve(token)?;
+            let options = stack::SealOptions {
+                schema_version: cell.derivation_schema(),
+                ..stack::SealOptions::default()
+            };
+            let data = stack::seal_with_options(
+                partition.key(),
+                cell.id(),
+                layer,
+                &context,
+                plaintext,
+                &options,
+            )?;
+            let payload = cell::Payload {
+                data,
+                sealed_at: layer,
+                created_at: now,
+                key_id: partition.key().key_id(),
+                expires_at: None,
+                content_id: None,
+            };
+            sealed.push((cell, payload));
+        }
+
+        for (cell, payload) in sealed {
+            cell.insert_sealed(key, payload);
+        }
+        Ok(())
+    }
+
+    /// Seal the file at `src` into `dst` as a chunked stream, without
+    /// loading it into memory. See the `stream` module for the format.
+    ///
+    /// The stream is sealed up to `layer` for `cell_id` in `partition`, and
+    /// is not stored in any cell. `dst` is created or truncated; if sealing
+    /// fails it is removed rather than left half-written.
+    pub fn seal_file(
+        &self,
+        partition: &Partition,
+        cell_id: &str,
+        src: &Path,
+        dst: &Path,
+        layer: Layer,
+        token: &str,
+    ) -> Result<(), error::HexvaultError> {
+        layer.check_min(self.min_layer)?;
+        let context = self.token_resolver.resolve(token)?;
+        let input = BufReader::new(File::open(src)?);
+        stream::write_file_or_remove(dst, |output| {
+            stream::seal_stream(partition.key(), cell_id, layer, &context, input, output)
+        })
+    }
+
+    /// Open a file sealed by `seal_file` into `dst`.
+    ///
+    /// `dst` is created or truncated; if the stream is tampered with,
+    /// truncated, or cannot be peeled with `token`, it is removed so that no
+    /// partial plaintext is left behind.
+    pub fn open_file(
+        &self,
+        partition: &Partition,
+        cell_id: &str,
+        src: &Path,
+        dst: &Path,
+        token: &str,
+    ) -> Result<(), error::HexvaultError> {
+        let context = self.token_resolver.resolve(token)?;
+        let input = BufReader::new(File::open(src)?);
+        stream::write_file_or_remove(dst, |output| {
+            stream::open_stream_with(
+                partition.key(),
+                cell_id,
+                &context,
+                input,
+                output,
+                &partition.peel_options(),
+            )
+        })
+    }
+
+    /// Seal everything `reader` yields into `cell` under `key`, as a
+    /// chunked stream (see the `stream` module), without holding more than
+    /// a frame of plaintext at a time.
+    ///
+    /// The payload is sealed up to `layer` with `token`'s context, and
+    /// `Payload::is_stream` reports it. Only `open_writer` opens it without
+    /// buffering; `Partition::open` and every other cell operation open it
+    /// whole, and re-sealing keeps it a stream. Its `PayloadInfo::suite` is
+    /// `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::ReservedKey` if `key` starts with
+    /// `RESERVED_KEY_PREFIX`. Nothing is stored if reading or sealing fails.
+    pub fn seal_reader(
+        &self,
+        partition: &Partition,
+        cell: &mut Cell,
+        key: &str,
+        reader: impl Read,
+        layer: Layer,
+        token: &str,
+    ) -> Result<(), error::HexvaultError> {
+        layer.check_min(self.min_layer)?;
+        cell::check_key(key)?;
+        let context = self.token_resolver.resolve(token)?;
+        let mut data = Vec::new();
+        stream::seal_stream_with(
+            partition.key(),
+            cell.id(),
+            layer,
+            &context,
+            reader,
+            &mut data,
+            cell.derivation_schema(),
+        )?;
+        cell.insert_sealed(
+            key,
+            cell::Payload {
+                data,
+                sealed_at: layer,
+                created_at: self.clock.now(),
+                key_id: partition.key().key_id(),
+                expires_at: None,
+                content_id: None,
             },
+        );
+        Ok(())
+    }
+
+    /// Open a payload sealed by `seal_reader` into `writer`.
+    ///
+    /// The whole stream is authenticated — every frame, their order, and
+    /// the last frame's presence — before the first byte is written, so a
+    /// truncated or tampered payload writes nothing. This peels the stream
+    /// twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::KeyNotFound` if `key` is not in `cell`, and
+    /// otherwise the errors of `stream::open_stream`.
+    pub fn open_writer(
+        &self,
+        partition: &Partition,
+        cell: &Cell,
+        key: &str,
+        writer: impl Write,
+        token: &str,
+    ) -> Result<(), error::HexvaultError> {
+        let payload = cell
+            .payload(key)
+            .ok_or_else(|| error::HexvaultError::KeyNotFound(key.to_string()))?;
+        let partition_key = partition.key_for(cell, key)?;
+        let context = self.token_resolver.resolve(token)?;
+        let options = stack::PeelOptions {
+            schema_version: cell.derivation_schema(),
+            ..partition.peel_options()
+        };
+        stream::open_stream_with(
+            partition_key,
+            cell.id(),
+            &context,
+            payload.data.as_slice(),
+            io::sink(),
+            &options,
+        )?;
+        stream::open_stream_with(
+            partition_key,
+            cell.id(),
+            &context,
+            payload.data.as_slice(),
+            writer,
+            &options,
+        )
+    }
+
+    /// A fingerprint of the keys sealing `cell` up to `layer` in
+    /// `partition`, with `token`'s context.
+    ///
+    /// Capture it before a backup and compare it after restoring the master
+    /// key: equal fingerprints confirm the restored key derives the same
+    /// cell keys, without decrypting anything. It is a SHA-256 hash of
+    /// HMAC tags made with the layer keys, and reveals nothing about them.
+    /// Covers every cipher suite and KDF hash named in the cell's payload
+    /// headers (the defaults for an empty cell), and the cell's bound
+    /// schema version, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::MalformedCiphertext` if a payload's header
+    /// cannot be read.
+    pub fn fingerprint_cell(
+        &self,
+        partition: &Partition,
+        cell: &Cell,
+        layer: Layer,
+        token: &str,
+    ) -> Result<String, error::HexvaultError> {
+        let context = self.token_resolver.resolve(token)?;
+        let mut params = Vec::new();
+        for key in cell.keys() {
+            let Some(payload) = cell.payload(key) else {
+                continue;
+            };
+            // Stream frames are always sealed with the defaults.
+            if payload.is_stream() {
+                params.push((crypto::CipherSuite::default(), crypto::KdfHash::default()));
+                continue;
+            }
+            match (payload.cipher_suite(), payload.kdf_hash()) {
+                (Some(suite), Some(kdf)) => params.push((suite, kdf)),
+                _ => return Err(error::HexvaultError::MalformedCiphertext),
+            }
+        }
+        if params.is_empty() {
+            params.push((crypto::CipherSuite::default(), crypto::KdfHash::default()));
+        }
+        params.sort_by_key(|&(suite, kdf)| (suite.id(), kdf.id()));
+        params.dedup();
+        stack::fingerprint(
+            partition.key(),
+            cell.id(),
+            layer,
+            &context,
+            &params,
+            cell.derivation_schema(),
         )
     }
 
+    /// The content ID `plaintext` gets when sealed into `partition` with
+    /// `SealOptions::content_id`, for looking it up with
+    /// `find_by_content_id`. Payloads sealed under an earlier master key
+    /// have IDs under that key, which this does not match.
+    pub fn content_id(
+        &self,
+        partition: &Partition,
+        plaintext: &[u8],
+    ) -> Result<cell::ContentId, error::HexvaultError> {
+        keys::content_id(partition.key(), plaintext)
+    }
+
+    /// Check a planned batch before doing any crypto.
+    ///
+    /// Each item pairs the layer an operation will seal or peel at with the
+    /// token it will use. Tokens are resolved and the resulting contexts
+    /// checked with `LayerContext::missing_for`; nothing is derived or
+    /// decrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns the resolver's error for the first token that does not
+    /// resolve, or `HexvaultError::IncompleteContext` naming the first item
+    /// whose context lacks an ID its layer needs.
+    pub fn validate_batch(&self, items: &[(Layer, &str)]) -> Result<(), error::HexvaultError> {
+        for (index, &(layer, token)) in items.iter().enumerate() {
+            let missing = self.token_resolver.resolve(token)?.missing_for(layer);
+            if !missing.is_empty() {
+                return Err(error::HexvaultError::IncompleteContext { index, missing });
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-seal a payload at a lower layer (a trust downgrade), e.g. to move
+    /// session-bound data to at-rest for archival.
+    ///
+    /// `current_token` must resolve to a context that can peel the payload's
+    /// current layer. The transient plaintext is zeroised, and the change is
+    /// recorded as an `AuditEvent::Downgrade` record.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidLayer` if `new_layer` is not strictly
+    /// below the layer the payload is currently sealed at.
+    pub fn downgrade(
+        &mut self,
+        partition: &Partition,
+        cell: &mut Cell,
+        key: &str,
+        new_layer: Layer,
+        current_token: &str,
+    ) -> Result<(), error::HexvaultError> {
+        new_layer.check_min(self.min_layer)?;
+        let context = self.token_resolver.resolve(current_token)?;
+        let from = cell.downgrade_with(
+            partition.key_for(cell, key)?,
+            key,
+            new_layer,
+            &context,
+            &partition.peel_options(),
+        )?;
+
+        self.record(AuditRecord::new(
+            AuditEvent::Downgrade { from },
+            cell.id(),
+            new_layer,
+            self.clock.now(),
+        ))?;
+        Ok(())
+    }
+
+    /// Re-seal a payload at its current layer with fresh nonces, without
+    /// changing its keys or plaintext, e.g. to bound how long any one nonce
+    /// stays in use.
+    ///
+    /// `token` must resolve to a context that can peel the payload. The
+    /// transient plaintext is zeroised, and the operation is recorded as an
+    /// `AuditEvent::Reseal` record.
+    pub fn reseal(
+        &mut self,
+        partition: &Partition,
+        cell: &mut Cell,
+        key: &str,
+        token: &str,
+    ) -> Result<(), error::HexvaultError> {
+        let context = self.token_resolver.resolve(token)?;
+        let partition_key = partition.key_for(cell, key)?;
+        let layer = cell.migrate(
+            partition_key,
+            partition_key,
+            key,
+            None,
+            &context,
+            &partition.peel_options(),
+        )?;
+
+        self.record(AuditRecord::new(
+            AuditEvent::Reseal,
+            cell.id(),
+            layer,
+            self.clock.now(),
+        ))?;
+        Ok(())
+    }
+
+    /// Re-seal every payload of `cell` that is not under the primary master
+    /// key, moving it to the primary — the migration step of a key
+    /// rotation. Returns the number of payloads re-sealed.
+    ///
+    /// Payloads keep their layer, suite, and creation time. Each is peeled
+    /// with `token`, and recorded as an `AuditEvent::Reseal` record. Stops
+    /// at the first error, with the payloads already moved staying moved.
+    pub fn rekey(
+        &mut self,
+        partition: &Partition,
+        cell: &mut Cell,
+        token: &str,
+    ) -> Result<usize, error::HexvaultError> {
+        let context = self.token_resolver.resolve(token)?;
+        let primary = partition.key();
+
+        let mut stale: Vec<String> = cell
+            .infos()
+            .filter(|(_, info)| info.key_id != primary.key_id())
+            .map(|(key, _)| key.to_string())
+            .collect();
+        stale.sort_unstable();
+
+        for key in &stale {
+            let layer = cell.migrate(
+                partition.key_for(cell, key)?,
+                primary,
+                key,
+                None,
+                &context,
+                &partition.peel_options(),
+            )?;
+            self.record(AuditRecord::new(
+                AuditEvent::Reseal,
+                cell.id(),
+                layer,
+                self.clock.now(),
+            ))?;
+        }
+        Ok(stale.len())
+    }
+
+    /// Start a batched, resumable migration of `cells` to the primary
+    /// master key — the long-running form of `rekey`. Payloads are peeled
+    /// with `token`. See `migration::Migration`.
+    pub fn migration<'a>(
+        &'a mut self,
+        cells: impl IntoIterator<Item = (&'a Partition, &'a mut Cell)>,
+        token: &'a str,
+    ) -> migration::Migration<'a> {
+        migration::Migration::new(self, cells.into_iter().collect(), token)
+    }
+
+    /// Remove every payload that `policy` marks for eviction.
+    ///
+    /// Walks `cells`, calling `policy(cell_id, key, info)` for each payload;
+    /// returning `true` evicts it. The policy sees only non-secret metadata,
+    /// so no layer context is required. Evicted ciphertext is zeroised and
+    /// each removal is recorded as an `AuditEvent::Remove` record. Returns
+    /// the number of payloads removed.
+    pub fn sweep_expired<'a>(
+        &mut self,
+        cells: impl IntoIterator<Item = &'a mut Cell>,
+        policy: impl Fn(&CellId, &str, &PayloadInfo) -> bool,
+    ) -> Result<usize, error::HexvaultError> {
+        let mut count = 0;
+        for cell in cells {
+            let cell_id: CellId = cell.id().to_string();
+            let removed = cell.retain(|key, info| !policy(&cell_id, key, info));
+            for (_, info) in &removed {
+                self.record(AuditRecord::new(
+                    AuditEvent::Remove,
+                    &cell_id,
+                    info.layer,
+                    self.clock.now(),
+                ))?;
+            }
+            count += removed.len();
+        }
+        Ok(count)
+    }
+
+    /// Destroy the vault and every payload in `cells`, e.g. on a suspected
+    /// full compromise. Returns the number of cells wiped.
+    ///
+    /// Every payload's ciphertext is zeroised and removed, with one
+    /// `AuditEvent::Remove` record each. Staged records are merged, the
+    /// audit sinks flushed and closed, and the in-memory audit logs
+    /// cleared; the master keys are zeroised as the vault drops. Partitions
+    /// already handed out hold their own derived keys; drop them too.
+    /// Everything is wiped even if auditing fails part-way, in which case
+    /// the first error is returned.
+    pub fn purge<'a>(
+        mut self,
+        cells: impl IntoIterator<Item = &'a mut Cell>,
+    ) -> Result<usize, error::HexvaultError> {
+        let mut result = Ok(());
+        let mut count = 0;
+        for cell in cells {
+            let cell_id: CellId = cell.id().to_string();
+            for (_, info) in cell.retain(|_, _| false) {
+                let recorded = self.record(AuditRecord::new(
+                    AuditEvent::Remove,
+                    &cell_id,
+                    info.layer,
+                    self.clock.now(),
+                ));
+                result = result.and(recorded);
+            }
+            cell.compact();
+            count += 1;
+        }
+
+        result = result.and(self.audit_log.flush_pending().map(drop));
+        result = result.and(self.flush_audit());
+        self.audit_log.close_sinks();
+        self.audit_log.drain();
+        self.cell_logs.clear();
+        result.map(|()| count)
+    }
+
+    /// Release memory left behind by removed payloads: compact each of
+    /// `cells` (see `Cell::compact`) and the vault's own per-cell audit log
+    /// map. Nothing is decrypted and no audit record is written.
+    pub fn compact_all<'a>(&mut self, cells: impl IntoIterator<Item = &'a mut Cell>) {
+        for cell in cells {
+            cell.compact();
+        }
+        self.cell_logs.shrink_to_fit();
+    }
+
+    /// Make every seal on the calling thread use a fixed, all-zero nonce,
+    /// so sealing the same input twice gives byte-identical output — for
+    /// golden-file tests of sealed payloads and bundles. `false` restores
+    /// random nonces. Pair it with a `clock::ManualClock` to fix creation
+    /// times too.
+    ///
+    /// **This is insecure.** Two different plaintexts sealed under the same
+    /// key and nonce leak their XOR and the authentication key, so never
+    /// enable it outside tests. Only available with the
+    /// `insecure-deterministic` feature, which is off by default. The
+    /// setting is per thread, so it cannot leak into seals made by other
+    /// tests running in parallel.
+    #[cfg(feature = "insecure-deterministic")]
+    pub fn set_deterministic_nonces(&self, enabled: bool) {
+        crypto::set_deterministic_nonces(enabled);
+    }
+
+    /// Panic if a seal under one of this vault's partitions reuses a nonce
+    /// under the same key, checking against the last `capacity` seals.
+    ///
+    /// This is a diagnostic for a broken random number generator, not a
+    /// security control. It covers partitions obtained from this vault,
+    /// including ones obtained before the call, and no other vault. Seeded
+    /// seals (`SealOptions::seed`) repeat by design and are not checked. In
+    /// release builds this does nothing.
+    pub fn enable_nonce_reuse_detection(&self, capacity: usize) {
+        #[cfg(debug_assertions)]
+        self.nonce_detector.enable(capacity);
+        #[cfg(not(debug_assertions))]
+        let _ = capacity;
+    }
+
+    /// Stop the check started by `enable_nonce_reuse_detection`. In release
+    /// builds this does nothing.
+    pub fn disable_nonce_reuse_detection(&self) {
+        #[cfg(debug_assertions)]
+        self.nonce_detector.disable();
+    }
+
+    /// Check that every payload of every target cell still decrypts, and
+    /// report each one that does not, e.g. after suspected disk corruption.
+    ///
+    /// Each target names the cell's partition, the cell, and a token that
+    /// resolves to a context for every layer its payloads were sealed at.
+    /// Every payload is verified as by `Partition::verify`; nothing stops
+    /// at a failure, and no plaintext is returned. Failures are reported as
+    /// `(cell ID, payload key, error)`, in target order and then key order.
+    /// An empty report means everything opened.
+    pub fn scan_integrity<'a>(
+        &self,
+        targets: impl IntoIterator<Item = (&'a Partition, &'a Cell, &'a str)>,
+    ) -> Vec<(CellId, String, error::HexvaultError)> {
+        let mut failures = Vec::new();
+        for (partition, cell, token) in targets {
+            let mut keys: Vec<&str> = cell.keys().collect();
+            keys.sort_unstable();
+            for key in keys {
+                if let Err(e) = partition.verify(cell, key, token) {
+                    failures.push((cell.id().to_string(), key.to_string(), e));
+                }
+            }
+        }
+        failures
+    }
+
+    /// Aggregate size and age statistics over `cells`, for capacity
+    /// planning. Computed in one pass from non-secret metadata; nothing is
+    /// decrypted.
+    pub fn stats<'a>(&self, cells: impl IntoIterator<Item = &'a Cell>) -> VaultStats {
+        let mut stats = VaultStats::default();
+        for cell in cells {
+            stats.cells += 1;
+            for (_, info) in cell.infos() {
+                stats.payloads += 1;
+                stats.by_layer[info.layer.index()] += 1;
+                stats.ciphertext_bytes += info.len;
+                stats.oldest = Some(
+                    stats
+                        .oldest
+                        .map_or(info.created_at, |t| t.min(info.created_at)),
+                );
+                stats.newest = Some(
+                    stats
+                        .newest
+                        .map_or(info.created_at, |t| t.max(info.created_at)),
+                );
+            }
+        }
+        stats
+    }
+
+    /// Every `(cell ID, key)` pair in `cells`, for sweeps that feed
+    /// `Partition::verify`, `Vault::peek`, `Vault::reseal`, and the like.
+    /// Cells are visited in the order given, keys in no particular order;
+    /// nothing is decrypted.
+    pub fn entries<'a>(
+        &self,
+        cells: impl IntoIterator<Item = &'a Cell>,
+    ) -> impl Iterator<Item = (&'a str, &'a str)> {
+        cells
+            .into_iter()
+            .flat_map(|cell| cell.keys().map(move |key| (cell.id(), key)))
+    }
+
+    /// Every `(cell ID, key)` pair in `cells` whose payload has content ID
+    /// `id`, e.g. to find duplicates before sealing a plaintext again.
+    /// Cells are visited in the order given; nothing is decrypted.
+    pub fn find_by_content_id<'a>(
+        &self,
+        cells: impl IntoIterator<Item = &'a Cell>,
+        id: &'a cell::ContentId,
+    ) -> impl Iterator<Item = (&'a str, &'a str)> {
+        cells.into_iter().flat_map(move |cell| {
+            cell.infos()
+                .filter(move |(_, info)| info.content_id.as_ref() == Some(id))
+                .map(move |(key, _)| (cell.id(), key))
+        })
+    }
+
     /// Inspect the audit log.
     pub fn audit_log(&self) -> &AuditLog {
         &self.audit_log
     }
 
+    /// Iterate over the central audit records appended after the one with
+    /// sequence number `seq`. See `AuditLog::iter_since`.
+    pub fn iter_audit_since(&self, seq: u64) -> std::slice::Iter<'_, AuditRecord> {
+        self.audit_log.iter_since(seq)
+    }
+
+    /// Iterate over the last `n` central audit records, oldest first. See
+    /// `AuditLog::tail`.
+    pub fn audit_tail(&self, n: usize) -> std::slice::Iter<'_, AuditRecord> {
+        self.audit_log.tail(n)
+    }
+
     /// Add a sink to receive a copy of every traversal record.
     /// Use this to persist the audit log to a file, S3, or other store.
     pub fn add_audit_sink(&mut self, sink: Box<dyn audit::AuditSink>) {
         self.audit_log.add_forward_sink(sink);
     }
 
+    /// Take every audit record held in memory, leaving the log empty but
+    /// with its chain tail intact. Sinks keep their copies. See
+    /// `AuditLog::drain`.
+    pub fn drain_audit(&mut self) -> Vec<AuditRecord> {
+        self.audit_log.drain()
+    }
+
+    /// Flush every audit sink so that all records logged so far are durable.
+    /// File sinks fsync. Call this before shutdown.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::Io` with the first sink failure. All sinks
+    /// are flushed regardless.
+    pub fn flush_audit(&mut self) -> Result<(), error::HexvaultError> {
+        Ok(self.audit_log.flush_sinks()?)
+    }
+
+    /// Flush and close every audit sink, releasing their file handles.
+    /// Later traversals are still recorded in the in-memory log.
+    pub fn close_audit(&mut self) {
+        self.audit_log.close_sinks();
+    }
+
+    /// Flush and close every audit sink, then forward later records to
+    /// `sinks` instead. See `AuditLog::set_forward_sinks`.
+    pub fn replace_audit_sinks(&mut self, sinks: Vec<Box<dyn audit::AuditSink>>) {
+        self.audit_log.set_forward_sinks(sinks);
+    }
+
+    /// Merge records staged through `audit_log().pending_appender()` into
+    /// the audit log. Returns the number of records merged. See
+    /// `AuditLog::flush_pending`.
+    ///
+    /// Like every record the vault appends, each is stamped with the
+    /// vault's actor and copied to the audit logs of the cells it names.
+    pub fn flush_pending_audit(&mut self) -> Result<usize, error::HexvaultError> {
+        let actor = &self.actor;
+        let cell_logs = &mut self.cell_logs;
+        self.audit_log.flush_pending_with(|record| {
+            record.actor = actor.clone();
+            mirror_record(cell_logs, record)
+        })
+    }
+
+    /// Counts of the central audit log's traversals per source cell,
+    /// destination cell, and layer. See `AuditLog::summarize`.
+    pub fn audit_summary(&self) -> audit::AuditSummary {
+        self.audit_log.summarize()
+    }
+
     /// Return the number of audit records logged so far.
     ///
     /// Convenience method equivalent to `vault.audit_log().len()`.
@@ -130,3 +1390,44 @@ impl Vault {
         self.audit_log.len()
     }
 }
+
+/// Copy `record` to the per-cell logs of its source and destination cells,
+/// once if they are the same cell.
+fn mirror_record(
+    cell_logs: &mut HashMap<CellId, AuditLog>,
+    record: &AuditRecord,
+) -> Result<(), error::HexvaultError> {
+    if let Some(log) = cell_logs.get_mut(&record.source_cell_id) {
+        log.push(record.clone())?;
+    }
+    if record.dest_cell_id != record.source_cell_id {
+        if let Some(log) = cell_logs.get_mut(&record.dest_cell_id) {
+            log.push(record.clone())?;
+        }
+    }
+    Ok(())
+}
+
+/// Aggregate statistics over a set of cells, returned by `Vault::stats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VaultStats {
+    /// The number of cells examined.
+    pub cells: usize,
+    /// The total number of payloads.
+    pub payloads: usize,
+    /// Payload counts indexed by `Layer::index()`; see `count_at`.
+    pub by_layer: [usize; 3],
+    /// The total size of the stored ciphertext, in bytes.
+    pub ciphertext_bytes: usize,
+    /// The earliest `created_at`, or `None` if there are no payloads.
+    pub oldest: Option<chrono::DateTime<chrono::Utc>>,
+    /// The latest `created_at`, or `None` if there are no payloads.
+    pub newest: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl VaultStats {
+    /// The number of payloads sealed at `layer`.
+    pub fn count_at(&self, layer: Layer) -> usize {
+        self.by_layer[layer.index()]
+    }
+}
@@ -0,0 +1,160 @@
+//! Resumable bulk migration.
+//!
+//! `Vault::rekey` moves one cell to the primary master key in a single
+//! call. A `Migration` does the same across many cells — optionally moving
+//! payloads to a new cipher suite as well — in batches, reporting progress
+//! after each one and recording a cursor: the last payload it completed.
+//! Payloads are visited in `(cell ID, key)` order, so a migration that was
+//! interrupted, even in another process, picks up after its cursor.
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::{AuditEvent, AuditRecord};
+use crate::cell::{Cell, CellId, Payload};
+use crate::crypto::CipherSuite;
+use crate::error::HexvaultError;
+use crate::partition::Partition;
+use crate::Vault;
+
+/// The number of payloads a migration re-seals between progress reports,
+/// unless set with `Migration::set_batch_size`.
+pub const DEFAULT_BATCH_SIZE: usize = 64;
+
+/// The last payload a migration completed. Persist it to resume the
+/// migration after an interruption.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MigrationCursor {
+    pub cell_id: CellId,
+    pub key: String,
+}
+
+/// Reported by `Migration::run` after each batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// Payloads re-sealed so far in this run.
+    pub migrated: usize,
+    /// Payloads this run has still to re-seal.
+    pub remaining: usize,
+    /// The last payload re-sealed.
+    pub cursor: MigrationCursor,
+}
+
+/// A batched, resumable re-seal of every payload in a set of cells that is
+/// not under the vault's primary master key, or not under the target suite
+/// if one is set. Created by `Vault::migration`.
+pub struct Migration<'a> {
+    vault: &'a mut Vault,
+    cells: Vec<(&'a Partition, &'a mut Cell)>,
+    token: &'a str,
+    suite: Option<CipherSuite>,
+    batch_size: usize,
+    cursor: Option<MigrationCursor>,
+}
+
+impl<'a> Migration<'a> {
+    pub(crate) fn new(
+        vault: &'a mut Vault,
+        cells: Vec<(&'a Partition, &'a mut Cell)>,
+        token: &'a str,
+    ) -> Self {
+        Self {
+            vault,
+            cells,
+            token,
+            suite: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+            cursor: None,
+        }
+    }
+
+    /// Also move every payload to `suite`, including payloads already
+    /// under the primary master key. Stream payloads keep the default suite
+    /// and are only moved to the primary key.
+    pub fn set_suite(&mut self, suite: CipherSuite) {
+        self.suite = Some(suite);
+    }
+
+    /// Report progress every `batch_size` payloads. Zero is treated as one.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1);
+    }
+
+    /// Skip every payload up to and including `cursor`, e.g. one saved from
+    /// an interrupted migration.
+    pub fn resume_from(&mut self, cursor: MigrationCursor) {
+        self.cursor = Some(cursor);
+    }
+
+    /// The last payload completed, if any.
+    pub fn cursor(&self) -> Option<&MigrationCursor> {
+        self.cursor.as_ref()
+    }
+
+    /// Re-seal every payload after the cursor that needs it, calling
+    /// `progress` after each batch. Returns the number of payloads
+    /// re-sealed.
+    ///
+    /// Each payload is peeled with the migration's token, keeps its layer,
+    /// KDF hash, and creation time, and is recorded as an
+    /// `AuditEvent::Reseal` record. Stops at the first error; the cursor
+    /// then names the last payload completed, so `run` can be called again
+    /// to carry on.
+    pub fn run(
+        &mut self,
+        mut progress: impl FnMut(MigrationProgress),
+    ) -> Result<usize, HexvaultError> {
+        let context = self.vault.token_resolver.resolve(self.token)?;
+
+        let mut pending: Vec<(MigrationCursor, usize)> = Vec::new();
+        for (index, (partition, cell)) in self.cells.iter().enumerate() {
+            let primary = partition.key().key_id();
+            for (key, info) in cell.infos() {
+                // Streams are always sealed under the default suite (see
+                // `Vault::seal_reader`), so only the master key moves them.
+                let is_stream = cell.payload(key).is_some_and(Payload::is_stream);
+                let stale = info.key_id != primary
+                    || self
+                        .suite
+                        .is_some_and(|suite| !is_stream && info.suite != Some(suite));
+                let cursor = MigrationCursor {
+                    cell_id: cell.id().to_string(),
+                    key: key.to_string(),
+                };
+                if stale && self.cursor.as_ref().is_none_or(|done| cursor > *done) {
+                    pending.push((cursor, index));
+                }
+            }
+        }
+        pending.sort_unstable();
+
+        let total = pending.len();
+        for (done, (cursor, index)) in pending.into_iter().enumerate() {
+            let (partition, cell) = &mut self.cells[index];
+            let layer = cell.migrate(
+                partition.key_for(cell, &cursor.key)?,
+                partition.key(),
+                &cursor.key,
+                self.suite,
+                &context,
+                &partition.peel_options(),
+            )?;
+            self.cursor = Some(cursor.clone());
+            self.vault.record(AuditRecord::new(
+                AuditEvent::Reseal,
+                &cursor.cell_id,
+                layer,
+                self.vault.clock.now(),
+            ))?;
+
+            let migrated = done + 1;
+            if migrated % self.batch_size == 0 || migrated == total {
+                progress(MigrationProgress {
+                    migrated,
+                    remaining: total - migrated,
+                    cursor,
+                });
+            }
+        }
+        Ok(total)
+    }
+}
@@ -0,0 +1,125 @@
+//! Nonce-reuse detection for debug builds.
+//!
+//! Random 96-bit nonces should never repeat under the same key, but a broken
+//! RNG would make them repeat silently — and a repeated GCM nonce leaks the
+//! XOR of the two plaintexts and the authentication key. When enabled, a
+//! `NonceDetector` remembers the most recent `(key fingerprint, nonce)` pairs
+//! sealed by `stack::seal` and panics on a repeat, so RNG misconfiguration
+//! is caught in development rather than by an attacker.
+//!
+//! Each vault owns one detector and hands it to the partition keys it
+//! derives, so enabling detection on one vault does not affect another.
+//! The module only exists in debug builds (`debug_assertions`); release
+//! builds contain no trace of it. Keys are identified by a truncated SHA-256
+//! fingerprint, never by their bytes.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::crypto::{self, NONCE_LEN};
+
+/// A one-way fingerprint of a derived key.
+pub(crate) type KeyId = [u8; 16];
+
+/// Fingerprint `key_bytes` with a truncated SHA-256 digest.
+pub(crate) fn key_id(key_bytes: &[u8]) -> KeyId {
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&crypto::sha256(key_bytes)[..16]);
+    id
+}
+
+/// One vault's detector. Off (`None`) until `enable` is called.
+#[derive(Default)]
+pub(crate) struct NonceDetector {
+    log: Mutex<Option<NonceLog>>,
+}
+
+/// A bounded record of recently used `(key, nonce)` pairs.
+pub(crate) struct NonceLog {
+    capacity: usize,
+    seen: HashSet<(KeyId, [u8; NONCE_LEN])>,
+    order: VecDeque<(KeyId, [u8; NONCE_LEN])>,
+}
+
+impl NonceLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Record a pair, evicting the oldest once full. Returns `false` if the
+    /// pair is already recorded.
+    pub(crate) fn insert(&mut self, key_id: KeyId, nonce: [u8; NONCE_LEN]) -> bool {
+        if !self.seen.insert((key_id, nonce)) {
+            return false;
+        }
+        self.order.push_back((key_id, nonce));
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+impl NonceDetector {
+    /// Start recording the last `capacity` pairs, replacing any previous
+    /// record.
+    pub(crate) fn enable(&self, capacity: usize) {
+        *self.lock() = Some(NonceLog::new(capacity));
+    }
+
+    /// Stop recording and forget every pair.
+    pub(crate) fn disable(&self) {
+        *self.lock() = None;
+    }
+
+    /// Record a pair if detection is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pair was already recorded — the nonce source is broken.
+    pub(crate) fn check(&self, key_id: KeyId, nonce: [u8; NONCE_LEN]) {
+        if let Some(log) = self.lock().as_mut() {
+            assert!(
+                log.insert(key_id, nonce),
+                "nonce reused under the same key: the random number generator is broken"
+            );
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<NonceLog>> {
+        // A panic from `check` poisons the lock; the record is still usable.
+        self.log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeat_is_detected() {
+        let mut log = NonceLog::new(4);
+        assert!(log.insert([1; 16], [0; NONCE_LEN]));
+        assert!(log.insert([2; 16], [0; NONCE_LEN]));
+        assert!(!log.insert([1; 16], [0; NONCE_LEN]));
+    }
+
+    #[test]
+    fn test_oldest_pairs_are_evicted() {
+        let mut log = NonceLog::new(2);
+        for n in 0..3u8 {
+            assert!(log.insert([0; 16], [n; NONCE_LEN]));
+        }
+        // [0; 12] was evicted, [2; 12] was not.
+        assert!(log.insert([0; 16], [0; NONCE_LEN]));
+        assert!(!log.insert([0; 16], [2; NONCE_LEN]));
+    }
+}
@@ -3,23 +3,115 @@
 //! A partition provides isolation between groups of cells. Keys for cells
 //! are derived from the partition key, enabling a two-level blast-radius containment.
 
-use crate::cell::{Cell, CellId};
+use chrono::{DateTime, Utc};
+
+use crate::cell::{Cell, CellId, PayloadInfo};
+use crate::clock::Clock;
 use crate::error::HexvaultError;
 use crate::keys::PartitionKey;
-use crate::stack::{Layer, TokenResolver};
+use crate::secret::SecretBytes;
+use crate::stack::{Layer, LayerGuard, PeelOptions, SealOptions, TokenResolver};
 
 use std::sync::Arc;
 
+/// How `Partition::seal_many` and `open_many` react to an item that fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchMode {
+    /// Stop at the first failure; later items are not attempted.
+    #[default]
+    FailFast,
+    /// Attempt every item and report each failure.
+    ContinueOnError,
+}
+
+/// The per-item results of `Partition::seal_many` or `open_many`, in input
+/// order. Items sealed before a failure stay sealed.
+#[derive(Debug)]
+pub struct BatchOutcome<T> {
+    /// One result per attempted item.
+    pub results: Vec<Result<T, HexvaultError>>,
+    /// Items not attempted because `BatchMode::FailFast` stopped the batch.
+    pub skipped: usize,
+}
+
+impl<T> BatchOutcome<T> {
+    /// Returns true if every item was attempted and succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.skipped == 0 && self.results.iter().all(Result::is_ok)
+    }
+
+    /// The number of items that succeeded.
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|r| r.is_ok()).count()
+    }
+
+    /// The index and error of every item that failed.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &HexvaultError)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.as_ref().err().map(|e| (i, e)))
+    }
+}
+
+/// Run `op` over `items` under `mode`.
+fn run_batch<I, T>(
+    items: impl IntoIterator<Item = I>,
+    mode: BatchMode,
+    mut op: impl FnMut(I) -> Result<T, HexvaultError>,
+) -> BatchOutcome<T> {
+    let mut results = Vec::new();
+    let mut items = items.into_iter();
+    for item in items.by_ref() {
+        let result = op(item);
+        let failed = result.is_err();
+        results.push(result);
+        if failed && mode == BatchMode::FailFast {
+            break;
+        }
+    }
+    BatchOutcome {
+        results,
+        skipped: items.count(),
+    }
+}
+
 /// A partition provides isolation between groups of cells.
+///
+/// Holds the partition key derived from every master key in the vault's
+/// ring: new payloads are sealed under the primary, and each payload is
+/// opened with the key matching the ID it was sealed under.
 pub struct Partition {
     id: String,
-    key: PartitionKey,
+    /// Primary first.
+    keys: Vec<PartitionKey>,
     resolver: Arc<dyn TokenResolver>,
+    /// The vault's clock, for creation times and expiry checks.
+    clock: Arc<dyn Clock>,
+    /// The vault's minimum seal layer.
+    min_layer: Layer,
+    /// The vault's layer guard, consulted on every peel.
+    guard: Option<Arc<dyn LayerGuard>>,
 }
 
 impl Partition {
-    pub(crate) fn new(id: String, key: PartitionKey, resolver: Arc<dyn TokenResolver>) -> Self {
-        Self { id, key, resolver }
+    pub(crate) fn new(
+        id: String,
+        keys: Vec<PartitionKey>,
+        resolver: Arc<dyn TokenResolver>,
+        clock: Arc<dyn Clock>,
+        min_layer: Layer,
+        guard: Option<Arc<dyn LayerGuard>>,
+    ) -> Self {
+        debug_assert!(!keys.is_empty());
+        Self {
+            id,
+            keys,
+            resolver,
+            clock,
+            min_layer,
+            guard,
+        }
     }
 
     /// Return the partition's ID.
@@ -27,14 +119,45 @@ impl Partition {
         &self.id
     }
 
-    /// Borrow the partition's key.
+    /// Borrow the partition key under the primary master key.
     pub(crate) fn key(&self) -> &PartitionKey {
-        &self.key
+        &self.keys[0]
+    }
+
+    /// Default peel options carrying the vault's layer guard, for every peel
+    /// made on the vault's behalf.
+    pub(crate) fn peel_options(&self) -> PeelOptions {
+        PeelOptions {
+            guard: self.guard.clone(),
+            ..PeelOptions::default()
+        }
+    }
+
+    /// Borrow the partition key that payload `key` of `cell` was sealed
+    /// under. A missing payload gets the primary key, so the caller reports
+    /// it as missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::UnknownKeyId` if the payload was sealed
+    /// under a master key no longer in the ring.
+    pub(crate) fn key_for(&self, cell: &Cell, key: &str) -> Result<&PartitionKey, HexvaultError> {
+        let Some(payload) = cell.payload(key) else {
+            return Ok(self.key());
+        };
+        self.keys
+            .iter()
+            .find(|k| k.key_id() == payload.key_id)
+            .ok_or(HexvaultError::UnknownKeyId(payload.key_id))
     }
 
     /// Create a new isolated cell within this partition.
-    pub fn create_cell(&self, id: CellId) -> Cell {
-        Cell::new(id)
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::InvalidCellId` if `id` is empty.
+    pub fn create_cell(&self, id: CellId) -> Result<Cell, HexvaultError> {
+        Cell::try_new(id)
     }
 
     /// Seal a payload into a specific cell.
@@ -46,13 +169,235 @@ impl Partition {
         layer: Layer,
         token: &str,
     ) -> Result<(), HexvaultError> {
+        self.seal_with_options(cell, key, plaintext, layer, token, &SealOptions::default())
+    }
+
+    /// Seal a payload into a specific cell with explicit sealing options,
+    /// e.g. a non-default cipher suite or application AAD.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::LayerPolicyViolation` if `layer` is below the
+    /// vault's minimum layer.
+    pub fn seal_with_options(
+        &self,
+        cell: &mut Cell,
+        key: &str,
+        plaintext: &[u8],
+        layer: Layer,
+        token: &str,
+        options: &SealOptions,
+    ) -> Result<(), HexvaultError> {
+        layer.check_min(self.min_layer)?;
         let context = self.resolver.resolve(token)?;
-        cell.store(&self.key, key, plaintext, layer, &context)
+        cell.store_at(
+            self.key(),
+            key,
+            plaintext,
+            layer,
+            &context,
+            options,
+            self.clock.now(),
+        )
+    }
+
+    /// Seal a payload that stops opening at `expires_at`.
+    ///
+    /// The expiry is stored with the payload and bound into every layer's
+    /// AAD, so it cannot be extended or stripped without breaking
+    /// decryption. Once it passes, every read fails with
+    /// `HexvaultError::Expired`; `Vault::sweep_expired` with a policy on
+    /// `PayloadInfo::expires_at` removes such payloads eagerly.
+    pub fn seal_with_ttl(
+        &self,
+        cell: &mut Cell,
+        key: &str,
+        plaintext: &[u8],
+        layer: Layer,
+        token: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), HexvaultError> {
+        let options = SealOptions {
+            expires_at: Some(expires_at),
+            ..SealOptions::default()
+        };
+        self.seal_with_options(cell, key, plaintext, layer, token, &options)
+    }
+
+    /// Seal several payloads into `cell` at `layer` with one token.
+    ///
+    /// Each item succeeds or fails on its own; see `BatchMode`. A payload
+    /// that fails to seal leaves any previous payload under its key intact.
+    ///
+    /// # Errors
+    ///
+    /// Fails before sealing anything if `layer` is below the vault's
+    /// minimum layer or `token` does not resolve.
+    pub fn seal_many<'a>(
+        &self,
+        cell: &mut Cell,
+        items: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+        layer: Layer,
+        token: &str,
+        mode: BatchMode,
+    ) -> Result<BatchOutcome<()>, HexvaultError> {
+        layer.check_min(self.min_layer)?;
+        let context = self.resolver.resolve(token)?;
+        let options = SealOptions::default();
+        let now = self.clock.now();
+        Ok(run_batch(items, mode, |(key, plaintext)| {
+            cell.store_at(self.key(), key, plaintext, layer, &context, &options, now)
+        }))
+    }
+
+    /// Open several payloads of `cell` with one token. See `BatchMode`.
+    ///
+    /// # Errors
+    ///
+    /// Fails before opening anything if `token` does not resolve.
+    pub fn open_many<'a>(
+        &self,
+        cell: &Cell,
+        keys: impl IntoIterator<Item = &'a str>,
+        token: &str,
+        mode: BatchMode,
+    ) -> Result<BatchOutcome<Vec<u8>>, HexvaultError> {
+        let context = self.resolver.resolve(token)?;
+        let options = self.peel_options();
+        let now = self.clock.now();
+        Ok(run_batch(keys, mode, |key| {
+            cell.retrieve_at(self.key_for(cell, key)?, key, &context, &options, now)
+        }))
     }
 
     /// Retrieve a payload from a cell.
     pub fn open(&self, cell: &Cell, key: &str, token: &str) -> Result<Vec<u8>, HexvaultError> {
+        self.open_with_options(cell, key, token, &PeelOptions::default())
+    }
+
+    /// Retrieve a payload with the first of `tokens` that opens it, e.g.
+    /// during session rotation when it may be sealed under either the old
+    /// or the new session.
+    ///
+    /// Tokens are tried in order. A token whose context fails to
+    /// authenticate the payload is skipped; which tokens failed is not
+    /// reported. Any other error, e.g. a token that fails to resolve or an
+    /// expired payload, is returned as is without trying the rest.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::KeyNotFound` if `key` is not in `cell`,
+    /// `HexvaultError::DecryptionFailure` if no token opens the payload,
+    /// and the first other error a token hits.
+    pub fn open_any(
+        &self,
+        cell: &Cell,
+        key: &str,
+        tokens: &[&str],
+    ) -> Result<Vec<u8>, HexvaultError> {
+        if !cell.contains_key(key) {
+            return Err(HexvaultError::KeyNotFound(key.to_string()));
+        }
+        let partition_key = self.key_for(cell, key)?;
+        let options = self.peel_options();
+        let now = self.clock.now();
+        for token in tokens {
+            let context = self.resolver.resolve(token)?;
+            match cell.retrieve_at(partition_key, key, &context, &options, now) {
+                Err(HexvaultError::DecryptionFailure) => continue,
+                result => return result,
+            }
+        }
+        Err(HexvaultError::DecryptionFailure)
+    }
+
+    /// Retrieve a payload as `SecretBytes`, which zeroises the plaintext
+    /// when dropped.
+    pub fn open_guarded(
+        &self,
+        cell: &Cell,
+        key: &str,
+        token: &str,
+    ) -> Result<SecretBytes, HexvaultError> {
+        self.open(cell, key, token).map(SecretBytes::new)
+    }
+
+    /// Retrieve a payload together with its metadata, e.g. to pick a cache
+    /// TTL from `created_at` without a separate `Cell::metadata` lookup.
+    ///
+    /// The info describes the same payload the plaintext was peeled from.
+    pub fn open_with_info(
+        &self,
+        cell: &Cell,
+        key: &str,
+        token: &str,
+    ) -> Result<(Vec<u8>, PayloadInfo), HexvaultError> {
+        let plaintext = self.open(cell, key, token)?;
+        let info = cell
+            .payload(key)
+            .ok_or_else(|| HexvaultError::KeyNotFound(key.to_string()))?
+            .info();
+        Ok((plaintext, info))
+    }
+
+    /// Retrieve a payload with explicit peel options.
+    ///
+    /// Payloads sealed with application AAD only open when `options.aad`
+    /// holds the identical bytes. The vault's layer guard applies unless
+    /// `options.guard` sets another.
+    pub fn open_with_options(
+        &self,
+        cell: &Cell,
+        key: &str,
+        token: &str,
+        options: &PeelOptions,
+    ) -> Result<Vec<u8>, HexvaultError> {
+        let context = self.resolver.resolve(token)?;
+        let partition_key = self.key_for(cell, key)?;
+        let options = PeelOptions {
+            guard: options.guard.clone().or_else(|| self.guard.clone()),
+            ..options.clone()
+        };
+        cell.retrieve_at(partition_key, key, &context, &options, self.clock.now())
+    }
+
+    /// Retrieve a payload into a caller-provided buffer.
+    ///
+    /// For hot read paths: every layer is peeled in place in `out`, so
+    /// keeping it across calls avoids the per-call allocation of `open`.
+    pub fn open_into(
+        &self,
+        cell: &Cell,
+        key: &str,
+        token: &str,
+        out: &mut Vec<u8>,
+    ) -> Result<(), HexvaultError> {
+        let context = self.resolver.resolve(token)?;
+        let partition_key = self.key_for(cell, key)?;
+        let options = self.peel_options();
+        cell.peel_at(
+            partition_key,
+            key,
+            &context,
+            &options,
+            self.clock.now(),
+            out,
+        )
+    }
+
+    /// Confirm a payload still decrypts, without returning the plaintext.
+    ///
+    /// Intended for scheduled integrity sweeps: a failure means the payload
+    /// is missing, tampered with, corrupted, or the token no longer resolves
+    /// to the context it was sealed under.
+    pub fn verify(&self, cell: &Cell, key: &str, token: &str) -> Result<(), HexvaultError> {
         let context = self.resolver.resolve(token)?;
-        cell.retrieve(&self.key, key, &context)
+        cell.verify_at(
+            self.key_for(cell, key)?,
+            key,
+            &context,
+            &self.peel_options(),
+            self.clock.now(),
+        )
     }
 }
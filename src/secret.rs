@@ -0,0 +1,63 @@
+//! Plaintext held under the same discipline as key material.
+//!
+//! `SecretBytes` owns a decrypted payload. Like the key types in `keys`, it
+//! is not `Clone`, its `Debug` output never shows the contents, and its
+//! memory is zeroised on drop — so callers get scrubbing without having to
+//! remember to do it.
+
+use std::fmt;
+use std::ops::Deref;
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Decrypted bytes that are zeroised when dropped.
+///
+/// - Derefs to `&[u8]` for reading.
+/// - Not `Clone`. Copying the contents out requires an explicit
+///   `to_vec()`, after which the copy is the caller's responsibility.
+/// - `Debug` prints only the length.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct SecretBytes {
+    bytes: Vec<u8>,
+}
+
+impl SecretBytes {
+    /// Take ownership of a plaintext buffer.
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for SecretBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes([REDACTED; {}])", self.bytes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_redacts_contents() {
+        let secret = SecretBytes::new(b"hunter2".to_vec());
+        assert_eq!(&*secret, b"hunter2");
+        let debug = format!("{secret:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains('7'));
+    }
+}
@@ -0,0 +1,188 @@
+//! Canonical on-disk format for cells.
+//!
+//! A snapshot is a self-describing binary frame:
+//!
+//! ```text
+//! [ magic "HXVC" (4) ][ version (1) ][ body length (4, BE) ][ body ][ CRC32 (4, BE) ]
+//! ```
+//!
+//! The CRC32 (IEEE) covers everything before it, so a truncated or
+//! corrupted file is rejected with `HexvaultError::CorruptSnapshot` before
+//! any payload is handed to the decryption path. The CRC detects accidental
+//! damage only — payload integrity against tampering still comes from the
+//! GCM tags inside each ciphertext.
+//!
+//! All integers are big-endian. Strings and byte fields are length-prefixed
+//! with a 4-byte length. A cell body is:
+//!
+//! ```text
+//! cell_id, schema version (4), schema bound (1), has label (1), [label],
+//! payload count (4), then per payload, ordered by key:
+//!     key, layer index (1), master key ID (4), has expiry (1),
+//!     [expires_at seconds (8), expires_at nanos (4)], created_at seconds (8),
+//!     created_at nanos (4), has content ID (1), [content ID (32)],
+//!     ciphertext
+//! ```
+//!
+//! The expiry fields are present only when "has expiry" is 1, the label
+//! only when "has label" is 1, and the content ID only when "has content
+//! ID" is 1.
+
+use crate::error::HexvaultError;
+
+/// Magic bytes opening every cell snapshot.
+pub(crate) const MAGIC: &[u8; 4] = b"HXVC";
+
+/// The snapshot format version, the only one written or read.
+pub(crate) const VERSION: u8 = 1;
+
+const PREFIX_LEN: usize = MAGIC.len() + 1 + 4;
+const CRC_LEN: usize = 4;
+
+/// Compute the CRC-32 (IEEE 802.3, reflected, polynomial 0xEDB88320).
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Wrap `body` in the magic, version, length, and trailing CRC.
+pub(crate) fn frame(body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(PREFIX_LEN + body.len() + CRC_LEN);
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend_from_slice(body);
+    let crc = crc32(&out);
+    out.extend_from_slice(&crc.to_be_bytes());
+    out
+}
+
+/// Check the frame and return the body.
+pub(crate) fn unframe(bytes: &[u8]) -> Result<&[u8], HexvaultError> {
+    if bytes.len() < PREFIX_LEN + CRC_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(HexvaultError::CorruptSnapshot);
+    }
+    if bytes[MAGIC.len()] != VERSION {
+        return Err(HexvaultError::CorruptSnapshot);
+    }
+    let mut reader = Reader::new(&bytes[MAGIC.len() + 1..]);
+    let body_len = reader.u32()? as usize;
+    if bytes.len() != PREFIX_LEN + body_len + CRC_LEN {
+        return Err(HexvaultError::CorruptSnapshot);
+    }
+
+    let (framed, crc) = bytes.split_at(bytes.len() - CRC_LEN);
+    let mut crc_bytes = [0u8; CRC_LEN];
+    crc_bytes.copy_from_slice(crc);
+    if crc32(framed) != u32::from_be_bytes(crc_bytes) {
+        return Err(HexvaultError::CorruptSnapshot);
+    }
+    Ok(&framed[PREFIX_LEN..])
+}
+
+/// Append a length-prefixed byte field.
+pub(crate) fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// A cursor over a snapshot body. Every read fails with `CorruptSnapshot`
+/// rather than panicking when the body is shorter than expected.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], HexvaultError> {
+        if self.bytes.len() < len {
+            return Err(HexvaultError::CorruptSnapshot);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, HexvaultError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, HexvaultError> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_be_bytes(buf))
+    }
+
+    pub(crate) fn i64(&mut self) -> Result<i64, HexvaultError> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(i64::from_be_bytes(buf))
+    }
+
+    pub(crate) fn bytes(&mut self) -> Result<&'a [u8], HexvaultError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> Result<[u8; N], HexvaultError> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    pub(crate) fn string(&mut self) -> Result<String, HexvaultError> {
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| HexvaultError::CorruptSnapshot)
+    }
+
+    /// Fail unless every byte of the body has been consumed.
+    pub(crate) fn finish(self) -> Result<(), HexvaultError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(HexvaultError::CorruptSnapshot)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_check_value() {
+        // The standard CRC-32 check value for the ASCII string "123456789".
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_unframe_rejects_damage() {
+        let framed = frame(b"body");
+        assert_eq!(unframe(&framed).unwrap(), b"body");
+
+        // Truncated.
+        assert!(unframe(&framed[..framed.len() - 1]).is_err());
+        // Flipped body bit.
+        let mut corrupt = framed.clone();
+        corrupt[PREFIX_LEN] ^= 1;
+        assert!(unframe(&corrupt).is_err());
+        // Unknown version.
+        let mut corrupt = framed.clone();
+        corrupt[MAGIC.len()] = VERSION + 1;
+        assert!(unframe(&corrupt).is_err());
+        // Wrong magic.
+        let mut corrupt = framed;
+        corrupt[0] = b'X';
+        assert!(unframe(&corrupt).is_err());
+    }
+}
@@ -4,14 +4,24 @@
 //! (top-down). Each layer corresponds to a different trust boundary and
 //! requires specific context to peel.
 
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
 
-use crate::crypto;
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
+
+use crate::crypto::{self, CipherSuite, KdfHash, TagLength};
 use crate::error::HexvaultError;
-use crate::keys::{self, PartitionKey};
+use crate::keys::{self, DerivedKey, PartitionKey};
 
 /// The three layers of the hexvault encryption stack.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+///
+/// Serialises as its index (`0`, `1`, `2`), which is stable and matches the
+/// discriminants. Deserialisation also accepts the variant names written by
+/// earlier versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Layer {
     /// Layer 0: Base data protection (at-rest).
     AtRest = 0,
@@ -21,9 +31,52 @@ pub enum Layer {
     SessionBound = 2,
 }
 
+/// Every layer, in sealing (bottom-up) order.
+const ALL_LAYERS: [Layer; 3] = [Layer::AtRest, Layer::AccessGated, Layer::SessionBound];
+
 impl Layer {
-    /// Returns the tag used for key derivation for this layer.
-    fn tag(&self) -> &'static str {
+    /// Every layer in sealing order: `AtRest`, `AccessGated`, `SessionBound`.
+    pub fn all() -> &'static [Layer] {
+        &ALL_LAYERS
+    }
+
+    /// Look up a layer by its position in the stack (0 = `AtRest`).
+    pub fn from_index(index: usize) -> Option<Layer> {
+        ALL_LAYERS.get(index).copied()
+    }
+
+    /// The layer's position in the stack (0 = `AtRest`).
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The layer directly above this one, or `None` at the top of the stack.
+    pub fn next(&self) -> Option<Layer> {
+        Self::from_index(self.index() + 1)
+    }
+
+    /// The layer directly below this one, or `None` at the bottom.
+    pub fn prev(&self) -> Option<Layer> {
+        self.index().checked_sub(1).and_then(Self::from_index)
+    }
+
+    /// Fail with `HexvaultError::LayerPolicyViolation` if `self` is below
+    /// `min`.
+    pub(crate) fn check_min(self, min: Layer) -> Result<(), HexvaultError> {
+        if self < min {
+            return Err(HexvaultError::LayerPolicyViolation { layer: self, min });
+        }
+        Ok(())
+    }
+
+    /// The layers a payload sealed at `self` passes through, bottom-up.
+    pub(crate) fn up_to(self) -> &'static [Layer] {
+        &ALL_LAYERS[..=self.index()]
+    }
+
+    /// Returns the tag used for key derivation for this layer. Also the
+    /// key its context ID is stored under; see `LayerContext::with_id`.
+    pub fn tag(&self) -> &'static str {
         match self {
             Self::AtRest => keys::layer_tag::AT_REST,
             Self::AccessGated => keys::layer_tag::ACCESS_GATED,
@@ -32,6 +85,95 @@ impl Layer {
     }
 }
 
+impl Serialize for Layer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.index() as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for Layer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LayerVisitor;
+
+        impl de::Visitor<'_> for LayerVisitor {
+            type Value = Layer;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a layer index (0-2) or layer name")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Layer, E> {
+                usize::try_from(v)
+                    .ok()
+                    .and_then(Layer::from_index)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Layer, E> {
+                match v {
+                    "AtRest" => Ok(Layer::AtRest),
+                    "AccessGated" => Ok(Layer::AccessGated),
+                    "SessionBound" => Ok(Layer::SessionBound),
+                    _ => Err(E::unknown_variant(
+                        v,
+                        &["AtRest", "AccessGated", "SessionBound"],
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(LayerVisitor)
+    }
+}
+
+/// The access policy ID that gates Layer 1 (`AccessGated`).
+///
+/// A distinct type from `SessionId`, so the two cannot be passed in each
+/// other's place. Built with `TryFrom`, which rejects an empty ID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PolicyId(String);
+
+/// The session ID that binds Layer 2 (`SessionBound`).
+///
+/// A distinct type from `PolicyId`, so the two cannot be passed in each
+/// other's place. Built with `TryFrom`, which rejects an empty ID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionId(String);
+
+macro_rules! context_id {
+    ($name:ident) => {
+        impl $name {
+            /// The ID as a string.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = HexvaultError;
+
+            /// Rejects an empty ID with `MissingOrInvalidContext`.
+            fn try_from(id: String) -> Result<Self, HexvaultError> {
+                if id.is_empty() {
+                    return Err(HexvaultError::MissingOrInvalidContext);
+                }
+                Ok(Self(id))
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = HexvaultError;
+
+            fn try_from(id: &str) -> Result<Self, HexvaultError> {
+                Self::try_from(id.to_string())
+            }
+        }
+    };
+}
+
+context_id!(PolicyId);
+context_id!(SessionId);
+
 /// Context required to peel or seal specific layers.
 ///
 /// Fields are validated on construction: `Some("")` (empty string) is rejected
@@ -41,8 +183,10 @@ impl Layer {
 /// via `LayerContext::new()` / `LayerContext::empty()`.
 #[derive(Debug, Clone, Default)]
 pub struct LayerContext {
-    access_policy_id: Option<String>,
-    session_id: Option<String>,
+    access_policy_id: Option<PolicyId>,
+    session_id: Option<SessionId>,
+    /// Context IDs for layer tags other than the built-in layers'.
+    other_ids: BTreeMap<String, String>,
 }
 
 impl LayerContext {
@@ -53,31 +197,96 @@ impl LayerContext {
 
     /// Create a new `LayerContext`.
     ///
+    /// An empty ID would derive the same Layer 2 key for all sessions or
+    /// the same Layer 1 key for all access policies — collapsing the
+    /// isolation guarantee — so `PolicyId` and `SessionId` reject one when
+    /// they are built. No character is reserved: IDs are length-prefixed in
+    /// key derivation (ADR-013).
+    ///
     /// # Errors
     ///
-    /// Returns `HexvaultError::MissingOrInvalidContext` if either ID is
-    /// `Some("")` (empty string). An empty string would derive the same
-    /// Layer 2 key for all sessions or the same Layer 1 key for all
-    /// access policies — collapsing the isolation guarantee.
+    /// None: the IDs are validated when built. The `Result` is kept so code
+    /// written against 1.1, when empty IDs were rejected here, still
+    /// compiles.
     pub fn new(
-        access_policy_id: Option<String>,
-        session_id: Option<String>,
+        access_policy_id: Option<PolicyId>,
+        session_id: Option<SessionId>,
     ) -> Result<Self, HexvaultError> {
-        if let Some(ref id) = access_policy_id {
-            if id.is_empty() {
-                return Err(HexvaultError::MissingOrInvalidContext);
-            }
-        }
-        if let Some(ref id) = session_id {
-            if id.is_empty() {
-                return Err(HexvaultError::MissingOrInvalidContext);
-            }
-        }
         Ok(Self {
             access_policy_id,
             session_id,
+            other_ids: BTreeMap::new(),
         })
     }
+
+    /// Set the context ID for the layer tagged `tag` (see `Layer::tag`),
+    /// replacing any it had. The built-in tags set the typed IDs, so
+    /// `with_id(Layer::SessionBound.tag(), ..)` is the same as passing a
+    /// `SessionId` to `new`; any other tag holds an ID for a layer defined
+    /// outside the built-in three.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::MissingOrInvalidContext` if `id` is empty,
+    /// and `HexvaultError::InvalidLayer` for Layer 0's tag, which takes no
+    /// context.
+    pub fn with_id(mut self, tag: &str, id: impl Into<String>) -> Result<Self, HexvaultError> {
+        let id = id.into();
+        if id.is_empty() {
+            return Err(HexvaultError::MissingOrInvalidContext);
+        }
+        match tag {
+            keys::layer_tag::AT_REST => return Err(HexvaultError::InvalidLayer),
+            keys::layer_tag::ACCESS_GATED => self.access_policy_id = Some(PolicyId(id)),
+            keys::layer_tag::SESSION_BOUND => self.session_id = Some(SessionId(id)),
+            _ => {
+                self.other_ids.insert(tag.to_string(), id);
+            }
+        }
+        Ok(self)
+    }
+
+    /// The context ID held for the layer tagged `tag`, if any.
+    pub fn id_for_tag(&self, tag: &str) -> Option<&str> {
+        match tag {
+            keys::layer_tag::ACCESS_GATED => self.access_policy_id.as_ref().map(PolicyId::as_str),
+            keys::layer_tag::SESSION_BOUND => self.session_id.as_ref().map(SessionId::as_str),
+            _ => self.other_ids.get(tag).map(String::as_str),
+        }
+    }
+
+    /// Every context ID held, keyed by layer tag, in tag order.
+    pub fn ids(&self) -> BTreeMap<&str, &str> {
+        let built_in = [
+            (
+                Layer::AccessGated.tag(),
+                self.id_for_tag(Layer::AccessGated.tag()),
+            ),
+            (
+                Layer::SessionBound.tag(),
+                self.id_for_tag(Layer::SessionBound.tag()),
+            ),
+        ];
+        built_in
+            .into_iter()
+            .filter_map(|(tag, id)| Some((tag, id?)))
+            .chain(
+                self.other_ids
+                    .iter()
+                    .map(|(tag, id)| (tag.as_str(), id.as_str())),
+            )
+            .collect()
+    }
+
+    /// The access policy ID, which Layer 1 (AccessGated) needs.
+    pub fn access_policy_id(&self) -> Option<&PolicyId> {
+        self.access_policy_id.as_ref()
+    }
+
+    /// The session ID, which Layer 2 (SessionBound) needs.
+    pub fn session_id(&self) -> Option<&SessionId> {
+        self.session_id.as_ref()
+    }
 }
 
 /// Resolves opaque authentication/capability tokens into structured `LayerContext`s.
@@ -86,31 +295,239 @@ pub trait TokenResolver: Send + Sync {
     fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError>;
 }
 
+/// An authorisation check consulted before each layer is peeled, e.g. for
+/// revocation lists, rate limits, or time windows.
+///
+/// Set one for a whole vault with `Vault::set_layer_guard`, or for a single
+/// peel with `PeelOptions::guard`. Layers are checked top-down, each just
+/// before its key is derived; an error stops the peel there.
+pub trait LayerGuard: Send + Sync {
+    /// Allow or refuse peeling `layer` with `context`.
+    fn check(&self, layer: Layer, context: &LayerContext) -> Result<(), HexvaultError>;
+}
+
+/// The default guard: allows every layer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAll;
+
+impl LayerGuard for AllowAll {
+    fn check(&self, _layer: Layer, _context: &LayerContext) -> Result<(), HexvaultError> {
+        Ok(())
+    }
+}
+
 impl LayerContext {
-    /// Get the context ID string for a specific layer.
+    /// The layers up to and including `target` whose context ID is absent,
+    /// bottom-up. Empty if this context can seal or peel at `target`.
+    ///
+    /// This only checks presence; nothing is derived or decrypted.
+    pub fn missing_for(&self, target: Layer) -> Vec<Layer> {
+        target
+            .up_to()
+            .iter()
+            .copied()
+            .filter(|&layer| self.get_id_for_layer(layer).is_err())
+            .collect()
+    }
+
+    /// The layers above `target` for which this context nevertheless holds
+    /// an ID, bottom-up. Sealing at `target` ignores them; a non-empty
+    /// result usually means the wrong context was wired up.
+    pub fn unexpected_for(&self, target: Layer) -> Vec<Layer> {
+        Layer::all()
+            .iter()
+            .copied()
+            .filter(|&layer| layer > target && self.get_id_for_layer(layer).is_ok())
+            .collect()
+    }
+
+    /// Get the context ID string for a specific layer, looked up by its
+    /// tag. Layer 0 needs none.
     fn get_id_for_layer(&self, layer: Layer) -> Result<String, HexvaultError> {
-        match layer {
-            Layer::AtRest => Ok(String::new()),
-            Layer::AccessGated => self
-                .access_policy_id
-                .clone()
-                .ok_or(HexvaultError::MissingOrInvalidContext),
-            Layer::SessionBound => self
-                .session_id
-                .clone()
-                .ok_or(HexvaultError::MissingOrInvalidContext),
+        if layer == Layer::AtRest {
+            return Ok(String::new());
         }
+        self.id_for_tag(layer.tag())
+            .map(str::to_string)
+            .ok_or(HexvaultError::MissingOrInvalidContext)
     }
 }
 
+/// Derive the key sealing `layer` for `cell_id`, from `context`'s ID for
+/// that layer.
+pub(crate) fn layer_key(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    layer: Layer,
+    context: &LayerContext,
+    suite: CipherSuite,
+    kdf: KdfHash,
+    schema_version: Option<u32>,
+) -> Result<DerivedKey, HexvaultError> {
+    let context_id = context.get_id_for_layer(layer)?;
+    keys::derive_key(
+        partition_key,
+        cell_id,
+        layer.tag(),
+        &context_id,
+        suite,
+        kdf,
+        schema_version,
+    )
+}
+
 /// Build the AAD (Additional Authenticated Data) for a specific cell and layer.
 ///
 /// The AAD binds the ciphertext to its cell and layer, preventing cross-cell
 /// and cross-layer replay attacks. Even if two cells share identical keys
 /// (impossible under correct HKDF usage), the AAD check would still reject
 /// replayed ciphertext.
-fn build_aad(cell_id: &str, layer: Layer) -> Vec<u8> {
-    format!("hexvault:{}:{}", cell_id, layer.tag()).into_bytes()
+pub(crate) fn build_aad(
+    cell_id: &str,
+    layer: Layer,
+    app_aad: &[u8],
+    expires_at: Option<DateTime<Utc>>,
+) -> Vec<u8> {
+    let mut aad = format!("hexvault:{}:{}", cell_id, layer.tag()).into_bytes();
+    // Application AAD is length-prefixed so it cannot be confused with the
+    // built-in prefix. Empty AAD adds nothing, keeping existing ciphertext valid.
+    if !app_aad.is_empty() {
+        aad.extend_from_slice(&(app_aad.len() as u32).to_be_bytes());
+        aad.extend_from_slice(app_aad);
+    }
+    // Binds the expiry, which is stored beside the ciphertext, so it cannot
+    // be extended or removed without failing authentication.
+    if let Some(expires_at) = expires_at {
+        aad.extend_from_slice(EXPIRY_AAD);
+        aad.extend_from_slice(&expires_at.timestamp().to_be_bytes());
+        aad.extend_from_slice(&expires_at.timestamp_subsec_nanos().to_be_bytes());
+    }
+    aad
+}
+
+/// Appended, followed by the expiry's seconds (8, BE) and nanoseconds
+/// (4, BE), to the AAD of layers sealed with an expiry. The NUL cannot occur
+/// in a layer tag, and application AAD is length-prefixed.
+const EXPIRY_AAD: &[u8] = b"\0expires";
+
+/// A one-way fingerprint of the keys that seal up to `target` for
+/// `cell_id` under each of `params`' suite and KDF hash pairs, in order,
+/// and `schema_version`, as lowercase hex.
+///
+/// The keys are combined by `keys::fingerprint_keys`. Equal fingerprints
+/// mean equal keys; the fingerprint reveals nothing about the keys
+/// themselves.
+pub(crate) fn fingerprint(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    target: Layer,
+    context: &LayerContext,
+    params: &[(CipherSuite, KdfHash)],
+    schema_version: Option<u32>,
+) -> Result<String, HexvaultError> {
+    let mut layer_keys = Vec::with_capacity(params.len() * target.up_to().len());
+    for &(suite, kdf) in params {
+        for &layer in target.up_to() {
+            let key = layer_key(
+                partition_key,
+                cell_id,
+                layer,
+                context,
+                suite,
+                kdf,
+                schema_version,
+            )?;
+            layer_keys.push(key);
+        }
+    }
+    Ok(crate::audit::to_hex(&keys::fingerprint_keys(&layer_keys)))
+}
+
+/// The bytes sealing a plaintext up to `target` under `suite` with `tag`
+/// adds, so the sealed size is the plaintext size plus this.
+///
+/// A payload sealed with `SealOptions::checksum` carries another 32 bytes.
+pub fn overhead_for(target: Layer, suite: CipherSuite, tag: TagLength) -> usize {
+    target.up_to().len() * suite.layer_overhead(tag)
+}
+
+/// Options controlling how a payload is sealed.
+///
+/// `SealOptions::default()` reproduces the behaviour of `seal`.
+#[derive(Debug, Clone, Default)]
+pub struct SealOptions {
+    /// The cipher suite applied at every layer.
+    pub suite: CipherSuite,
+    /// The hash HKDF derives every layer's key with.
+    pub kdf: KdfHash,
+    /// Application data bound into every layer's authentication tag, e.g. a
+    /// request ID. The payload only opens when the same bytes are supplied.
+    pub aad: Vec<u8>,
+    /// Seal a SHA-256 of the plaintext inside the innermost layer, checked
+    /// after a full peel. Catches layering or logic errors that return
+    /// wrong bytes which still authenticate; fails with
+    /// `HexvaultError::IntegrityCheckFailed`. Costs 32 bytes per payload.
+    pub checksum: bool,
+    /// The length of every layer's authentication tag. Truncated tags are
+    /// for interop with peers that require them, and weaken authentication;
+    /// see `TagLength::Truncated96`.
+    pub tag_length: TagLength,
+    /// Reject a context holding IDs for layers above the target (see
+    /// `LayerContext::unexpected_for`) with
+    /// `HexvaultError::UnexpectedContext`, instead of ignoring them.
+    pub strict: bool,
+    /// When the payload stops opening. Bound into every layer's AAD, so it
+    /// must be given again at peel time (`PeelOptions::expires_at`); cells
+    /// store it beside the ciphertext and do this themselves.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// A schema version mixed into every layer's key derivation, so the
+    /// payload only peels with the same `PeelOptions::schema_version`.
+    /// Cells bound to a schema version (see `Cell::with_bound_schema`) set
+    /// this themselves.
+    pub schema_version: Option<u32>,
+    /// Have the cell record a content ID for the payload (see
+    /// `Payload::content_id`). Ignored outside a cell. Reveals which
+    /// payloads in a partition hold equal plaintexts.
+    pub content_id: bool,
+    /// Derive each layer's nonce from its key, this seed, its header and
+    /// AAD, and the bytes it seals, instead of drawing it at random, so
+    /// sealing the same plaintext with the same seed and options reproduces
+    /// the same bytes; e.g. for idempotent write retries. See `seal_seeded`.
+    ///
+    /// Everything authenticated is mixed in, so reusing a seed with a
+    /// different plaintext, AAD, expiry, or tag length still gets a
+    /// different nonce. Equal plaintexts sealed with
+    /// equal seeds are visibly equal, though: use a fresh seed per logical
+    /// write, such as its idempotency key, never a constant.
+    pub seed: Option<Vec<u8>>,
+}
+
+/// Options controlling how a payload is peeled.
+///
+/// `PeelOptions::default()` reproduces the behaviour of `peel`.
+#[derive(Clone, Default)]
+pub struct PeelOptions {
+    /// The application AAD the payload was sealed with.
+    pub aad: Vec<u8>,
+    /// The expiry the payload was sealed with. Once it has passed, peeling
+    /// fails with `HexvaultError::Expired` before anything is decrypted.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The schema version the payload was sealed with, if any.
+    pub schema_version: Option<u32>,
+    /// Checked before each layer is peeled. `None` allows every layer.
+    pub guard: Option<Arc<dyn LayerGuard>>,
+}
+
+/// Prints whether a guard is set, not the guard.
+impl fmt::Debug for PeelOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeelOptions")
+            .field("aad", &self.aad)
+            .field("expires_at", &self.expires_at)
+            .field("schema_version", &self.schema_version)
+            .field("guard", &self.guard.is_some())
+            .finish()
+    }
 }
 
 /// Seal a payload into the stack up to the target layer.
@@ -123,56 +540,392 @@ pub fn seal(
     context: &LayerContext,
     plaintext: &[u8],
 ) -> Result<Vec<u8>, HexvaultError> {
-    let mut current_data = plaintext.to_vec();
+    seal_with_options(
+        partition_key,
+        cell_id,
+        target,
+        context,
+        plaintext,
+        &SealOptions::default(),
+    )
+}
 
+/// Seal a payload into the stack up to the target layer with explicit options.
+///
+/// Identical to `seal`, except that the cipher suite (and any future
+/// sealing parameters) come from `options`.
+pub fn seal_with_options(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    target: Layer,
+    context: &LayerContext,
+    plaintext: &[u8],
+    options: &SealOptions,
+) -> Result<Vec<u8>, HexvaultError> {
+    if options.strict {
+        let unexpected = context.unexpected_for(target);
+        if !unexpected.is_empty() {
+            return Err(HexvaultError::UnexpectedContext(unexpected));
+        }
+    }
+    // Every layer is sealed in place in one buffer, sized up front for all
+    // of them so it never reallocates and strands a copy of the plaintext.
+    let layers = target.up_to();
+    let mut buf = Vec::with_capacity(
+        plaintext.len() + crypto::CHECKSUM_LEN + layers.len() * crypto::MIN_CIPHERTEXT_LEN,
+    );
+    buf.extend_from_slice(plaintext);
+    if options.checksum {
+        buf.extend_from_slice(&crypto::checksum(plaintext));
+    }
+
+    let result = seal_layers(partition_key, cell_id, layers, context, options, &mut buf);
+    if result.is_err() {
+        // Nothing sealed yet leaves the plaintext in the buffer.
+        buf.zeroize();
+    }
+    result.map(|()| buf)
+}
+
+fn seal_layers(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    layers: &[Layer],
+    context: &LayerContext,
+    options: &SealOptions,
+    buf: &mut Vec<u8>,
+) -> Result<(), HexvaultError> {
     // Iterate through layers from 0 up to and including the target layer.
-    for i in 0..=(target as usize) {
-        let layer = match i {
-            0 => Layer::AtRest,
-            1 => Layer::AccessGated,
-            2 => Layer::SessionBound,
-            _ => return Err(HexvaultError::InvalidLayer),
-        };
+    for &layer in layers {
+        let key = layer_key(
+            partition_key,
+            cell_id,
+            layer,
+            context,
+            options.suite,
+            options.kdf,
+            options.schema_version,
+        )?;
+        let aad = build_aad(cell_id, layer, &options.aad, options.expires_at);
 
-        let context_id = context.get_id_for_layer(layer)?;
-        let key = keys::derive_key(partition_key, cell_id, layer.tag(), &context_id)?;
-        let aad = build_aad(cell_id, layer);
+        match &options.seed {
+            Some(seed) => crypto::encrypt_in_place_seeded(
+                options.suite,
+                options.kdf,
+                key.as_bytes(),
+                buf,
+                &aad,
+                options.checksum,
+                options.tag_length,
+                seed,
+            )?,
+            None => crypto::encrypt_in_place(
+                options.suite,
+                options.kdf,
+                key.as_bytes(),
+                buf,
+                &aad,
+                options.checksum,
+                options.tag_length,
+            )?,
+        }
+        // A repeated seeded nonce is a repeated seal of the same plaintext,
+        // not a broken nonce source, so only random nonces are checked.
+        #[cfg(debug_assertions)]
+        if let (Some(detector), None) = (partition_key.nonce_detector(), &options.seed) {
+            let mut nonce = [0u8; crypto::NONCE_LEN];
+            nonce.copy_from_slice(&buf[crypto::HEADER_LEN..crypto::HEADER_LEN + crypto::NONCE_LEN]);
+            detector.check(crate::nonce_check::key_id(key.as_bytes()), nonce);
+        }
+    }
+    Ok(())
+}
 
-        current_data = crypto::encrypt(key.as_bytes(), &current_data, &aad)?;
+/// `seal` with `SealOptions::seed`: sealing the same plaintext for the same
+/// cell and context with the same seed returns the same bytes, and any
+/// other seed returns different ones.
+pub fn seal_seeded(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    target: Layer,
+    context: &LayerContext,
+    plaintext: &[u8],
+    seed: &[u8],
+) -> Result<Vec<u8>, HexvaultError> {
+    let options = SealOptions {
+        seed: Some(seed.to_vec()),
+        ..SealOptions::default()
+    };
+    seal_with_options(partition_key, cell_id, target, context, plaintext, &options)
+}
+
+/// A payload sealed up to a known layer, produced by `seal_blob` and
+/// consumed by `peel_blob`.
+///
+/// Unlike the `Vec<u8>` that `seal` returns, a blob can only be built by
+/// sealing or by `SealedBlob::parse`, which checks the outer header and the
+/// minimum length for the layer once, at the boundary, so bytes that could
+/// never peel are rejected before any key is derived.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SealedBlob {
+    layer: Layer,
+    bytes: Vec<u8>,
+}
+
+impl SealedBlob {
+    /// Wrap stored ciphertext sealed up to `layer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexvaultError::MalformedCiphertext` if the header is not
+    /// recognised or `bytes` is too short to hold every layer up to `layer`.
+    pub fn parse(layer: Layer, bytes: Vec<u8>) -> Result<Self, HexvaultError> {
+        check_framing(layer, &bytes)?;
+        Ok(Self { layer, bytes })
+    }
+
+    /// The layer the payload is sealed up to.
+    pub fn layer(&self) -> Layer {
+        self.layer
+    }
+
+    /// The cipher suite of the outermost layer.
+    pub fn suite(&self) -> CipherSuite {
+        // Checked when the blob was built.
+        crypto::read_suite(&self.bytes).unwrap_or_default()
+    }
+
+    /// Borrow the sealed bytes, e.g. to store or send them.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Take the sealed bytes. `SealedBlob::parse` with the same layer
+    /// rebuilds the blob.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// The sealed bytes, so a blob can be passed to `peel` and friends.
+impl AsRef<[u8]> for SealedBlob {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Prints the layer and length, not the ciphertext.
+impl fmt::Debug for SealedBlob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SealedBlob")
+            .field("layer", &self.layer)
+            .field("len", &self.bytes.len())
+            .finish()
     }
+}
 
-    Ok(current_data)
+/// The structural checks a payload sealed up to `top` must pass before it
+/// is peeled: a known outer header, and room for every layer's header,
+/// nonce, and tag.
+pub(crate) fn check_framing(top: Layer, ciphertext: &[u8]) -> Result<(), HexvaultError> {
+    crypto::read_header(ciphertext)?;
+    let tag = crypto::tag_length(ciphertext);
+    let min_layer_len = crypto::MIN_CIPHERTEXT_LEN - (crypto::TAG_LEN - tag.bytes());
+    if ciphertext.len() < top.up_to().len() * min_layer_len {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+    Ok(())
+}
+
+/// `seal_with_options`, returning the payload as a `SealedBlob`.
+pub fn seal_blob(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    target: Layer,
+    context: &LayerContext,
+    plaintext: &[u8],
+    options: &SealOptions,
+) -> Result<SealedBlob, HexvaultError> {
+    let bytes = seal_with_options(partition_key, cell_id, target, context, plaintext, options)?;
+    Ok(SealedBlob {
+        layer: target,
+        bytes,
+    })
+}
+
+/// Peel a `SealedBlob` down to plaintext, from the layer it records.
+pub fn peel_blob(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    context: &LayerContext,
+    blob: &SealedBlob,
+    options: &PeelOptions,
+) -> Result<Vec<u8>, HexvaultError> {
+    peel_with_options(
+        partition_key,
+        cell_id,
+        blob.layer,
+        context,
+        &blob.bytes,
+        options,
+    )
 }
 
 /// Peel a payload from its current top layer down to plaintext.
 ///
-/// Decryption is applied top-down: current -> ... -> Layer 0.
+/// Decryption is applied top-down: current -> ... -> Layer 0. Each layer's
+/// cipher suite is read from its ciphertext header. `ciphertext` may be raw
+/// bytes or a `SealedBlob`; `peel_blob` also takes the layer from the blob.
 pub fn peel(
     partition_key: &PartitionKey,
     cell_id: &str,
     current_top: Layer,
     context: &LayerContext,
-    ciphertext: &[u8],
+    ciphertext: impl AsRef<[u8]>,
 ) -> Result<Vec<u8>, HexvaultError> {
-    let mut current_data = ciphertext.to_vec();
-
-    // Iterate through layers from the top layer down to 0.
-    for i in (0..=(current_top as usize)).rev() {
-        let layer = match i {
-            0 => Layer::AtRest,
-            1 => Layer::AccessGated,
-            2 => Layer::SessionBound,
-            _ => return Err(HexvaultError::InvalidLayer),
-        };
+    peel_with_options(
+        partition_key,
+        cell_id,
+        current_top,
+        context,
+        ciphertext,
+        &PeelOptions::default(),
+    )
+}
 
-        let context_id = context.get_id_for_layer(layer)?;
-        let key = keys::derive_key(partition_key, cell_id, layer.tag(), &context_id)?;
-        let aad = build_aad(cell_id, layer);
+/// Peel a payload with explicit options.
+///
+/// Identical to `peel`, except that the application AAD comes from
+/// `options`. It must match the AAD given at seal time.
+pub fn peel_with_options(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    current_top: Layer,
+    context: &LayerContext,
+    ciphertext: impl AsRef<[u8]>,
+    options: &PeelOptions,
+) -> Result<Vec<u8>, HexvaultError> {
+    let mut out = Vec::new();
+    peel_into_with_options(
+        partition_key,
+        cell_id,
+        current_top,
+        context,
+        ciphertext.as_ref(),
+        options,
+        Utc::now(),
+        &mut out,
+    )?;
+    Ok(out)
+}
+
+/// Peel a payload into a caller-provided buffer.
+///
+/// Behaves like `peel`, but peels in place in `out`, so a caller that keeps
+/// it across calls performs no per-call buffer allocation once it has grown
+/// to the ciphertext size. `out` is left empty on failure.
+pub fn peel_into(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    current_top: Layer,
+    context: &LayerContext,
+    ciphertext: impl AsRef<[u8]>,
+    out: &mut Vec<u8>,
+) -> Result<(), HexvaultError> {
+    peel_into_with_options(
+        partition_key,
+        cell_id,
+        current_top,
+        context,
+        ciphertext.as_ref(),
+        &PeelOptions::default(),
+        Utc::now(),
+        out,
+    )
+}
 
-        current_data = crypto::decrypt(key.as_bytes(), &current_data, &aad)?;
+/// `peel_into` with explicit options, checking any expiry against `now`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn peel_into_with_options(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    current_top: Layer,
+    context: &LayerContext,
+    ciphertext: &[u8],
+    options: &PeelOptions,
+    now: DateTime<Utc>,
+    out: &mut Vec<u8>,
+) -> Result<(), HexvaultError> {
+    // Wipe the previous plaintext, including spare capacity, once up front.
+    // The layers below only ever write ciphertext or this call's plaintext.
+    out.zeroize();
+    let result = peel_layers_into(
+        partition_key,
+        cell_id,
+        current_top,
+        context,
+        ciphertext,
+        options,
+        now,
+        out,
+    );
+    if result.is_err() {
+        out.zeroize();
     }
+    result
+}
 
-    Ok(current_data)
+#[allow(clippy::too_many_arguments)]
+fn peel_layers_into(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    current_top: Layer,
+    context: &LayerContext,
+    ciphertext: &[u8],
+    options: &PeelOptions,
+    now: DateTime<Utc>,
+    out: &mut Vec<u8>,
+) -> Result<(), HexvaultError> {
+    if options
+        .expires_at
+        .is_some_and(|expires_at| now >= expires_at)
+    {
+        return Err(HexvaultError::Expired);
+    }
+    check_framing(current_top, ciphertext)?;
+    out.clear();
+    out.extend_from_slice(ciphertext);
+
+    // Iterate through layers from the top layer down to 0, each decrypted
+    // in place in `out`, which shrinks to the next layer's ciphertext.
+    let mut checksum = false;
+    for &layer in current_top.up_to().iter().rev() {
+        if let Some(guard) = &options.guard {
+            guard.check(layer, context)?;
+        }
+        let (suite, kdf) = crypto::read_header(out)?;
+        let key = layer_key(
+            partition_key,
+            cell_id,
+            layer,
+            context,
+            suite,
+            kdf,
+            options.schema_version,
+        )?;
+        checksum = crypto::has_checksum(out);
+        let aad = build_aad(cell_id, layer, &options.aad, options.expires_at);
+
+        crypto::decrypt_in_place(key.as_bytes(), out, &aad)?;
+    }
+
+    // The innermost layer's flag says whether a checksum follows the plaintext.
+    if checksum {
+        let len = crypto::verify_checksum(out)?;
+        out[len..].zeroize();
+        out.truncate(len);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -180,6 +933,105 @@ mod tests {
     use super::*;
     use crate::keys::{self, MasterKey};
 
+    #[test]
+    fn test_layer_navigation() {
+        assert_eq!(
+            Layer::all(),
+            &[Layer::AtRest, Layer::AccessGated, Layer::SessionBound]
+        );
+        for (i, layer) in Layer::all().iter().enumerate() {
+            assert_eq!(layer.index(), i);
+            assert_eq!(Layer::from_index(i), Some(*layer));
+        }
+        assert_eq!(Layer::from_index(3), None);
+
+        assert_eq!(Layer::AtRest.next(), Some(Layer::AccessGated));
+        assert_eq!(Layer::SessionBound.next(), None);
+        assert_eq!(Layer::SessionBound.prev(), Some(Layer::AccessGated));
+        assert_eq!(Layer::AtRest.prev(), None);
+    }
+
+    #[test]
+    fn test_layer_serializes_as_index() {
+        for &layer in Layer::all() {
+            let json = serde_json::to_string(&layer).unwrap();
+            assert_eq!(json, layer.index().to_string());
+            assert_eq!(serde_json::from_str::<Layer>(&json).unwrap(), layer);
+        }
+        // Names written by earlier versions still load.
+        assert_eq!(
+            serde_json::from_str::<Layer>("\"SessionBound\"").unwrap(),
+            Layer::SessionBound
+        );
+        assert!(serde_json::from_str::<Layer>("3").is_err());
+
+        let mut index = std::collections::HashMap::new();
+        index.insert(("cell".to_string(), Layer::AccessGated), 1);
+        assert_eq!(index[&("cell".to_string(), Layer::AccessGated)], 1);
+    }
+
+    #[test]
+    fn test_context_ids() {
+        let policy = PolicyId::try_from("policy").unwrap();
+        let session = SessionId::try_from(String::from("session")).unwrap();
+        assert_eq!(policy.as_str(), "policy");
+
+        let ctx = LayerContext::new(Some(policy), Some(session)).unwrap();
+        assert_eq!(
+            ctx.get_id_for_layer(Layer::SessionBound).unwrap(),
+            "session"
+        );
+        assert!(PolicyId::try_from("").is_err());
+        assert!(SessionId::try_from(String::new()).is_err());
+    }
+
+    #[test]
+    fn test_context_ids_by_tag() {
+        let ctx = LayerContext::empty()
+            .with_id(Layer::AccessGated.tag(), "policy")
+            .unwrap()
+            .with_id("geo", "eu-west")
+            .unwrap();
+        assert_eq!(ctx.access_policy_id().unwrap().as_str(), "policy");
+        assert_eq!(ctx.id_for_tag("geo"), Some("eu-west"));
+        assert_eq!(ctx.id_for_tag(Layer::SessionBound.tag()), None);
+        assert_eq!(
+            ctx.ids().into_iter().collect::<Vec<_>>(),
+            [("access", "policy"), ("geo", "eu-west")]
+        );
+
+        // The typed constructor and the tag map derive the same keys.
+        let typed = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        assert_eq!(
+            typed.get_id_for_layer(Layer::AccessGated).unwrap(),
+            ctx.get_id_for_layer(Layer::AccessGated).unwrap()
+        );
+
+        assert!(matches!(
+            LayerContext::empty().with_id("geo", ""),
+            Err(HexvaultError::MissingOrInvalidContext)
+        ));
+        assert!(matches!(
+            LayerContext::empty().with_id(Layer::AtRest.tag(), "x"),
+            Err(HexvaultError::InvalidLayer)
+        ));
+    }
+
+    #[test]
+    fn test_missing_for() {
+        let policy_only = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        assert!(policy_only.missing_for(Layer::AccessGated).is_empty());
+        assert_eq!(
+            policy_only.missing_for(Layer::SessionBound),
+            [Layer::SessionBound]
+        );
+        assert_eq!(
+            LayerContext::empty().missing_for(Layer::SessionBound),
+            [Layer::AccessGated, Layer::SessionBound]
+        );
+        assert!(LayerContext::empty().missing_for(Layer::AtRest).is_empty());
+    }
+
     #[test]
     fn test_seal_peel_roundtrip() {
         let master = MasterKey::from_bytes([0u8; 32]);
@@ -187,19 +1039,428 @@ mod tests {
         let cell_id = "test-cell";
         let plaintext = b"secret message";
         let context = LayerContext::new(
-            Some("policy-123".to_string()),
-            Some("session-456".to_string()),
+            Some("policy-123".try_into().unwrap()),
+            Some("session-456".try_into().unwrap()),
         )
         .unwrap();
 
         // Test roundtrip for each layer depth.
-        for layer in [Layer::AtRest, Layer::AccessGated, Layer::SessionBound] {
+        for &layer in Layer::all() {
             let sealed = seal(&partition, cell_id, layer, &context, plaintext).unwrap();
             let peeled = peel(&partition, cell_id, layer, &context, &sealed).unwrap();
             assert_eq!(plaintext, &peeled[..]);
         }
     }
 
+    #[test]
+    fn test_peel_into_reuses_buffers() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::new(
+            Some("policy-123".try_into().unwrap()),
+            Some("session-456".try_into().unwrap()),
+        )
+        .unwrap();
+        let mut out = Vec::new();
+
+        for &layer in Layer::all() {
+            let sealed = seal(&partition, "cell", layer, &context, b"reused").unwrap();
+            peel_into(&partition, "cell", layer, &context, &sealed, &mut out).unwrap();
+            assert_eq!(out, b"reused");
+        }
+
+        // A failed peel leaves the output buffer empty.
+        let sealed = seal(&partition, "cell", Layer::AtRest, &context, b"reused").unwrap();
+        assert!(peel_into(
+            &partition,
+            "other-cell",
+            Layer::AtRest,
+            &context,
+            &sealed,
+            &mut out,
+        )
+        .is_err());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_application_aad_must_match() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let seal_options = SealOptions {
+            aad: b"request-42".to_vec(),
+            ..SealOptions::default()
+        };
+        let sealed = seal_with_options(
+            &partition,
+            "cell",
+            Layer::AccessGated,
+            &context,
+            b"bound",
+            &seal_options,
+        )
+        .unwrap();
+
+        let peel = |aad: &[u8]| {
+            peel_with_options(
+                &partition,
+                "cell",
+                Layer::AccessGated,
+                &context,
+                &sealed,
+                &PeelOptions {
+                    aad: aad.to_vec(),
+                    ..PeelOptions::default()
+                },
+            )
+        };
+        assert_eq!(peel(b"request-42").unwrap(), b"bound");
+        assert!(peel(b"request-43").is_err());
+        assert!(peel(b"").is_err());
+    }
+
+    #[test]
+    fn test_strict_seal_rejects_unused_context() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let session_only = LayerContext::new(None, Some("session".try_into().unwrap())).unwrap();
+        let strict = SealOptions {
+            strict: true,
+            ..SealOptions::default()
+        };
+        let seal = |target, context: &LayerContext, options: &SealOptions| {
+            seal_with_options(&partition, "cell", target, context, b"data", options)
+        };
+
+        // A session ID on an at-rest seal is ignored unless strict.
+        assert!(seal(Layer::AtRest, &session_only, &SealOptions::default()).is_ok());
+        assert!(matches!(
+            seal(Layer::AtRest, &session_only, &strict),
+            Err(HexvaultError::UnexpectedContext(layers)) if layers == [Layer::SessionBound]
+        ));
+        let full = LayerContext::new(
+            Some("policy".try_into().unwrap()),
+            Some("session".try_into().unwrap()),
+        )
+        .unwrap();
+        assert!(seal(Layer::SessionBound, &full, &strict).is_ok());
+
+        // A session-bound seal without its session ID names the gap.
+        let policy_only = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        assert_eq!(
+            policy_only.missing_for(Layer::SessionBound),
+            [Layer::SessionBound]
+        );
+        assert!(matches!(
+            seal(Layer::SessionBound, &policy_only, &strict),
+            Err(HexvaultError::MissingOrInvalidContext)
+        ));
+    }
+
+    #[test]
+    fn test_checksum_roundtrip_and_mismatch() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let options = SealOptions {
+            checksum: true,
+            ..SealOptions::default()
+        };
+
+        let sealed = seal_with_options(
+            &partition,
+            "cell",
+            Layer::AccessGated,
+            &context,
+            b"checked",
+            &options,
+        )
+        .unwrap();
+        assert!(crypto::has_checksum(&sealed));
+        let peeled = peel(&partition, "cell", Layer::AccessGated, &context, &sealed).unwrap();
+        assert_eq!(peeled, b"checked");
+
+        // Clearing the flag changes the AAD, so authentication fails.
+        let mut unflagged = sealed.clone();
+        unflagged[1] &= 0x7f;
+        assert!(matches!(
+            peel(&partition, "cell", Layer::AccessGated, &context, &unflagged),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+
+        // A layer that authenticates but carries the wrong checksum.
+        let key = keys::derive_key(
+            &partition,
+            "cell",
+            Layer::AtRest.tag(),
+            "",
+            CipherSuite::default(),
+            KdfHash::default(),
+            None,
+        )
+        .unwrap();
+        let mut inner = b"checked".to_vec();
+        inner.extend_from_slice(&crypto::checksum(b"other"));
+        let aad = build_aad("cell", Layer::AtRest, &[], None);
+        let mut bad = inner;
+        bad.reserve(crypto::MIN_CIPHERTEXT_LEN);
+        crypto::encrypt_in_place(
+            CipherSuite::default(),
+            KdfHash::default(),
+            key.as_bytes(),
+            &mut bad,
+            &aad,
+            true,
+            TagLength::Full,
+        )
+        .unwrap();
+        assert!(matches!(
+            peel(&partition, "cell", Layer::AtRest, &context, &bad),
+            Err(HexvaultError::IntegrityCheckFailed)
+        ));
+    }
+
+    #[test]
+    fn test_overhead_matches_sealed_size() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::new(
+            Some("policy".try_into().unwrap()),
+            Some("session".try_into().unwrap()),
+        )
+        .unwrap();
+
+        for &suite in &[CipherSuite::Aes256Gcm, CipherSuite::Aes128Gcm] {
+            for &layer in Layer::all() {
+                let options = SealOptions {
+                    suite,
+                    ..SealOptions::default()
+                };
+                let sealed =
+                    seal_with_options(&partition, "cell", layer, &context, b"12345", &options)
+                        .unwrap();
+                assert_eq!(
+                    sealed.len(),
+                    5 + overhead_for(layer, suite, TagLength::Full)
+                );
+
+                let truncated = SealOptions {
+                    tag_length: TagLength::Truncated96,
+                    ..options
+                };
+                let sealed =
+                    seal_with_options(&partition, "cell", layer, &context, b"12345", &truncated)
+                        .unwrap();
+                assert_eq!(
+                    sealed.len(),
+                    5 + overhead_for(layer, suite, TagLength::Truncated96)
+                );
+            }
+        }
+        assert_eq!(
+            overhead_for(Layer::SessionBound, CipherSuite::default(), TagLength::Full),
+            90
+        );
+        assert_eq!(
+            overhead_for(
+                Layer::SessionBound,
+                CipherSuite::ChaCha20Poly1305,
+                TagLength::Truncated96
+            ),
+            90
+        );
+    }
+
+    #[test]
+    fn test_header_is_authenticated() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::empty();
+        let sealed = seal(&partition, "cell", Layer::AtRest, &context, b"agile").unwrap();
+
+        // Downgrade AES-256-GCM to AES-128-GCM in the suite byte.
+        let mut downgraded = sealed.clone();
+        downgraded[1] = downgraded[1] & 0xf0 | CipherSuite::Aes128Gcm.id();
+        assert!(matches!(
+            peel(&partition, "cell", Layer::AtRest, &context, &downgraded),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+
+        // The KDF bits do not change the cipher, so only the AAD catches them.
+        let key = [7u8; 32];
+        let mut ciphertext =
+            crypto::encrypt(CipherSuite::default(), KdfHash::Sha256, &key, b"x", b"aad").unwrap();
+        ciphertext[1] |= KdfHash::Sha512.id() << 4;
+        assert!(matches!(
+            crypto::decrypt(&key, &ciphertext, b"aad"),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+    }
+
+    #[test]
+    fn test_aes128_suite_roundtrip() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let options = SealOptions {
+            suite: CipherSuite::Aes128Gcm,
+            ..SealOptions::default()
+        };
+        assert_eq!(CipherSuite::Aes128Gcm.key_len(), 16);
+
+        let sealed = seal_with_options(
+            &partition,
+            "cell",
+            Layer::AccessGated,
+            &context,
+            b"short keys",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(crypto::read_suite(&sealed).unwrap(), CipherSuite::Aes128Gcm);
+        let peeled = peel(&partition, "cell", Layer::AccessGated, &context, &sealed).unwrap();
+        assert_eq!(peeled, b"short keys");
+    }
+
+    #[test]
+    fn test_kdf_hash_roundtrip() {
+        let master = MasterKey::from_bytes([9u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(
+            Some("policy".try_into().unwrap()),
+            Some("session".try_into().unwrap()),
+        )
+        .unwrap();
+        let options = SealOptions {
+            kdf: KdfHash::Sha384,
+            ..SealOptions::default()
+        };
+
+        let sealed = seal_with_options(
+            &partition,
+            "cell",
+            Layer::SessionBound,
+            &ctx,
+            b"derived with sha-384",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            crypto::read_header(&sealed).unwrap(),
+            (CipherSuite::Aes256Gcm, KdfHash::Sha384)
+        );
+        assert_eq!(sealed[1], 0x11);
+        assert_eq!(
+            peel(&partition, "cell", Layer::SessionBound, &ctx, &sealed).unwrap(),
+            b"derived with sha-384"
+        );
+
+        // The header picks the hash: relabelling it as SHA-256 fails.
+        let mut relabelled = sealed.clone();
+        relabelled[1] = 0x01;
+        assert!(peel(&partition, "cell", Layer::SessionBound, &ctx, &relabelled).is_err());
+    }
+
+    #[test]
+    fn test_chacha_suite_roundtrip() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let options = SealOptions {
+            suite: CipherSuite::ChaCha20Poly1305,
+            ..SealOptions::default()
+        };
+
+        let sealed = seal_with_options(
+            &partition,
+            "cell",
+            Layer::AccessGated,
+            &context,
+            b"agile",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            crypto::read_suite(&sealed).unwrap(),
+            CipherSuite::ChaCha20Poly1305
+        );
+
+        let peeled = peel(&partition, "cell", Layer::AccessGated, &context, &sealed).unwrap();
+        assert_eq!(peeled, b"agile");
+    }
+
+    #[test]
+    fn test_sealed_blob_roundtrip_and_parse() {
+        let master = MasterKey::from_bytes([0u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let context = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let options = PeelOptions::default();
+
+        let blob = seal_blob(
+            &partition,
+            "cell",
+            Layer::AccessGated,
+            &context,
+            b"typed",
+            &SealOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(blob.layer(), Layer::AccessGated);
+        assert_eq!(blob.suite(), CipherSuite::default());
+        assert_eq!(
+            peel_blob(&partition, "cell", &context, &blob, &options).unwrap(),
+            b"typed"
+        );
+        assert_eq!(
+            peel(&partition, "cell", Layer::AccessGated, &context, &blob).unwrap(),
+            b"typed"
+        );
+        assert_eq!(blob.as_bytes(), blob.as_ref());
+
+        let bytes = blob.clone().into_bytes();
+        let parsed = SealedBlob::parse(Layer::AccessGated, bytes.clone()).unwrap();
+        assert_eq!(parsed, blob);
+
+        // Too short for two layers, or with an unknown header.
+        let short = bytes[..crypto::MIN_CIPHERTEXT_LEN].to_vec();
+        assert!(matches!(
+            SealedBlob::parse(Layer::AccessGated, short),
+            Err(HexvaultError::MalformedCiphertext)
+        ));
+        let mut garbage = bytes;
+        garbage[0] = 0xff;
+        assert!(matches!(
+            SealedBlob::parse(Layer::AccessGated, garbage),
+            Err(HexvaultError::MalformedCiphertext)
+        ));
+    }
+
+    #[cfg(feature = "insecure-deterministic")]
+    #[test]
+    fn test_deterministic_nonces_reproduce_ciphertext() {
+        let master = MasterKey::from_bytes([5u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let seal_twice = || {
+            let a = seal(&partition, "cell", Layer::AccessGated, &ctx, b"golden").unwrap();
+            let b = seal(&partition, "cell", Layer::AccessGated, &ctx, b"golden").unwrap();
+            (a, b)
+        };
+
+        crypto::set_deterministic_nonces(true);
+        let (a, b) = seal_twice();
+        crypto::set_deterministic_nonces(false);
+        assert_eq!(a, b);
+        assert_eq!(
+            peel(&partition, "cell", Layer::AccessGated, &ctx, &a).unwrap(),
+            b"golden"
+        );
+
+        let (a, b) = seal_twice();
+        assert_ne!(a, b);
+    }
+
     #[test]
     fn test_peel_fails_with_wrong_context() {
         let master = MasterKey::from_bytes([0u8; 32]);
@@ -207,8 +1468,8 @@ mod tests {
         let cell_id = "test-cell";
         let plaintext = b"secret message";
         let context = LayerContext::new(
-            Some("correct-policy".to_string()),
-            Some("correct-session".to_string()),
+            Some("correct-policy".try_into().unwrap()),
+            Some("correct-session".try_into().unwrap()),
         )
         .unwrap();
 
@@ -223,8 +1484,8 @@ mod tests {
 
         // Wrong session ID
         let wrong_context = LayerContext::new(
-            Some("correct-policy".to_string()),
-            Some("wrong-session".to_string()),
+            Some("correct-policy".try_into().unwrap()),
+            Some("wrong-session".try_into().unwrap()),
         )
         .unwrap();
         assert!(peel(
@@ -0,0 +1,494 @@
+//! Chunked streaming encryption.
+//!
+//! Large inputs are sealed a frame at a time, so neither side ever holds
+//! the whole payload in memory. Every layer of a stream is a framed
+//! ciphertext (see `crypto::encrypt_framed`), and each frame is sealed by
+//! every layer in turn, bottom-up, as in `stack::seal`:
+//!
+//! ```text
+//! stream = header, then one or more frames
+//! header = [ magic "HXVS" (4) ][ version (1) ][ layer (1) ][ frame size (4, BE) ][ layer prefix (14) ]...
+//! layer prefix = [ framed header (2) ][ base nonce (12) ], one per layer from layer 0 up
+//! frame  = [ ciphertext (frame size, or less for the last) ][ tag (16) ]... one tag per layer
+//! AAD    = the layer's AAD (see `stack`), with the first 10 header bytes as application AAD
+//! ```
+//!
+//! Every integer is big-endian whatever the host's byte order, so a stream
+//! sealed on one architecture opens on any other. A stream sealed up to
+//! layer 0 with the default frame size is, after its first 10 bytes, an
+//! `encrypt_framed` ciphertext: there is one chunked format.
+//!
+//! Frame nonces bind each frame's index and whether it is the last, and
+//! the random base nonces tie it to its stream, so reordered frames, frames
+//! spliced in from another stream, and a stream cut at a frame boundary
+//! all fail authentication.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use zeroize::Zeroizing;
+
+use crate::crypto::{self, CipherSuite, FrameOpener, FrameSealer, KdfHash, TAG_LEN};
+use crate::error::HexvaultError;
+use crate::keys::PartitionKey;
+use crate::stack::{self, Layer, LayerContext, PeelOptions};
+
+/// The plaintext size of every frame but the last. The same as the frame
+/// size of `encrypt_framed`.
+pub const DEFAULT_CHUNK_SIZE: usize = crypto::FRAME_LEN;
+
+/// Magic bytes opening every stream.
+const MAGIC: &[u8; 4] = b"HXVS";
+
+/// The stream format version.
+const VERSION: u8 = 2;
+
+/// The header bytes before the layer prefixes, bound into every frame.
+const FIXED_HEADER_LEN: usize = MAGIC.len() + 1 + 1 + 4;
+
+/// Whether `data` begins with the stream magic, i.e. was sealed by
+/// `seal_stream` rather than `stack::seal`.
+pub(crate) fn is_stream(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// The largest frame size a stream header may declare. Bounds the buffer
+/// allocated for a frame before its tags have been checked.
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Seal everything `reader` yields into `writer` as a chunked stream.
+///
+/// Every frame is sealed up to `layer` for `cell_id` under the default
+/// cipher suite, so `context` must satisfy `layer`. Plaintext frames are
+/// zeroised once sealed.
+pub fn seal_stream(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    layer: Layer,
+    context: &LayerContext,
+    reader: impl Read,
+    writer: impl Write,
+) -> Result<(), HexvaultError> {
+    seal_stream_with(partition_key, cell_id, layer, context, reader, writer, None)
+}
+
+/// `seal_stream`, mixing `schema_version` into every layer's key.
+pub(crate) fn seal_stream_with(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    layer: Layer,
+    context: &LayerContext,
+    reader: impl Read,
+    writer: impl Write,
+    schema_version: Option<u32>,
+) -> Result<(), HexvaultError> {
+    seal_frames(
+        partition_key,
+        cell_id,
+        layer,
+        context,
+        reader,
+        writer,
+        DEFAULT_CHUNK_SIZE,
+        schema_version,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn seal_frames(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    layer: Layer,
+    context: &LayerContext,
+    mut reader: impl Read,
+    mut writer: impl Write,
+    frame_len: usize,
+    schema_version: Option<u32>,
+) -> Result<(), HexvaultError> {
+    let mut header =
+        Vec::with_capacity(FIXED_HEADER_LEN + layer.up_to().len() * crypto::PREFIX_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(layer.index() as u8);
+    header.extend_from_slice(&(frame_len as u32).to_be_bytes());
+
+    // Every layer's sealer, and so every base nonce, is ready before
+    // anything is written.
+    let (suite, kdf) = (CipherSuite::default(), KdfHash::default());
+    let mut sealers = Vec::with_capacity(layer.up_to().len());
+    for &layer in layer.up_to() {
+        let key = stack::layer_key(
+            partition_key,
+            cell_id,
+            layer,
+            context,
+            suite,
+            kdf,
+            schema_version,
+        )?;
+        let aad = stack::build_aad(cell_id, layer, &header[..FIXED_HEADER_LEN], None);
+        let (sealer, prefix) = FrameSealer::new(suite, kdf, key.as_bytes(), &aad)?;
+        #[cfg(debug_assertions)]
+        if let Some(detector) = partition_key.nonce_detector() {
+            let mut nonce = [0u8; crypto::NONCE_LEN];
+            nonce.copy_from_slice(&prefix[crypto::HEADER_LEN..]);
+            detector.check(crate::nonce_check::key_id(key.as_bytes()), nonce);
+        }
+        header.extend_from_slice(&prefix);
+        sealers.push(sealer);
+    }
+    writer.write_all(&header)?;
+
+    // Frames are sealed in place, in buffers sized up front for every tag so
+    // they never reallocate and strand a copy of the plaintext. One frame of
+    // look-ahead: a frame is the last only if nothing follows it.
+    let sealed_len = frame_len + sealers.len() * TAG_LEN;
+    let mut current = Zeroizing::new(Vec::with_capacity(sealed_len));
+    let mut next = Zeroizing::new(Vec::with_capacity(sealed_len));
+    fill(&mut reader, &mut current, frame_len)?;
+    loop {
+        let last = current.len() < frame_len || {
+            fill(&mut reader, &mut next, frame_len)?;
+            next.is_empty()
+        };
+        for sealer in &mut sealers {
+            sealer.seal_frame(&mut current, last)?;
+        }
+        writer.write_all(&current)?;
+
+        if last {
+            break;
+        }
+        std::mem::swap(&mut current, &mut next);
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Open a stream produced by `seal_stream`, writing the plaintext to
+/// `writer` one frame at a time.
+///
+/// Each frame is authenticated before it is written, but truncation is only
+/// detected at the end of the stream — on error, discard whatever was
+/// written.
+///
+/// # Errors
+///
+/// Returns `HexvaultError::MalformedCiphertext` if the stream is structurally
+/// invalid, and `HexvaultError::DecryptionFailure` if a frame fails
+/// authentication, including one that was reordered, taken from another
+/// stream, or left last by truncation.
+pub fn open_stream(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    context: &LayerContext,
+    reader: impl Read,
+    writer: impl Write,
+) -> Result<(), HexvaultError> {
+    open_stream_with(
+        partition_key,
+        cell_id,
+        context,
+        reader,
+        writer,
+        &PeelOptions::default(),
+    )
+}
+
+/// `open_stream`, checking every layer with `options`' guard and deriving
+/// its key with `options`' schema version.
+pub(crate) fn open_stream_with(
+    partition_key: &PartitionKey,
+    cell_id: &str,
+    context: &LayerContext,
+    mut reader: impl Read,
+    mut writer: impl Write,
+    options: &PeelOptions,
+) -> Result<(), HexvaultError> {
+    let mut header = [0u8; FIXED_HEADER_LEN];
+    read_exact(&mut reader, &mut header)?;
+    if &header[..MAGIC.len()] != MAGIC || header[MAGIC.len()] != VERSION {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+    let layer = Layer::from_index(usize::from(header[MAGIC.len() + 1]))
+        .ok_or(HexvaultError::MalformedCiphertext)?;
+    let frame_len = be_u32(&header[MAGIC.len() + 2..]) as usize;
+    if frame_len == 0 || frame_len > MAX_CHUNK_SIZE {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+
+    // Layers are checked top-down, as `stack::peel` does, before any key is
+    // derived.
+    if let Some(guard) = &options.guard {
+        for &layer in layer.up_to().iter().rev() {
+            guard.check(layer, context)?;
+        }
+    }
+    let mut openers = Vec::with_capacity(layer.up_to().len());
+    for (depth, &layer) in layer.up_to().iter().enumerate() {
+        let mut prefix = [0u8; crypto::PREFIX_LEN];
+        read_exact(&mut reader, &mut prefix)?;
+        let (suite, kdf) = crypto::read_framed_header(&prefix)?;
+        let key = stack::layer_key(
+            partition_key,
+            cell_id,
+            layer,
+            context,
+            suite,
+            kdf,
+            options.schema_version,
+        )?;
+        let aad = stack::build_aad(cell_id, layer, &header, None);
+        // Layer n seals the frame with the tags of the n layers below.
+        openers.push(FrameOpener::new(
+            key.as_bytes(),
+            &prefix,
+            &aad,
+            frame_len + depth * TAG_LEN,
+        )?);
+    }
+
+    // The last frame is whatever follows the full frames, so a stream cut
+    // anywhere ends on a frame that was not sealed as the last.
+    let sealed_len = frame_len + openers.len() * TAG_LEN;
+    let mut current = Zeroizing::new(Vec::with_capacity(sealed_len));
+    let mut next = Zeroizing::new(Vec::with_capacity(sealed_len));
+    fill(&mut reader, &mut current, sealed_len)?;
+    loop {
+        let last = current.len() < sealed_len || {
+            fill(&mut reader, &mut next, sealed_len)?;
+            next.is_empty()
+        };
+        if current.len() < openers.len() * TAG_LEN {
+            return Err(HexvaultError::MalformedCiphertext);
+        }
+        let mut len = current.len();
+        for opener in openers.iter_mut().rev() {
+            len = opener.open_frame(&mut current[..len], last)?;
+        }
+        writer.write_all(&current[..len])?;
+
+        if last {
+            break;
+        }
+        std::mem::swap(&mut current, &mut next);
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Create `path` and run `write` against it, syncing on success. On error
+/// the partial file is removed, so a failed operation never leaves a
+/// half-written output behind.
+pub(crate) fn write_file_or_remove(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), HexvaultError>,
+) -> Result<(), HexvaultError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let result = write(&mut writer).and_then(|()| {
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    });
+    if result.is_err() {
+        drop(writer);
+        // The original error is the one worth reporting.
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(bytes);
+    u32::from_be_bytes(buf)
+}
+
+/// Read until `buf` is full or the input ends. Returns the bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, HexvaultError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
+
+/// Replace `buf`'s contents with up to `len` bytes read from `reader`,
+/// fewer only if the input ends.
+fn fill(reader: &mut impl Read, buf: &mut Vec<u8>, len: usize) -> Result<(), HexvaultError> {
+    buf.resize(len, 0);
+    let filled = read_full(reader, buf)?;
+    buf.truncate(filled);
+    Ok(())
+}
+
+/// Fill `buf` exactly; running out of input means the stream was truncated.
+fn read_exact(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), HexvaultError> {
+    if read_full(reader, buf)? != buf.len() {
+        return Err(HexvaultError::MalformedCiphertext);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::{self, MasterKey};
+
+    /// The header of a stream sealed up to `Layer::AccessGated`.
+    const HEADER_LEN: usize = FIXED_HEADER_LEN + 2 * crypto::PREFIX_LEN;
+
+    fn seal_small(partition: &PartitionKey, ctx: &LayerContext, plaintext: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        seal_frames(
+            partition,
+            "cell",
+            Layer::AccessGated,
+            ctx,
+            plaintext,
+            &mut out,
+            16,
+            None,
+        )
+        .unwrap();
+        out
+    }
+
+    fn open(
+        partition: &PartitionKey,
+        ctx: &LayerContext,
+        sealed: &[u8],
+    ) -> Result<Vec<u8>, HexvaultError> {
+        let mut out = Vec::new();
+        open_stream(partition, "cell", ctx, sealed, &mut out)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn test_roundtrip_at_frame_boundaries() {
+        let master = MasterKey::from_bytes([4u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+
+        for len in [0, 1, 15, 16, 17, 32, 100] {
+            let plaintext: Vec<u8> = (0..len as u8).collect();
+            let sealed = seal_small(&partition, &ctx, &plaintext);
+            assert_eq!(open(&partition, &ctx, &sealed).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_truncation_and_reordering_rejected() {
+        let master = MasterKey::from_bytes([4u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+        let plaintext = [7u8; 40];
+        let sealed = seal_small(&partition, &ctx, &plaintext);
+
+        // Every proper prefix fails, including those ending on a frame boundary.
+        for len in 0..sealed.len() {
+            assert!(open(&partition, &ctx, &sealed[..len]).is_err());
+        }
+
+        // Trailing data after the last frame.
+        let mut extended = sealed.clone();
+        extended.push(0);
+        assert!(open(&partition, &ctx, &extended).is_err());
+
+        // Swap the first two frames, which are both full.
+        let frame_len = 16 + 2 * TAG_LEN;
+        let mut swapped = sealed[..HEADER_LEN].to_vec();
+        swapped.extend_from_slice(&sealed[HEADER_LEN + frame_len..HEADER_LEN + 2 * frame_len]);
+        swapped.extend_from_slice(&sealed[HEADER_LEN..HEADER_LEN + frame_len]);
+        swapped.extend_from_slice(&sealed[HEADER_LEN + 2 * frame_len..]);
+        assert!(matches!(
+            open(&partition, &ctx, &swapped),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+
+        // Splice the first frame of another stream for the same cell.
+        let other = seal_small(&partition, &ctx, &plaintext);
+        let mut spliced = sealed.clone();
+        spliced[HEADER_LEN..HEADER_LEN + frame_len]
+            .copy_from_slice(&other[HEADER_LEN..HEADER_LEN + frame_len]);
+        assert!(matches!(
+            open(&partition, &ctx, &spliced),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+    }
+
+    #[test]
+    fn test_frame_layout_is_big_endian() {
+        let master = MasterKey::from_bytes([4u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::new(Some("policy".try_into().unwrap()), None).unwrap();
+
+        // A frame size of 0x0102 reads differently under either byte order.
+        let mut sealed = Vec::new();
+        seal_frames(
+            &partition,
+            "cell",
+            Layer::AccessGated,
+            &ctx,
+            &[9u8; 300][..],
+            &mut sealed,
+            0x0102,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(&sealed[..4], b"HXVS");
+        assert_eq!(sealed[4..6], [VERSION, 1]);
+        assert_eq!(sealed[6..10], [0x00, 0x00, 0x01, 0x02]);
+        for prefix in sealed[FIXED_HEADER_LEN..HEADER_LEN].chunks(crypto::PREFIX_LEN) {
+            assert_eq!(prefix[0], crypto::FRAMED_FORMAT_VERSION);
+        }
+        // One full frame and a last frame of the remaining 42 bytes, each
+        // with a tag per layer.
+        assert_eq!(
+            sealed.len(),
+            HEADER_LEN + (0x0102 + 2 * TAG_LEN) + (42 + 2 * TAG_LEN)
+        );
+    }
+
+    #[test]
+    fn test_single_layer_stream_is_framed_ciphertext() {
+        let master = MasterKey::from_bytes([4u8; 32]);
+        let partition = keys::derive_partition_key(&master, "p1").unwrap();
+        let ctx = LayerContext::empty();
+        let plaintext = vec![5u8; crypto::FRAME_LEN + 5];
+
+        let mut sealed = Vec::new();
+        seal_stream(
+            &partition,
+            "cell",
+            Layer::AtRest,
+            &ctx,
+            &plaintext[..],
+            &mut sealed,
+        )
+        .unwrap();
+
+        let key = stack::layer_key(
+            &partition,
+            "cell",
+            Layer::AtRest,
+            &ctx,
+            CipherSuite::default(),
+            KdfHash::default(),
+            None,
+        )
+        .unwrap();
+        let header = &sealed[..FIXED_HEADER_LEN];
+        let aad = stack::build_aad("cell", Layer::AtRest, header, None);
+        let opened =
+            crypto::decrypt_framed(key.as_bytes(), &sealed[FIXED_HEADER_LEN..], &aad).unwrap();
+        assert_eq!(opened, plaintext);
+    }
+}
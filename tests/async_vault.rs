@@ -0,0 +1,274 @@
+#![cfg(feature = "async")]
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hexvault::error::HexvaultError;
+use hexvault::stack::{Layer, LayerContext, TokenResolver};
+use hexvault::{generate_master_key, AsyncVault, Vault};
+
+/// Blocks every resolution until the test sends a go-ahead, and fails if
+/// none arrives in time.
+struct GatedResolver(Mutex<Receiver<()>>);
+impl TokenResolver for GatedResolver {
+    fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+        let gate = self.0.lock().unwrap();
+        gate.recv_timeout(Duration::from_secs(5))
+            .map_err(|_| HexvaultError::MissingOrInvalidContext)?;
+        Ok(LayerContext::empty())
+    }
+}
+
+fn gated_vault() -> (AsyncVault, Sender<()>) {
+    let (tx, rx) = mpsc::channel();
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        Arc::new(GatedResolver(Mutex::new(rx))),
+    );
+    (AsyncVault::new(vault), tx)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_seal_does_not_block_the_executor() {
+    let (vault, gate) = gated_vault();
+    let partition = vault.get_partition("p1").await.unwrap();
+    let cell = Arc::new(Mutex::new(partition.create_cell("cell".into()).unwrap()));
+
+    // The seal waits inside the resolver for a go-ahead that only this task
+    // sends. On a single-threaded executor that only works if the seal runs
+    // off the executor thread.
+    let seal = tokio::spawn({
+        let (vault, partition, cell) = (vault.clone(), Arc::clone(&partition), Arc::clone(&cell));
+        async move {
+            vault
+                .seal(
+                    partition,
+                    cell,
+                    "k".into(),
+                    vec![7u8; 1 << 20],
+                    Layer::AtRest,
+                    "".into(),
+                )
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    gate.send(()).unwrap();
+    seal.await.unwrap().unwrap();
+
+    gate.send(()).unwrap();
+    let plaintext = vault
+        .open(
+            Arc::clone(&partition),
+            Arc::clone(&cell),
+            "k".into(),
+            "".into(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(plaintext.as_ref(), vec![7u8; 1 << 20].as_slice());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_traverse_records_audit() {
+    let (vault, gate) = gated_vault();
+    let partition = vault.get_partition("p1").await.unwrap();
+    let source = Arc::new(Mutex::new(partition.create_cell("a".into()).unwrap()));
+    let dest = Arc::new(Mutex::new(partition.create_cell("b".into()).unwrap()));
+
+    gate.send(()).unwrap();
+    vault
+        .seal(
+            Arc::clone(&partition),
+            Arc::clone(&source),
+            "k".into(),
+            b"moving".to_vec(),
+            Layer::AtRest,
+            "".into(),
+        )
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        gate.send(()).unwrap();
+    }
+    let record = vault
+        .traverse(
+            Arc::clone(&partition),
+            Arc::clone(&source),
+            Arc::clone(&partition),
+            Arc::clone(&dest),
+            "k".into(),
+            Layer::AtRest,
+            "".into(),
+            "".into(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(record.dest_cell_id, "b");
+    assert!(dest.lock().unwrap().contains_key("k"));
+    let logged = vault.with_vault(|v| v.audit_log().len()).await.unwrap();
+    assert_eq!(logged, 1);
+
+    assert!(matches!(
+        vault
+            .traverse(
+                Arc::clone(&partition),
+                Arc::clone(&source),
+                Arc::clone(&partition),
+                Arc::clone(&source),
+                "k".into(),
+                Layer::AtRest,
+                "".into(),
+                "".into(),
+            )
+            .await,
+        Err(HexvaultError::InvalidTraversal(_))
+    ));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_traverse_does_not_hold_the_vault() {
+    let (vault, gate) = gated_vault();
+    let partition = vault.get_partition("p1").await.unwrap();
+    let source = Arc::new(Mutex::new(partition.create_cell("a".into()).unwrap()));
+    let dest = Arc::new(Mutex::new(partition.create_cell("b".into()).unwrap()));
+
+    gate.send(()).unwrap();
+    vault
+        .seal(
+            Arc::clone(&partition),
+            Arc::clone(&source),
+            "k".into(),
+            vec![3u8; 1 << 20],
+            Layer::AtRest,
+            "".into(),
+        )
+        .await
+        .unwrap();
+
+    // The traversal waits inside the resolver; the vault stays free for
+    // other operations meanwhile.
+    let traversal = tokio::spawn({
+        let (vault, partition) = (vault.clone(), Arc::clone(&partition));
+        let (source, dest) = (Arc::clone(&source), Arc::clone(&dest));
+        async move {
+            vault
+                .traverse(
+                    Arc::clone(&partition),
+                    source,
+                    partition,
+                    dest,
+                    "k".into(),
+                    Layer::AtRest,
+                    "".into(),
+                    "".into(),
+                )
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let logged = tokio::time::timeout(
+        Duration::from_secs(1),
+        vault.with_vault(|v| v.audit_log().len()),
+    )
+    .await
+    .expect("the vault was held during the traversal")
+    .unwrap();
+    assert_eq!(logged, 0);
+
+    for _ in 0..2 {
+        gate.send(()).unwrap();
+    }
+    assert_eq!(traversal.await.unwrap().unwrap().seq, 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_concurrent_operations_forward_to_async_sink() {
+    use hexvault::audit::{AsyncFileAuditSink, AuditRecord};
+
+    struct OpenResolver;
+    impl TokenResolver for OpenResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            Ok(LayerContext::empty())
+        }
+    }
+
+    let path =
+        std::env::temp_dir().join(format!("hexvault_async_audit_{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let vault = AsyncVault::new(Vault::new(
+        generate_master_key().unwrap(),
+        Arc::new(OpenResolver),
+    ));
+    let sink = AsyncFileAuditSink::new(&path).await.unwrap();
+    vault
+        .with_vault(move |v| v.add_audit_sink(Box::new(sink)))
+        .await
+        .unwrap();
+    let partition = vault.get_partition("p1").await.unwrap();
+
+    // Sixteen seal, traverse, and open sequences on distinct cells, all in
+    // flight at once.
+    let mut tasks = Vec::new();
+    for i in 0..16u8 {
+        let (vault, partition) = (vault.clone(), Arc::clone(&partition));
+        tasks.push(tokio::spawn(async move {
+            let source = Arc::new(Mutex::new(partition.create_cell(format!("a{i}")).unwrap()));
+            let dest = Arc::new(Mutex::new(partition.create_cell(format!("b{i}")).unwrap()));
+            let plaintext = vec![i; 256 * 1024];
+            vault
+                .seal(
+                    Arc::clone(&partition),
+                    Arc::clone(&source),
+                    "k".into(),
+                    plaintext.clone(),
+                    Layer::AtRest,
+                    "".into(),
+                )
+                .await
+                .unwrap();
+            vault
+                .traverse(
+                    Arc::clone(&partition),
+                    source,
+                    Arc::clone(&partition),
+                    Arc::clone(&dest),
+                    "k".into(),
+                    Layer::AtRest,
+                    "".into(),
+                    "".into(),
+                )
+                .await
+                .unwrap();
+            let opened = vault
+                .open(partition, dest, "k".into(), "".into())
+                .await
+                .unwrap();
+            assert_eq!(opened.as_ref(), plaintext.as_slice());
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let (logged, chained) = vault
+        .with_vault(|v| {
+            v.flush_audit().unwrap();
+            (v.audit_log().len(), v.audit_log().verify_chain())
+        })
+        .await
+        .unwrap();
+    assert_eq!(logged, 16);
+    assert!(chained);
+
+    let raw = std::fs::read_to_string(&path).unwrap();
+    let seqs: Vec<u64> = raw
+        .lines()
+        .map(|line| serde_json::from_str::<AuditRecord>(line).unwrap().seq)
+        .collect();
+    assert_eq!(seqs, (1..=16).collect::<Vec<_>>());
+    let _ = std::fs::remove_file(&path);
+}
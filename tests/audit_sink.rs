@@ -40,8 +40,8 @@ fn test_forward_sink_receives_records() {
     vault.add_audit_sink(Box::new(SharedVecSink::new(Arc::clone(&records))));
 
     let partition = vault.get_partition("test").unwrap();
-    let mut cell_a = partition.create_cell("cell-x".into());
-    let mut cell_b = partition.create_cell("cell-y".into());
+    let mut cell_a = partition.create_cell("cell-x".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-y".into()).unwrap();
     let token = "";
 
     partition
@@ -69,3 +69,779 @@ fn test_forward_sink_receives_records() {
     assert_eq!(collected[0].source_cell_id, "cell-x");
     assert_eq!(collected[0].dest_cell_id, "cell-y");
 }
+
+/// Seals one payload and reseals it twice, so `vault`'s sinks see two
+/// records.
+fn emit_two_records(vault: &mut Vault) {
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell = partition.create_cell("tenant-a".into()).unwrap();
+    partition
+        .seal(&mut cell, "key", b"secret", Layer::AtRest, "")
+        .unwrap();
+    for _ in 0..2 {
+        vault.reseal(&partition, &mut cell, "key", "").unwrap();
+    }
+}
+
+#[test]
+fn test_file_sink_with_custom_encoder() {
+    use hexvault::audit::{AuditEncoder, FileAuditSink};
+
+    /// A fixed-width format: an 8-digit sequence number and the cell ID
+    /// padded to 16 bytes.
+    struct FixedWidth;
+    impl AuditEncoder for FixedWidth {
+        fn encode(&self, record: &AuditRecord) -> Vec<u8> {
+            format!("{:08}{:<16}\n", record.seq, record.source_cell_id).into_bytes()
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!(
+        "hexvault_fixed_width_audit_{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let mut vault = Vault::new(generate_master_key().unwrap(), Arc::new(DummyResolver));
+    let sink = FileAuditSink::with_encoder(&path, FixedWidth).unwrap();
+    vault.add_audit_sink(Box::new(sink));
+    emit_two_records(&mut vault);
+
+    let raw = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(raw, "00000001tenant-a        \n00000002tenant-a        \n");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_file_sink_with_cbor_encoder() {
+    use hexvault::audit::{CborEncoder, FileAuditSink};
+
+    let path = std::env::temp_dir().join(format!("hexvault_cbor_audit_{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut vault = Vault::new(generate_master_key().unwrap(), Arc::new(DummyResolver));
+    let sink = FileAuditSink::with_encoder(&path, CborEncoder).unwrap();
+    vault.add_audit_sink(Box::new(sink));
+    emit_two_records(&mut vault);
+
+    let raw = std::fs::read(&path).unwrap();
+    let mut reader = raw.as_slice();
+    let mut records = Vec::new();
+    while !reader.is_empty() {
+        let record: AuditRecord = ciborium::from_reader(&mut reader).unwrap();
+        records.push(record);
+    }
+    let decoded: Vec<_> = records.iter().map(|r| &r.entry_hash).collect();
+    let logged: Vec<_> = vault.audit_log().iter().map(|r| &r.entry_hash).collect();
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded, logged);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "current_thread")]
+async fn test_async_file_sink_with_custom_encoder() {
+    use hexvault::audit::{AsyncFileAuditSink, AuditEncoder};
+
+    /// The sequence number and cell ID of each record, one per line.
+    struct SeqAndCell;
+    impl AuditEncoder for SeqAndCell {
+        fn encode(&self, record: &AuditRecord) -> Vec<u8> {
+            format!("{} {}\n", record.seq, record.source_cell_id).into_bytes()
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!(
+        "hexvault_async_encoded_audit_{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let mut vault = Vault::new(generate_master_key().unwrap(), Arc::new(DummyResolver));
+    let sink = AsyncFileAuditSink::with_encoder(&path, SeqAndCell)
+        .await
+        .unwrap();
+    vault.add_audit_sink(Box::new(sink));
+    emit_two_records(&mut vault);
+
+    // Flushing waits for the sink's task, so it must not block the executor.
+    tokio::task::spawn_blocking(move || vault.flush_audit())
+        .await
+        .unwrap()
+        .unwrap();
+    let raw = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(raw, "1 tenant-a\n2 tenant-a\n");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_encrypted_file_sink_roundtrip() {
+    use hexvault::audit::EncryptedFileAuditSink;
+    use hexvault::keys::MasterKey;
+
+    let path = std::env::temp_dir().join(format!(
+        "hexvault_encrypted_audit_{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let mut vault = Vault::new(
+        MasterKey::from_bytes([7u8; 32]),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let sink = EncryptedFileAuditSink::new(&path, &MasterKey::from_bytes([7u8; 32])).unwrap();
+    vault.add_audit_sink(Box::new(sink));
+
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("tenant-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("tenant-b".into()).unwrap();
+    partition
+        .seal(&mut cell_a, "key", b"secret", Layer::AtRest, "")
+        .unwrap();
+    for _ in 0..2 {
+        vault
+            .traverse(
+                &partition,
+                &cell_a,
+                &partition,
+                &mut cell_b,
+                "key",
+                Layer::AtRest,
+                "",
+                "",
+            )
+            .unwrap();
+    }
+
+    // Cell IDs are not visible in the file.
+    let raw = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(raw.lines().count(), 2);
+    assert!(!raw.contains("tenant-a"));
+
+    // Key holders can recover and verify the chain.
+    let log = EncryptedFileAuditSink::load(&path, &MasterKey::from_bytes([7u8; 32])).unwrap();
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.iter().next().unwrap().source_cell_id, "tenant-a");
+    assert!(log.verify_chain());
+
+    // A different master key cannot read it.
+    assert!(matches!(
+        EncryptedFileAuditSink::load(&path, &MasterKey::from_bytes([8u8; 32])),
+        Err(HexvaultError::DecryptionFailure)
+    ));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_sweep_expired_removes_and_audits() {
+    use hexvault::audit::AuditEvent;
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+
+    partition
+        .seal(&mut cell_a, "old", b"x", Layer::AtRest, "")
+        .unwrap();
+    partition
+        .seal(&mut cell_a, "keep", b"y", Layer::AtRest, "")
+        .unwrap();
+    partition
+        .seal(&mut cell_b, "old", b"z", Layer::AtRest, "")
+        .unwrap();
+
+    let cutoff = chrono::Utc::now() + chrono::Duration::seconds(1);
+    let removed = vault
+        .sweep_expired([&mut cell_a, &mut cell_b], |_, key, info| {
+            key == "old" && info.created_at < cutoff
+        })
+        .unwrap();
+
+    assert_eq!(removed, 2);
+    assert!(partition.open(&cell_a, "old", "").is_err());
+    assert_eq!(partition.open(&cell_a, "keep", "").unwrap(), b"y");
+    assert!(partition.open(&cell_b, "old", "").is_err());
+
+    assert_eq!(vault.audit_log_len(), 2);
+    assert!(vault
+        .audit_log()
+        .iter()
+        .all(|r| r.event == AuditEvent::Remove && r.source_cell_id == r.dest_cell_id));
+    assert!(vault.audit_log().verify_chain());
+}
+
+#[test]
+fn test_purge_wipes_cells_and_flushes_audit() {
+    use hexvault::audit::AuditEvent;
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let records = Arc::new(Mutex::new(Vec::new()));
+    vault.add_audit_sink(Box::new(SharedVecSink::new(Arc::clone(&records))));
+
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+    let mut empty = partition.create_cell("empty".into()).unwrap();
+    for key in ["k1", "k2"] {
+        partition
+            .seal(&mut cell_a, key, b"x", Layer::AtRest, "")
+            .unwrap();
+    }
+    partition
+        .seal(&mut cell_b, "k", b"y", Layer::AtRest, "")
+        .unwrap();
+
+    let wiped = vault.purge([&mut cell_a, &mut cell_b, &mut empty]).unwrap();
+
+    assert_eq!(wiped, 3);
+    assert!(partition.open(&cell_a, "k1", "").is_err());
+    assert_eq!(cell_a.keys().count() + cell_b.keys().count(), 0);
+
+    // Every removal reached the sink before the log was cleared.
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 3);
+    assert!(records.iter().all(|r| r.event == AuditEvent::Remove));
+}
+
+#[test]
+fn test_seal_with_ttl_expires_on_read_and_sweep() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+
+    let now = chrono::Utc::now();
+    partition
+        .seal_with_ttl(&mut cell, "past", b"x", Layer::AtRest, "", now)
+        .unwrap();
+    partition
+        .seal_with_ttl(
+            &mut cell,
+            "future",
+            b"y",
+            Layer::AtRest,
+            "",
+            now + chrono::Duration::hours(1),
+        )
+        .unwrap();
+
+    // Lazy: the read is refused once the deadline passes.
+    assert!(matches!(
+        partition.open(&cell, "past", ""),
+        Err(HexvaultError::Expired)
+    ));
+    assert_eq!(partition.open(&cell, "future", "").unwrap(), b"y");
+
+    // Eager: a sweep on the recorded expiry removes it.
+    let removed = vault
+        .sweep_expired([&mut cell], |_, _, info| {
+            info.expires_at.is_some_and(|at| at <= chrono::Utc::now())
+        })
+        .unwrap();
+    assert_eq!(removed, 1);
+    assert!(!cell.contains_key("past"));
+    assert!(cell.contains_key("future"));
+}
+
+#[test]
+fn test_clock_drives_timestamps_and_expiry() {
+    use hexvault::clock::ManualClock;
+
+    let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+    let clock = Arc::new(ManualClock::new(start));
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::with_clock(master, Arc::new(DummyResolver), clock.clone());
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+
+    let expires_at = start + chrono::Duration::minutes(5);
+    partition
+        .seal_with_ttl(&mut cell_a, "k", b"v", Layer::AtRest, "", expires_at)
+        .unwrap();
+    let (_, info) = cell_a.infos().next().unwrap();
+    assert_eq!(info.created_at, start);
+
+    clock.advance(chrono::Duration::minutes(1));
+    let record = vault
+        .traverse(
+            &partition,
+            &cell_a,
+            &partition,
+            &mut cell_b,
+            "k",
+            Layer::AtRest,
+            "",
+            "",
+        )
+        .unwrap();
+    assert_eq!(record.timestamp, start + chrono::Duration::minutes(1));
+
+    clock.set(expires_at);
+    assert!(matches!(
+        partition.open(&cell_a, "k", ""),
+        Err(HexvaultError::Expired)
+    ));
+    // The traversal carried the expiry along.
+    assert!(matches!(
+        partition.open(&cell_b, "k", ""),
+        Err(HexvaultError::Expired)
+    ));
+}
+
+#[test]
+fn test_downgrade_is_audited() {
+    use hexvault::audit::AuditEvent;
+
+    struct FullResolver;
+    impl TokenResolver for FullResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            LayerContext::new(Some("policy".try_into()?), Some("session".try_into()?))
+        }
+    }
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(FullResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+
+    partition
+        .seal(&mut cell, "k", b"data", Layer::SessionBound, "")
+        .unwrap();
+
+    // Not a downgrade: rejected and not audited.
+    assert!(vault
+        .downgrade(&partition, &mut cell, "k", Layer::SessionBound, "")
+        .is_err());
+    assert_eq!(vault.audit_log_len(), 0);
+
+    vault
+        .downgrade(&partition, &mut cell, "k", Layer::AtRest, "")
+        .unwrap();
+    assert_eq!(partition.open(&cell, "k", "").unwrap(), b"data");
+
+    let record = vault.audit_log().iter().next().unwrap();
+    assert_eq!(record.layer, Layer::AtRest);
+    assert_eq!(
+        record.event,
+        AuditEvent::Downgrade {
+            from: Layer::SessionBound
+        }
+    );
+    assert!(vault.audit_log().verify_chain());
+}
+
+#[test]
+fn test_reseal_refreshes_nonces_and_is_audited() {
+    use hexvault::audit::AuditEvent;
+    use hexvault::cell::Cell;
+
+    struct FullResolver;
+    impl TokenResolver for FullResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            LayerContext::new(Some("policy".try_into()?), Some("session".try_into()?))
+        }
+    }
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(FullResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+
+    partition
+        .seal(&mut cell, "k", b"data", Layer::AccessGated, "")
+        .unwrap();
+    let snapshot = |cell: &Cell| {
+        let mut bytes = Vec::new();
+        cell.write_to(&mut bytes).unwrap();
+        bytes
+    };
+    let info = cell.infos().next().unwrap().1;
+    let before = snapshot(&cell);
+
+    vault.reseal(&partition, &mut cell, "k", "").unwrap();
+    assert_ne!(snapshot(&cell), before);
+    assert_eq!(cell.infos().next().unwrap().1, info);
+    assert_eq!(partition.open(&cell, "k", "").unwrap(), b"data");
+
+    assert!(vault.reseal(&partition, &mut cell, "missing", "").is_err());
+    assert_eq!(vault.audit_log_len(), 1);
+    let record = vault.audit_log().iter().next().unwrap();
+    assert_eq!(record.event, AuditEvent::Reseal);
+    assert_eq!(record.layer, Layer::AccessGated);
+    assert!(vault.audit_log().verify_chain());
+}
+
+#[test]
+fn test_flush_and_close_reach_every_sink() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingSink {
+        flushes: Arc<AtomicUsize>,
+        closes: Arc<AtomicUsize>,
+    }
+    impl AuditSink for CountingSink {
+        fn append(&mut self, _record: AuditRecord) {}
+        fn flush(&mut self) -> Result<(), std::io::Error> {
+            self.flushes.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+        fn close(self: Box<Self>) {
+            self.closes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let flushes = Arc::new(AtomicUsize::new(0));
+    let closes = Arc::new(AtomicUsize::new(0));
+    for _ in 0..2 {
+        vault.add_audit_sink(Box::new(CountingSink {
+            flushes: Arc::clone(&flushes),
+            closes: Arc::clone(&closes),
+        }));
+    }
+    // A sink relying on the default no-op flush.
+    vault.add_audit_sink(Box::new(SharedVecSink::new(Arc::default())));
+
+    vault.flush_audit().unwrap();
+    assert_eq!(flushes.load(Ordering::SeqCst), 2);
+
+    vault.close_audit();
+    assert_eq!(closes.load(Ordering::SeqCst), 2);
+
+    // Closed sinks are detached.
+    vault.flush_audit().unwrap();
+    assert_eq!(flushes.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_per_cell_audit_logs() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+
+    let mut tenant_a = vault
+        .create_audited_cell(&partition, "tenant-a".into())
+        .unwrap();
+    let mut tenant_b = vault
+        .create_audited_cell(&partition, "tenant-b".into())
+        .unwrap();
+    let mut shared = partition.create_cell("shared".into()).unwrap();
+
+    partition
+        .seal(&mut tenant_a, "k", b"a", Layer::AtRest, "")
+        .unwrap();
+    partition
+        .seal(&mut shared, "k", b"s", Layer::AtRest, "")
+        .unwrap();
+
+    vault
+        .traverse(
+            &partition,
+            &tenant_a,
+            &partition,
+            &mut shared,
+            "k",
+            Layer::AtRest,
+            "",
+            "",
+        )
+        .unwrap();
+    vault
+        .traverse(
+            &partition,
+            &shared,
+            &partition,
+            &mut tenant_b,
+            "k",
+            Layer::AtRest,
+            "",
+            "",
+        )
+        .unwrap();
+
+    assert_eq!(vault.audit_log_len(), 2);
+
+    let log_a = vault.cell_audit("tenant-a").unwrap();
+    assert_eq!(log_a.len(), 1);
+    assert_eq!(log_a.iter().next().unwrap().dest_cell_id, "shared");
+    assert!(log_a.verify_chain());
+
+    let log_b = vault.cell_audit("tenant-b").unwrap();
+    assert_eq!(log_b.len(), 1);
+    assert_eq!(log_b.iter().next().unwrap().source_cell_id, "shared");
+
+    assert!(vault.cell_audit("shared").is_none());
+}
+
+#[test]
+fn test_iter_audit_since_resumes_after_drain() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    for key in ["a", "b", "c"] {
+        partition
+            .seal(&mut cell, key, b"v", Layer::AtRest, "")
+            .unwrap();
+    }
+
+    vault.reseal(&partition, &mut cell, "a", "").unwrap();
+    vault.reseal(&partition, &mut cell, "b", "").unwrap();
+    let shipped: Vec<u64> = vault.iter_audit_since(0).map(|r| r.seq).collect();
+    assert_eq!(shipped, [1, 2]);
+    let last = *shipped.last().unwrap();
+
+    // Draining does not reset the sequence.
+    vault.drain_audit();
+    vault.reseal(&partition, &mut cell, "c", "").unwrap();
+    let next: Vec<u64> = vault.iter_audit_since(last).map(|r| r.seq).collect();
+    assert_eq!(next, [3]);
+    assert_eq!(vault.iter_audit_since(3).count(), 0);
+    assert!(vault.audit_log().verify_chain());
+}
+
+#[test]
+fn test_audit_tail_returns_most_recent() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "a", b"v", Layer::AtRest, "")
+        .unwrap();
+    assert_eq!(vault.audit_tail(2).count(), 0);
+
+    for _ in 0..4 {
+        vault.reseal(&partition, &mut cell, "a", "").unwrap();
+    }
+    let tail: Vec<u64> = vault.audit_tail(2).map(|r| r.seq).collect();
+    assert_eq!(tail, [3, 4]);
+    let newest: Vec<u64> = vault.audit_tail(3).rev().map(|r| r.seq).collect();
+    assert_eq!(newest, [4, 3, 2]);
+    assert_eq!(vault.audit_tail(10).count(), 4);
+    assert_eq!(vault.audit_tail(0).count(), 0);
+}
+
+#[test]
+fn test_actor_is_recorded_and_chained() {
+    use hexvault::edge::TraversalOptions;
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut a = partition.create_cell("a".into()).unwrap();
+    let mut b = partition.create_cell("b".into()).unwrap();
+    partition
+        .seal(&mut a, "k", b"v", Layer::AtRest, "")
+        .unwrap();
+
+    vault.set_actor(Some("svc-batch".into()));
+    vault.reseal(&partition, &mut a, "k", "").unwrap();
+    let options = TraversalOptions {
+        actor: Some("alice".into()),
+        ..TraversalOptions::default()
+    };
+    vault
+        .traverse_with_options(
+            &partition,
+            &a,
+            &partition,
+            &mut b,
+            "k",
+            Layer::AtRest,
+            "",
+            "",
+            options,
+        )
+        .unwrap();
+    vault.set_actor(None);
+    vault.reseal(&partition, &mut b, "k", "").unwrap();
+
+    let actors: Vec<Option<&str>> = vault
+        .audit_log()
+        .iter()
+        .map(|r| r.actor.as_deref())
+        .collect();
+    assert_eq!(actors, [Some("svc-batch"), Some("alice"), None]);
+    assert!(vault.audit_log().verify_chain());
+
+    // Rewriting the actor after the fact breaks the chain.
+    let json = serde_json::to_string(vault.audit_log()).unwrap();
+    let forged: hexvault::audit::AuditLog =
+        serde_json::from_str(&json.replace("\"alice\"", "\"mallory\"")).unwrap();
+    assert!(!forged.verify_chain());
+}
+
+#[test]
+fn test_panicking_sink_is_isolated() {
+    use hexvault::audit::{AuditEvent, AuditLog};
+
+    struct PanickingSink;
+    impl AuditSink for PanickingSink {
+        fn append(&mut self, _record: AuditRecord) {
+            panic!("sink is down");
+        }
+    }
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let records = Arc::new(Mutex::new(Vec::new()));
+    vault.add_audit_sink(Box::new(PanickingSink));
+    vault.add_audit_sink(Box::new(SharedVecSink::new(Arc::clone(&records))));
+
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-x".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-y".into()).unwrap();
+    partition
+        .seal(&mut cell_a, "key", b"secret", Layer::AtRest, "")
+        .unwrap();
+
+    // The traversal completes; the failure is only counted.
+    vault
+        .traverse(
+            &partition,
+            &cell_a,
+            &partition,
+            &mut cell_b,
+            "key",
+            Layer::AtRest,
+            "",
+            "",
+        )
+        .unwrap();
+    assert_eq!(vault.audit_log().len(), 1);
+    assert_eq!(vault.audit_log().sink_failures(), 1);
+    assert_eq!(records.lock().unwrap().len(), 1);
+
+    // A direct append reports it.
+    let mut log = AuditLog::new();
+    log.add_forward_sink(Box::new(PanickingSink));
+    let result = log.append(AuditRecord {
+        dest_cell_id: "b".into(),
+        ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, chrono::Utc::now())
+    });
+    assert!(matches!(result, Err(HexvaultError::AuditSinkFailed(1))));
+    assert_eq!(log.len(), 1);
+    assert!(log.verify_chain());
+}
+
+#[test]
+fn test_ring_buffer_sink_keeps_latest_records() {
+    use hexvault::audit::{AuditEvent, AuditLog, RingBufferAuditSink};
+
+    let sink = RingBufferAuditSink::new(2);
+    let mut log = AuditLog::new();
+    log.add_forward_sink(Box::new(sink.clone()));
+    for dest in ["b", "c", "d"] {
+        log.append(AuditRecord {
+            dest_cell_id: dest.into(),
+            ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, chrono::Utc::now())
+        })
+        .unwrap();
+    }
+
+    let kept = sink.records();
+    assert_eq!(
+        kept.iter()
+            .map(|r| r.dest_cell_id.as_str())
+            .collect::<Vec<_>>(),
+        ["c", "d"]
+    );
+    assert_eq!(kept[1].seq, log.iter().last().unwrap().seq);
+
+    let mut empty = RingBufferAuditSink::new(0);
+    empty.append(kept[0].clone());
+    assert!(empty.records().is_empty());
+}
+
+#[test]
+fn test_replace_audit_sinks_flushes_old_sinks() {
+    use hexvault::audit::RingBufferAuditSink;
+
+    /// Holds records until flushed; relies on the default `close`.
+    struct BufferingSink {
+        pending: Vec<AuditRecord>,
+        flushed: Arc<Mutex<Vec<AuditRecord>>>,
+    }
+    impl AuditSink for BufferingSink {
+        fn append(&mut self, record: AuditRecord) {
+            self.pending.push(record);
+        }
+        fn flush(&mut self) -> Result<(), std::io::Error> {
+            self.flushed.lock().unwrap().append(&mut self.pending);
+            Ok(())
+        }
+    }
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let flushed = Arc::new(Mutex::new(Vec::new()));
+    vault.add_audit_sink(Box::new(BufferingSink {
+        pending: Vec::new(),
+        flushed: Arc::clone(&flushed),
+    }));
+
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "k", b"v", Layer::AtRest, "")
+        .unwrap();
+    vault.reseal(&partition, &mut cell, "k", "").unwrap();
+    assert!(flushed.lock().unwrap().is_empty());
+
+    let replacement = RingBufferAuditSink::new(8);
+    vault.replace_audit_sinks(vec![Box::new(replacement.clone())]);
+    assert_eq!(flushed.lock().unwrap().len(), 1);
+
+    vault.reseal(&partition, &mut cell, "k", "").unwrap();
+    assert_eq!(flushed.lock().unwrap().len(), 1);
+    assert_eq!(replacement.records().len(), 1);
+    assert_eq!(
+        replacement.records()[0].seq,
+        vault.audit_log().iter().last().unwrap().seq
+    );
+
+    // An empty replacement detaches every sink.
+    vault.replace_audit_sinks(Vec::new());
+    vault.reseal(&partition, &mut cell, "k", "").unwrap();
+    assert_eq!(replacement.records().len(), 1);
+}
+
+#[test]
+fn test_flushed_records_are_stamped_and_mirrored() {
+    use hexvault::audit::AuditEvent;
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    vault.create_audited_cell(&partition, "a".into()).unwrap();
+    vault.set_actor(Some("svc-worker".into()));
+
+    let appender = vault.audit_log().pending_appender();
+    std::thread::spawn(move || {
+        appender.stage(AuditRecord {
+            dest_cell_id: "b".into(),
+            ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, chrono::Utc::now())
+        });
+    })
+    .join()
+    .unwrap();
+    assert_eq!(vault.flush_pending_audit().unwrap(), 1);
+
+    let record = vault.audit_log().iter().next().unwrap();
+    assert_eq!(record.actor.as_deref(), Some("svc-worker"));
+    let mirrored = vault.cell_audit("a").unwrap();
+    assert_eq!(mirrored.len(), 1);
+    assert_eq!(
+        mirrored.iter().next().unwrap().actor.as_deref(),
+        Some("svc-worker")
+    );
+    assert!(vault.audit_log().verify_chain());
+}
@@ -0,0 +1,64 @@
+use hexvault::error::HexvaultError;
+use hexvault::partition::BatchMode;
+use hexvault::stack::{Layer, LayerContext, TokenResolver};
+use hexvault::{generate_master_key, Vault};
+
+struct DummyResolver;
+impl TokenResolver for DummyResolver {
+    fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+        Ok(LayerContext::empty())
+    }
+}
+
+#[test]
+fn test_seal_many_reports_each_item() {
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    let items: [(&str, &[u8]); 3] = [("a", b"1"), ("__hexvault:x", b"2"), ("c", b"3")];
+
+    // Fail-fast stops at the reserved key, keeping what was sealed.
+    let outcome = partition
+        .seal_many(&mut cell, items, Layer::AtRest, "", BatchMode::FailFast)
+        .unwrap();
+    assert!(!outcome.is_complete());
+    assert_eq!(outcome.succeeded(), 1);
+    assert_eq!(outcome.skipped, 1);
+    assert!(cell.contains_key("a") && !cell.contains_key("c"));
+
+    let outcome = partition
+        .seal_many(
+            &mut cell,
+            items,
+            Layer::AtRest,
+            "",
+            BatchMode::ContinueOnError,
+        )
+        .unwrap();
+    assert_eq!(outcome.succeeded(), 2);
+    assert_eq!(outcome.skipped, 0);
+    let failures: Vec<_> = outcome.failures().map(|(i, _)| i).collect();
+    assert_eq!(failures, [1]);
+    assert!(matches!(
+        outcome.results[1],
+        Err(HexvaultError::ReservedKey)
+    ));
+
+    let outcome = partition
+        .open_many(&cell, ["a", "missing", "c"], "", BatchMode::ContinueOnError)
+        .unwrap();
+    assert!(matches!(
+        outcome.results[1],
+        Err(HexvaultError::KeyNotFound(_))
+    ));
+    assert_eq!(outcome.results[0].as_deref().unwrap(), b"1");
+    assert_eq!(outcome.results[2].as_deref().unwrap(), b"3");
+
+    let outcome = partition
+        .open_many(&cell, ["a", "c"], "", BatchMode::FailFast)
+        .unwrap();
+    assert!(outcome.is_complete());
+}
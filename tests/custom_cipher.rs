@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use hexvault::error::HexvaultError;
+use hexvault::stack::{Layer, LayerContext, SealOptions, TokenResolver};
+use hexvault::{generate_master_key, register_global_cipher, CipherImpl, CipherSuite, Vault};
+
+/// A toy AEAD: XOR with a key- and nonce-derived keystream, and a tag that
+/// mixes the key, nonce, AAD, and ciphertext. Not secure; it only exercises
+/// the registry. Registrations are process-wide, so each test names its own.
+struct XorCipher(&'static str);
+
+impl XorCipher {
+    fn keystream(key: &[u8], nonce: &[u8], i: usize) -> u8 {
+        key[i % key.len()] ^ nonce[i % nonce.len()] ^ (i as u8)
+    }
+
+    fn tag(key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+        let mut state = 0xcbf2_9ce4_8422_2325u64;
+        for &b in key.iter().chain(nonce).chain(aad).chain(ciphertext) {
+            state = (state ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3);
+        }
+        let mut tag = [0u8; 16];
+        tag[..8].copy_from_slice(&state.to_be_bytes());
+        tag[8..].copy_from_slice(&state.rotate_left(29).to_le_bytes());
+        tag
+    }
+}
+
+impl CipherImpl for XorCipher {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn key_len(&self) -> usize {
+        32
+    }
+
+    fn encrypt(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> Result<(), HexvaultError> {
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b ^= Self::keystream(key, nonce, i);
+        }
+        let tag = Self::tag(key, nonce, aad, buf);
+        buf.extend_from_slice(&tag);
+        Ok(())
+    }
+
+    fn decrypt(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, HexvaultError> {
+        let len = buf.len() - 16;
+        if Self::tag(key, nonce, aad, &buf[..len]) != buf[len..] {
+            return Err(HexvaultError::DecryptionFailure);
+        }
+        for (i, b) in buf[..len].iter_mut().enumerate() {
+            *b ^= Self::keystream(key, nonce, i);
+        }
+        Ok(len)
+    }
+}
+
+struct PolicyResolver;
+
+impl TokenResolver for PolicyResolver {
+    fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+        LayerContext::new(Some(token.try_into()?), None)
+    }
+}
+
+#[test]
+fn test_custom_cipher_roundtrip() {
+    let vault = Vault::new(generate_master_key().unwrap(), Arc::new(PolicyResolver));
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    let suite = CipherSuite::Custom(9);
+
+    // Nothing is registered under the ID yet.
+    let options = SealOptions {
+        suite,
+        ..SealOptions::default()
+    };
+    assert!(matches!(
+        partition.seal_with_options(
+            &mut cell,
+            "k",
+            b"agile",
+            Layer::AccessGated,
+            "policy",
+            &options
+        ),
+        Err(HexvaultError::InvalidCipherSuite(9))
+    ));
+
+    register_global_cipher(9, Arc::new(XorCipher("toy-roundtrip"))).unwrap();
+    partition
+        .seal_with_options(
+            &mut cell,
+            "k",
+            b"agile",
+            Layer::AccessGated,
+            "policy",
+            &options,
+        )
+        .unwrap();
+    let (_, info) = cell.infos().next().unwrap();
+    assert_eq!(info.suite, Some(suite));
+    assert_eq!(partition.open(&cell, "k", "policy").unwrap(), b"agile");
+    assert!(partition.open(&cell, "k", "other").is_err());
+}
+
+#[test]
+fn test_custom_suite_rejects_builtin_ids() {
+    let vault = Vault::new(generate_master_key().unwrap(), Arc::new(PolicyResolver));
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+
+    // IDs 1-3 belong to the built-in suites, and 0 and 16 fit no header.
+    for id in [0, 1, 2, 3, 16] {
+        let options = SealOptions {
+            suite: CipherSuite::Custom(id),
+            ..SealOptions::default()
+        };
+        let result = partition.seal_with_options(
+            &mut cell,
+            "k",
+            b"agile",
+            Layer::AccessGated,
+            "policy",
+            &options,
+        );
+        assert!(
+            matches!(result, Err(HexvaultError::InvalidCipherSuite(got)) if got == id),
+            "Custom({id}) sealed: {result:?}"
+        );
+    }
+    assert!(!cell.contains_key("k"));
+}
+
+#[test]
+fn test_register_global_cipher_rejects_bad_registrations() {
+    // Built-in IDs, IDs that do not fit the header nibble, and taken names.
+    for id in [0, 1, 3, 16] {
+        assert!(matches!(
+            register_global_cipher(id, Arc::new(XorCipher("toy-bad-id"))),
+            Err(HexvaultError::InvalidCipherSuite(_))
+        ));
+    }
+    register_global_cipher(12, Arc::new(XorCipher("toy-taken"))).unwrap();
+    assert!(register_global_cipher(12, Arc::new(XorCipher("toy-other"))).is_err());
+    assert!(register_global_cipher(13, Arc::new(XorCipher("toy-taken"))).is_err());
+    assert!(register_global_cipher(13, Arc::new(XorCipher("aes-128-gcm"))).is_err());
+}
@@ -17,8 +17,8 @@ fn test_successful_traversal() {
     let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
 
     let partition = vault.get_partition("test").unwrap();
-    let mut cell_a = partition.create_cell("cell-a".into());
-    let mut cell_b = partition.create_cell("cell-b".into());
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
 
     let token = "";
     let plaintext = b"moving target";
@@ -55,8 +55,8 @@ fn test_audit_logging() {
     let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
 
     let partition = vault.get_partition("test").unwrap();
-    let mut cell_a = partition.create_cell("source".into());
-    let mut cell_b = partition.create_cell("dest".into());
+    let mut cell_a = partition.create_cell("source".into()).unwrap();
+    let mut cell_b = partition.create_cell("dest".into()).unwrap();
     let token = "";
 
     // 1. Perform traversal.
@@ -88,3 +88,570 @@ fn test_audit_logging() {
     // 3. Verify the audit chain is intact.
     assert!(log.verify_chain(), "Audit chain should be valid");
 }
+
+#[test]
+fn test_traversal_with_application_aad() {
+    use hexvault::edge::TraversalOptions;
+    use hexvault::stack::{PeelOptions, SealOptions};
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+    let token = "";
+
+    let seal_options = SealOptions {
+        aad: b"tenant-sig-a".to_vec(),
+        ..SealOptions::default()
+    };
+    partition
+        .seal_with_options(
+            &mut cell_a,
+            "data",
+            b"bound",
+            Layer::AtRest,
+            token,
+            &seal_options,
+        )
+        .unwrap();
+
+    // Wrong source AAD fails authentication and moves nothing.
+    let wrong = TraversalOptions {
+        source_aad: b"tenant-sig-x".to_vec(),
+        ..TraversalOptions::default()
+    };
+    assert!(vault
+        .traverse_with_options(
+            &partition,
+            &cell_a,
+            &partition,
+            &mut cell_b,
+            "data",
+            Layer::AtRest,
+            token,
+            token,
+            wrong,
+        )
+        .is_err());
+    assert_eq!(vault.audit_log_len(), 0);
+
+    let options = TraversalOptions {
+        source_aad: b"tenant-sig-a".to_vec(),
+        dest_aad: b"tenant-sig-b".to_vec(),
+        ..TraversalOptions::default()
+    };
+    vault
+        .traverse_with_options(
+            &partition,
+            &cell_a,
+            &partition,
+            &mut cell_b,
+            "data",
+            Layer::AtRest,
+            token,
+            token,
+            options,
+        )
+        .unwrap();
+
+    // The destination is bound to the new AAD, not the source's.
+    assert!(partition.open(&cell_b, "data", token).is_err());
+    let peel = PeelOptions {
+        aad: b"tenant-sig-b".to_vec(),
+        ..PeelOptions::default()
+    };
+    assert_eq!(
+        partition
+            .open_with_options(&cell_b, "data", token, &peel)
+            .unwrap(),
+        b"bound"
+    );
+}
+
+#[test]
+fn test_traverse_enforces_min_dest_layer() {
+    use hexvault::edge::TraversalOptions;
+
+    struct PolicyResolver;
+    impl TokenResolver for PolicyResolver {
+        fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+            match token {
+                "" => Ok(LayerContext::empty()),
+                policy => LayerContext::new(Some(policy.try_into()?), None),
+            }
+        }
+    }
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(PolicyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut staging = partition.create_cell("staging".into()).unwrap();
+    let mut sensitive = partition.create_cell("sensitive".into()).unwrap();
+    partition
+        .seal(&mut staging, "ssn", b"123-45-6789", Layer::AtRest, "")
+        .unwrap();
+
+    let options = TraversalOptions {
+        min_dest_layer: Some(Layer::AccessGated),
+        ..TraversalOptions::default()
+    };
+    let result = vault.traverse_with_options(
+        &partition,
+        &staging,
+        &partition,
+        &mut sensitive,
+        "ssn",
+        Layer::AtRest,
+        "",
+        "",
+        options.clone(),
+    );
+    assert!(matches!(
+        result,
+        Err(HexvaultError::LayerPolicyViolation {
+            layer: Layer::AtRest,
+            min: Layer::AccessGated,
+        })
+    ));
+    assert!(!sensitive.contains_key("ssn"));
+    assert_eq!(vault.audit_log_len(), 0);
+
+    vault
+        .traverse_with_options(
+            &partition,
+            &staging,
+            &partition,
+            &mut sensitive,
+            "ssn",
+            Layer::AccessGated,
+            "",
+            "ops",
+            options,
+        )
+        .unwrap();
+    assert_eq!(
+        partition.open(&sensitive, "ssn", "ops").unwrap(),
+        b"123-45-6789"
+    );
+}
+
+#[test]
+fn test_traverse_records_sealing_parameters() {
+    use hexvault::audit::AuditLog;
+    use hexvault::edge::TraversalOptions;
+    use hexvault::CipherSuite;
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+    partition
+        .seal(&mut cell_a, "data", b"payload", Layer::AtRest, "")
+        .unwrap();
+
+    let options = TraversalOptions {
+        dest_suite: Some(CipherSuite::ChaCha20Poly1305),
+        ..TraversalOptions::default()
+    };
+    let record = vault
+        .traverse_with_options(
+            &partition,
+            &cell_a,
+            &partition,
+            &mut cell_b,
+            "data",
+            Layer::AtRest,
+            "",
+            "",
+            options,
+        )
+        .unwrap();
+    assert_eq!(record.cipher_suite, Some(CipherSuite::ChaCha20Poly1305));
+    assert_eq!(record.format_version, Some(1));
+
+    // The parameters are chained: rewriting them breaks verification.
+    let json = serde_json::to_string(vault.audit_log()).unwrap();
+    assert!(serde_json::from_str::<AuditLog>(&json)
+        .unwrap()
+        .verify_chain());
+    let forged = json.replace("ChaCha20Poly1305", "Aes128Gcm");
+    assert!(!serde_json::from_str::<AuditLog>(&forged)
+        .unwrap()
+        .verify_chain());
+}
+
+#[test]
+fn test_traverse_returns_appended_record() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+    partition
+        .seal(&mut cell_a, "data", b"payload", Layer::AtRest, "")
+        .unwrap();
+
+    let record = vault
+        .traverse(
+            &partition,
+            &cell_a,
+            &partition,
+            &mut cell_b,
+            "data",
+            Layer::AtRest,
+            "",
+            "",
+        )
+        .unwrap();
+
+    assert_eq!(record.source_cell_id, "cell-a");
+    assert_eq!(record.dest_cell_id, "cell-b");
+    let logged = vault.audit_log().iter().last().unwrap();
+    assert_eq!(record.entry_hash, logged.entry_hash);
+}
+
+#[test]
+fn test_traverse_between_vaults() {
+    let mut vault_a = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let mut vault_b = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let partition_a = vault_a.get_partition("shared").unwrap();
+    let partition_b = vault_b.get_partition("shared").unwrap();
+    let mut source = partition_a.create_cell("outbox".into()).unwrap();
+    let mut dest = partition_b.create_cell("inbox".into()).unwrap();
+    partition_a
+        .seal(&mut source, "k", b"handover", Layer::AtRest, "")
+        .unwrap();
+
+    let (source_record, dest_record) = Vault::traverse_between_vaults(
+        &mut vault_a,
+        &partition_a,
+        &source,
+        &mut vault_b,
+        &partition_b,
+        &mut dest,
+        "k",
+        Layer::AtRest,
+        "",
+        "",
+    )
+    .unwrap();
+
+    // Re-encrypted under vault B's key: only B's partition opens it.
+    assert_eq!(partition_b.open(&dest, "k", "").unwrap(), b"handover");
+    assert!(partition_a.open(&dest, "k", "").is_err());
+
+    assert_eq!(vault_a.audit_log().len(), 1);
+    assert_eq!(vault_b.audit_log().len(), 1);
+    assert_eq!(source_record.dest_cell_id, "inbox");
+    assert_eq!(dest_record.source_cell_id, "outbox");
+    assert_eq!(dest_record.timestamp, source_record.timestamp);
+    assert!(vault_a.audit_log().verify_chain());
+    assert!(vault_b.audit_log().verify_chain());
+}
+
+#[test]
+fn test_merge_cell_conflict_policies() {
+    use hexvault::edge::ConflictPolicy;
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+
+    let setup = |partition: &hexvault::partition::Partition| {
+        let mut from = partition.create_cell("tenant-b".into()).unwrap();
+        let mut into = partition.create_cell("tenant-a".into()).unwrap();
+        for (key, value) in [("shared", &b"from-b"[..]), ("only-b", b"b")] {
+            partition
+                .seal(&mut from, key, value, Layer::AtRest, "")
+                .unwrap();
+        }
+        for (key, value) in [("shared", &b"from-a"[..]), ("shared.1", b"taken")] {
+            partition
+                .seal(&mut into, key, value, Layer::AtRest, "")
+                .unwrap();
+        }
+        (from, into)
+    };
+
+    let (from, mut into) = setup(&partition);
+    let moved = vault
+        .merge_cell(
+            &partition,
+            &from,
+            &partition,
+            &mut into,
+            "",
+            "",
+            ConflictPolicy::Skip,
+        )
+        .unwrap();
+    assert_eq!(moved, 1);
+    assert_eq!(partition.open(&into, "shared", "").unwrap(), b"from-a");
+    assert_eq!(partition.open(&into, "only-b", "").unwrap(), b"b");
+
+    let (from, mut into) = setup(&partition);
+    vault
+        .merge_cell(
+            &partition,
+            &from,
+            &partition,
+            &mut into,
+            "",
+            "",
+            ConflictPolicy::Overwrite,
+        )
+        .unwrap();
+    assert_eq!(partition.open(&into, "shared", "").unwrap(), b"from-b");
+
+    let (from, mut into) = setup(&partition);
+    vault
+        .merge_cell(
+            &partition,
+            &from,
+            &partition,
+            &mut into,
+            "",
+            "",
+            ConflictPolicy::Rename,
+        )
+        .unwrap();
+    assert_eq!(partition.open(&into, "shared", "").unwrap(), b"from-a");
+    assert_eq!(partition.open(&into, "shared.2", "").unwrap(), b"from-b");
+
+    // One traversal record per moved payload: 1 + 2 + 2.
+    assert_eq!(vault.audit_log_len(), 5);
+    assert!(vault
+        .audit_log()
+        .iter()
+        .all(|r| r.source_cell_id == "tenant-b" && r.dest_cell_id == "tenant-a"));
+}
+
+#[test]
+fn test_gather_collects_from_many_cells() {
+    use hexvault::edge::{ConflictPolicy, GatherSource};
+
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+    let mut sources = Vec::new();
+    for tenant in ["tenant-a", "tenant-b"] {
+        let mut cell = partition.create_cell(tenant.into()).unwrap();
+        partition
+            .seal(&mut cell, "total", tenant.as_bytes(), Layer::AtRest, "")
+            .unwrap();
+        sources.push(cell);
+    }
+    let mut report = partition.create_cell("report".into()).unwrap();
+
+    let gather: Vec<_> = sources
+        .iter()
+        .map(|cell| GatherSource {
+            partition: &partition,
+            cell,
+            key: "total",
+            token: "",
+            dest_key: "total",
+        })
+        .collect();
+    let gathered = vault
+        .gather(
+            gather.clone(),
+            &partition,
+            &mut report,
+            Layer::AtRest,
+            "",
+            ConflictPolicy::Rename,
+        )
+        .unwrap();
+    assert_eq!(gathered, 2);
+    assert_eq!(partition.open(&report, "total", "").unwrap(), b"tenant-a");
+    assert_eq!(partition.open(&report, "total.1", "").unwrap(), b"tenant-b");
+
+    let gathered = vault
+        .gather(
+            gather,
+            &partition,
+            &mut report,
+            Layer::AtRest,
+            "",
+            ConflictPolicy::Skip,
+        )
+        .unwrap();
+    assert_eq!(gathered, 0);
+
+    let logged: Vec<_> = vault
+        .audit_log()
+        .iter()
+        .map(|r| &r.source_cell_id)
+        .collect();
+    assert_eq!(logged, ["tenant-a", "tenant-b"]);
+    assert!(vault.audit_log().iter().all(|r| r.dest_cell_id == "report"));
+}
+
+#[test]
+fn test_split_cell_moves_selected_payloads() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+
+    let mut from = partition.create_cell("tenant".into()).unwrap();
+    for (key, value) in [
+        ("billing/a", &b"1"[..]),
+        ("billing/b", b"2"),
+        ("profile", b"3"),
+    ] {
+        partition
+            .seal(&mut from, key, value, Layer::AtRest, "")
+            .unwrap();
+    }
+
+    let billing = vault
+        .split_cell(
+            &partition,
+            &mut from,
+            "tenant-billing".into(),
+            |key, _| key.starts_with("billing/"),
+            "",
+            "",
+        )
+        .unwrap();
+
+    assert_eq!(billing.id(), "tenant-billing");
+    assert_eq!(partition.open(&billing, "billing/a", "").unwrap(), b"1");
+    assert_eq!(partition.open(&billing, "billing/b", "").unwrap(), b"2");
+    assert!(!billing.contains_key("profile"));
+
+    let mut left: Vec<&str> = from.keys().collect();
+    left.sort_unstable();
+    assert_eq!(left, ["profile"]);
+
+    assert_eq!(vault.audit_log_len(), 2);
+    assert!(vault
+        .audit_log()
+        .iter()
+        .all(|r| r.source_cell_id == "tenant" && r.dest_cell_id == "tenant-billing"));
+}
+
+#[test]
+fn test_traverse_between_partitions_with_same_cell_id() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let p1 = vault.get_partition("p1").unwrap();
+    let p2 = vault.get_partition("p2").unwrap();
+    let mut a1 = p1.create_cell("a".into()).unwrap();
+    let mut a2 = p2.create_cell("a".into()).unwrap();
+    p1.seal(&mut a1, "k", b"cross-partition", Layer::AtRest, "")
+        .unwrap();
+
+    vault
+        .traverse(&p1, &a1, &p2, &mut a2, "k", Layer::AtRest, "", "")
+        .unwrap();
+    assert_eq!(p2.open(&a2, "k", "").unwrap(), b"cross-partition");
+
+    // The same ID in the same partition is still the same cell.
+    let mut alias = p1.create_cell("a".into()).unwrap();
+    assert!(matches!(
+        vault.traverse(&p1, &a1, &p1, &mut alias, "k", Layer::AtRest, "", ""),
+        Err(HexvaultError::InvalidTraversal(_))
+    ));
+}
+
+#[test]
+fn test_clone_cell_leaves_source_intact() {
+    let master = generate_master_key().unwrap();
+    let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("test").unwrap();
+
+    let mut prod = hexvault::cell::Cell::with_label("prod".into(), "customers").unwrap();
+    for (key, value) in [("email", &b"a@example.com"[..]), ("session", b"token")] {
+        partition
+            .seal(&mut prod, key, value, Layer::AtRest, "")
+            .unwrap();
+    }
+    let before = prod.metadata("email").unwrap();
+
+    let staging = vault
+        .clone_cell(&partition, &prod, "staging".into(), "", "")
+        .unwrap();
+
+    assert_eq!(staging.id(), "staging");
+    assert_eq!(staging.label(), Some("customers"));
+    assert_eq!(
+        partition.open(&staging, "email", "").unwrap(),
+        b"a@example.com"
+    );
+    assert_eq!(partition.open(&staging, "session", "").unwrap(), b"token");
+
+    // The source still opens, and its payloads were not touched.
+    assert_eq!(
+        partition.open(&prod, "email", "").unwrap(),
+        b"a@example.com"
+    );
+    assert_eq!(prod.metadata("email"), Some(before));
+    assert_eq!(prod.keys().count(), 2);
+
+    assert_eq!(vault.audit_log_len(), 2);
+    assert!(vault
+        .audit_log()
+        .iter()
+        .all(|r| r.source_cell_id == "prod" && r.dest_cell_id == "staging"));
+}
+
+#[test]
+fn test_seal_multi_is_all_or_nothing() {
+    struct PolicyResolver;
+    impl TokenResolver for PolicyResolver {
+        fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+            match token {
+                "" => Ok(LayerContext::empty()),
+                policy => LayerContext::new(Some(policy.try_into()?), None),
+            }
+        }
+    }
+
+    let master = generate_master_key().unwrap();
+    let vault = Vault::new(master, std::sync::Arc::new(PolicyResolver));
+    let p1 = vault.get_partition("p1").unwrap();
+    let p2 = vault.get_partition("p2").unwrap();
+    let mut a = p1.create_cell("a".into()).unwrap();
+    let mut b = p2.create_cell("b".into()).unwrap();
+
+    vault
+        .seal_multi(
+            [
+                (&p1, &mut a, Layer::AtRest, ""),
+                (&p2, &mut b, Layer::AccessGated, "ops"),
+            ],
+            "config",
+            b"v1",
+        )
+        .unwrap();
+    assert_eq!(p1.open(&a, "config", "").unwrap(), b"v1");
+    assert_eq!(p2.open(&b, "config", "ops").unwrap(), b"v1");
+
+    // The second target has no access policy, so neither cell changes.
+    let result = vault.seal_multi(
+        [
+            (&p1, &mut a, Layer::AtRest, ""),
+            (&p2, &mut b, Layer::AccessGated, ""),
+        ],
+        "config",
+        b"v2",
+    );
+    assert!(matches!(
+        result,
+        Err(HexvaultError::MissingOrInvalidContext)
+    ));
+    assert_eq!(p1.open(&a, "config", "").unwrap(), b"v1");
+    assert_eq!(p2.open(&b, "config", "ops").unwrap(), b"v1");
+}
@@ -0,0 +1,226 @@
+use std::fs;
+
+use hexvault::error::HexvaultError;
+use hexvault::stack::{Layer, LayerContext, TokenResolver};
+use hexvault::{generate_master_key, Vault};
+
+struct PolicyResolver;
+impl TokenResolver for PolicyResolver {
+    fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+        match token {
+            "" => Ok(LayerContext::empty()),
+            policy => LayerContext::new(Some(policy.try_into()?), None),
+        }
+    }
+}
+
+#[test]
+fn test_seal_file_roundtrip_and_cleanup() {
+    let dir = std::env::temp_dir().join(format!("hexvault_file_stream_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let plain = dir.join("plain.bin");
+    let sealed = dir.join("sealed.hxv");
+    let opened = dir.join("opened.bin");
+
+    // Several chunks plus a partial one.
+    let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&plain, &data).unwrap();
+
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(PolicyResolver),
+    );
+    let partition = vault.get_partition("files").unwrap();
+
+    vault
+        .seal_file(
+            &partition,
+            "docs",
+            &plain,
+            &sealed,
+            Layer::AccessGated,
+            "ops",
+        )
+        .unwrap();
+    vault
+        .open_file(&partition, "docs", &sealed, &opened, "ops")
+        .unwrap();
+    assert_eq!(fs::read(&opened).unwrap(), data);
+
+    // A truncated stream fails and leaves no partial plaintext behind.
+    let bytes = fs::read(&sealed).unwrap();
+    fs::write(&sealed, &bytes[..bytes.len() - 100]).unwrap();
+    fs::remove_file(&opened).unwrap();
+    assert!(vault
+        .open_file(&partition, "docs", &sealed, &opened, "ops")
+        .is_err());
+    assert!(!opened.exists());
+
+    // So does opening for the wrong cell.
+    fs::write(&sealed, &bytes).unwrap();
+    assert!(vault
+        .open_file(&partition, "other", &sealed, &opened, "ops")
+        .is_err());
+    assert!(!opened.exists());
+
+    // A seal that cannot satisfy its layer removes its output too.
+    assert!(vault
+        .seal_file(&partition, "docs", &plain, &opened, Layer::AccessGated, "")
+        .is_err());
+    assert!(!opened.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_framed_roundtrip_and_truncation() {
+    use hexvault::{decrypt_framed, encrypt_framed, CipherSuite, KdfHash, FRAME_LEN};
+
+    let key = [9u8; 32];
+    let seal = |data: &[u8]| {
+        encrypt_framed(
+            CipherSuite::ChaCha20Poly1305,
+            KdfHash::Sha256,
+            &key,
+            data,
+            b"aad",
+        )
+        .unwrap()
+    };
+
+    for len in [0, 1, FRAME_LEN, FRAME_LEN + 1, 3 * FRAME_LEN] {
+        let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+        let sealed = seal(&data);
+        assert_eq!(decrypt_framed(&key, &sealed, b"aad").unwrap(), data);
+        assert!(decrypt_framed(&key, &sealed, b"other").is_err());
+    }
+
+    // Three full frames: header (2) + base nonce (12), then frame + tag each.
+    let data = vec![7u8; 3 * FRAME_LEN];
+    let sealed = seal(&data);
+    let frame = FRAME_LEN + 16;
+    assert_eq!(sealed.len(), 14 + 3 * frame);
+
+    // Cut at a frame boundary: the new last frame was not sealed as final.
+    assert!(matches!(
+        decrypt_framed(&key, &sealed[..14 + 2 * frame], b"aad"),
+        Err(HexvaultError::DecryptionFailure)
+    ));
+
+    // Swapping two frames breaks their nonces.
+    let mut swapped = sealed.clone();
+    swapped[14..14 + frame].copy_from_slice(&sealed[14 + frame..14 + 2 * frame]);
+    swapped[14 + frame..14 + 2 * frame].copy_from_slice(&sealed[14..14 + frame]);
+    assert!(decrypt_framed(&key, &swapped, b"aad").is_err());
+
+    // A framed ciphertext is not a single-shot one, and the header is bound.
+    let mut downgraded = sealed.clone();
+    downgraded[1] = 0x01;
+    assert!(decrypt_framed(&key, &downgraded, b"aad").is_err());
+    assert!(matches!(
+        decrypt_framed(&key, &sealed[..10], b"aad"),
+        Err(HexvaultError::MalformedCiphertext)
+    ));
+}
+
+#[test]
+fn test_seal_reader_open_writer_rejects_truncation() {
+    use hexvault::cell::Cell;
+    use hexvault::FRAME_LEN;
+
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(PolicyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("piped".into()).unwrap();
+
+    // Several frames plus a partial one.
+    let data: Vec<u8> = (0..150_000u32).map(|i| (i % 253) as u8).collect();
+    vault
+        .seal_reader(
+            &partition,
+            &mut cell,
+            "k",
+            data.as_slice(),
+            Layer::AccessGated,
+            "policy",
+        )
+        .unwrap();
+    assert_eq!(cell.metadata("k").unwrap().layer, Layer::AccessGated);
+
+    let mut out = Vec::new();
+    vault
+        .open_writer(&partition, &cell, "k", &mut out, "policy")
+        .unwrap();
+    assert_eq!(out, data);
+
+    let mut out = Vec::new();
+    assert!(vault
+        .open_writer(&partition, &cell, "k", &mut out, "other")
+        .is_err());
+    assert!(out.is_empty());
+
+    // Drop the last frame: the two full frames before it (each with a tag
+    // per layer, after a 10-byte header and a 14-byte prefix per layer)
+    // still authenticate, but the new last one was not sealed as the last,
+    // and nothing is written.
+    let mut bundle = cell.to_bundle();
+    let sealed = &mut bundle.payloads[0].data;
+    sealed.truncate(10 + 2 * 14 + 2 * (FRAME_LEN + 2 * 16));
+    let truncated = Cell::from_bundle(bundle).unwrap();
+    let mut out = Vec::new();
+    assert!(matches!(
+        vault.open_writer(&partition, &truncated, "k", &mut out, "policy"),
+        Err(HexvaultError::DecryptionFailure)
+    ));
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_streamed_payloads_rekey_and_scan() {
+    let mut vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(PolicyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("piped".into()).unwrap();
+    let data: Vec<u8> = (0..70_000u32).map(|i| (i % 251) as u8).collect();
+    vault
+        .seal_reader(
+            &partition,
+            &mut cell,
+            "stream",
+            data.as_slice(),
+            Layer::AccessGated,
+            "policy",
+        )
+        .unwrap();
+    partition
+        .seal(&mut cell, "plain", b"layered", Layer::AccessGated, "policy")
+        .unwrap();
+
+    assert!(vault
+        .scan_integrity([(&partition, &cell, "policy")])
+        .is_empty());
+    assert_eq!(partition.open(&cell, "stream", "policy").unwrap(), data);
+
+    // Rotate the master key: the stream migrates and stays a stream.
+    let ring = vault.key_ring_mut();
+    ring.insert(1, generate_master_key().unwrap());
+    ring.set_primary(1).unwrap();
+    let partition = vault.get_partition("p1").unwrap();
+    assert_eq!(vault.rekey(&partition, &mut cell, "policy").unwrap(), 2);
+    vault.key_ring_mut().remove(0).unwrap();
+    let partition = vault.get_partition("p1").unwrap();
+
+    assert_eq!(cell.metadata("stream").unwrap().key_id, 1);
+    assert!(vault
+        .scan_integrity([(&partition, &cell, "policy")])
+        .is_empty());
+    let mut out = Vec::new();
+    vault
+        .open_writer(&partition, &cell, "stream", &mut out, "policy")
+        .unwrap();
+    assert_eq!(out, data);
+}
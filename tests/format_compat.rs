@@ -0,0 +1,121 @@
+//! Byte-exact payloads checked in under `tests/fixtures/`, each named for
+//! the crate version that wrote it. Each must still open with the current
+//! code; a failure here means the on-disk format changed. Such a change
+//! must be versioned and keep opening these fixtures, not replace them.
+
+use hexvault::cell::Cell;
+use hexvault::keys::{self, MasterKey, PartitionKey};
+use hexvault::stack::{self, Layer, LayerContext};
+use hexvault::stream;
+
+const CELL_ID: &str = "compat-cell";
+const PLAINTEXT: &[u8] = b"hexvault format compatibility fixture";
+
+const AT_REST: &[u8] = include_bytes!("fixtures/at_rest-2.0.0.bin");
+const SESSION_BOUND: &[u8] = include_bytes!("fixtures/session_bound-2.0.0.bin");
+const CELL_SNAPSHOT: &[u8] = include_bytes!("fixtures/cell_snapshot-2.0.0.bin");
+const STREAM: &[u8] = include_bytes!("fixtures/stream-2.0.0.bin");
+
+/// The fixtures' master key: bytes 0x00..=0x1f.
+fn partition_key() -> PartitionKey {
+    let mut bytes = [0u8; 32];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    keys::derive_partition_key(&MasterKey::from_bytes(bytes), "compat").unwrap()
+}
+
+fn context() -> LayerContext {
+    LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_stack_fixtures_open() {
+    let partition = partition_key();
+    let ctx = context();
+    for (layer, sealed) in [
+        (Layer::AtRest, AT_REST),
+        (Layer::SessionBound, SESSION_BOUND),
+    ] {
+        let opened = stack::peel(&partition, CELL_ID, layer, &ctx, sealed).unwrap();
+        assert_eq!(opened, PLAINTEXT, "{layer:?} fixture no longer opens");
+    }
+}
+
+#[test]
+fn test_cell_snapshot_fixture_opens() {
+    let partition = partition_key();
+    let cell = Cell::read_from(CELL_SNAPSHOT).unwrap();
+    assert_eq!(cell.id(), CELL_ID);
+    for key in ["at-rest", "access-gated", "session-bound"] {
+        assert_eq!(
+            cell.retrieve(&partition, key, &context()).unwrap(),
+            PLAINTEXT,
+            "{key} payload no longer opens"
+        );
+    }
+}
+
+#[test]
+fn test_stream_fixture_opens() {
+    let mut opened = Vec::new();
+    stream::open_stream(&partition_key(), CELL_ID, &context(), STREAM, &mut opened).unwrap();
+    assert_eq!(opened, PLAINTEXT);
+}
+
+/// Writes a new set of fixtures named for the current crate version. Only
+/// run this for a deliberate, versioned format change, then include the new
+/// files above alongside the old ones. Refuses to overwrite existing files.
+#[test]
+#[ignore = "writes new files to tests/fixtures"]
+fn regenerate_fixtures() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let write = |stem: &str, bytes: &[u8]| {
+        use std::io::Write;
+        let path = dir.join(format!("{stem}-{}.bin", env!("CARGO_PKG_VERSION")));
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(bytes))
+            .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    };
+    let partition = partition_key();
+    let ctx = context();
+
+    for (layer, name) in [
+        (Layer::AtRest, "at_rest"),
+        (Layer::SessionBound, "session_bound"),
+    ] {
+        let sealed = stack::seal(&partition, CELL_ID, layer, &ctx, PLAINTEXT).unwrap();
+        write(name, &sealed);
+    }
+
+    let mut cell = Cell::new(CELL_ID.into());
+    for (key, layer) in [
+        ("at-rest", Layer::AtRest),
+        ("access-gated", Layer::AccessGated),
+        ("session-bound", Layer::SessionBound),
+    ] {
+        cell.store(&partition, key, PLAINTEXT, layer, &ctx).unwrap();
+    }
+    let mut snapshot = Vec::new();
+    cell.write_to(&mut snapshot).unwrap();
+    write("cell_snapshot", &snapshot);
+
+    let mut sealed = Vec::new();
+    stream::seal_stream(
+        &partition,
+        CELL_ID,
+        Layer::SessionBound,
+        &ctx,
+        PLAINTEXT,
+        &mut sealed,
+    )
+    .unwrap();
+    write("stream", &sealed);
+}
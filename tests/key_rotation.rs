@@ -0,0 +1,335 @@
+use hexvault::audit::AuditEvent;
+use hexvault::error::HexvaultError;
+use hexvault::keys::{KeyRing, MasterKey};
+use hexvault::stack::{Layer, LayerContext, TokenResolver};
+use hexvault::{generate_master_key, Vault};
+
+struct DummyResolver;
+impl TokenResolver for DummyResolver {
+    fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+        Ok(LayerContext::empty())
+    }
+}
+
+#[test]
+fn test_rotation_without_downtime() {
+    let mut vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "old", b"sealed under key 0", Layer::AtRest, "")
+        .unwrap();
+
+    // Add a new key and make it primary; the old payload stays readable.
+    let ring = vault.key_ring_mut();
+    ring.insert(1, generate_master_key().unwrap());
+    ring.set_primary(1).unwrap();
+    let partition = vault.get_partition("p1").unwrap();
+    partition
+        .seal(&mut cell, "new", b"sealed under key 1", Layer::AtRest, "")
+        .unwrap();
+    let ids: Vec<_> = cell
+        .infos()
+        .map(|(k, i)| (k.to_string(), i.key_id))
+        .collect();
+    assert!(ids.contains(&("old".into(), 0)) && ids.contains(&("new".into(), 1)));
+    assert_eq!(
+        partition.open(&cell, "old", "").unwrap(),
+        b"sealed under key 0"
+    );
+
+    // Migrate, then retire the old key.
+    assert_eq!(vault.rekey(&partition, &mut cell, "").unwrap(), 1);
+    assert_eq!(vault.rekey(&partition, &mut cell, "").unwrap(), 0);
+    assert!(vault
+        .audit_log()
+        .iter()
+        .all(|r| r.event == AuditEvent::Reseal));
+    vault.key_ring_mut().remove(0).unwrap();
+
+    let partition = vault.get_partition("p1").unwrap();
+    assert_eq!(
+        partition.open(&cell, "old", "").unwrap(),
+        b"sealed under key 0"
+    );
+    assert_eq!(
+        partition.open(&cell, "new", "").unwrap(),
+        b"sealed under key 1"
+    );
+}
+
+#[test]
+fn test_payload_under_retired_key_reports_its_id() {
+    let mut ring = KeyRing::new(0, MasterKey::from_bytes([1u8; 32]));
+    ring.insert(3, MasterKey::from_bytes([2u8; 32]));
+    let mut vault = Vault::with_key_ring(ring, std::sync::Arc::new(DummyResolver));
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "k", b"orphaned", Layer::AtRest, "")
+        .unwrap();
+
+    let ring = vault.key_ring_mut();
+    ring.set_primary(3).unwrap();
+    ring.remove(0).unwrap();
+
+    let partition = vault.get_partition("p1").unwrap();
+    assert!(matches!(
+        partition.open(&cell, "k", ""),
+        Err(HexvaultError::UnknownKeyId(0))
+    ));
+}
+
+#[test]
+fn test_migration_resumes_from_cursor() {
+    use hexvault::migration::MigrationCursor;
+    use hexvault::CipherSuite;
+
+    /// "policy" can peel `AccessGated`; anything else only `AtRest`.
+    struct PolicyResolver;
+    impl TokenResolver for PolicyResolver {
+        fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+            match token {
+                "policy" => LayerContext::new(Some("policy".try_into()?), None),
+                _ => Ok(LayerContext::empty()),
+            }
+        }
+    }
+
+    let mut vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(PolicyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell_a = partition.create_cell("a".into()).unwrap();
+    let mut cell_b = partition.create_cell("b".into()).unwrap();
+    for key in ["k1", "k2", "k3"] {
+        partition
+            .seal(&mut cell_a, key, b"a", Layer::AtRest, "")
+            .unwrap();
+    }
+    partition
+        .seal(&mut cell_b, "k1", b"gated", Layer::AccessGated, "policy")
+        .unwrap();
+    partition
+        .seal(&mut cell_b, "k2", b"b", Layer::AtRest, "")
+        .unwrap();
+
+    let ring = vault.key_ring_mut();
+    ring.insert(1, generate_master_key().unwrap());
+    ring.set_primary(1).unwrap();
+    let partition = vault.get_partition("p1").unwrap();
+
+    // The empty token cannot peel b/k1, so the run stops after cell a.
+    let cursor = {
+        let mut migration =
+            vault.migration([(&partition, &mut cell_a), (&partition, &mut cell_b)], "");
+        migration.set_batch_size(2);
+        let mut reports = Vec::new();
+        assert!(migration.run(|p| reports.push(p)).is_err());
+        assert_eq!(reports.iter().map(|p| p.migrated).collect::<Vec<_>>(), [2]);
+        migration.cursor().cloned().unwrap()
+    };
+    assert_eq!(
+        cursor,
+        MigrationCursor {
+            cell_id: "a".into(),
+            key: "k3".into(),
+        }
+    );
+    assert!(cell_a.infos().all(|(_, info)| info.key_id == 1));
+    assert_eq!(vault.audit_log().len(), 3);
+
+    // Resume with a token that can peel everything, moving suites too.
+    let mut migration = vault.migration(
+        [(&partition, &mut cell_a), (&partition, &mut cell_b)],
+        "policy",
+    );
+    migration.resume_from(cursor);
+    migration.set_suite(CipherSuite::ChaCha20Poly1305);
+    let mut last = None;
+    assert_eq!(migration.run(|p| last = Some(p)).unwrap(), 2);
+    let last = last.unwrap();
+    assert_eq!((last.migrated, last.remaining), (2, 0));
+    assert_eq!(last.cursor.key, "k2");
+
+    // Cell a was behind the cursor, so it kept its suite.
+    assert!(cell_a
+        .infos()
+        .all(|(_, i)| i.suite == Some(CipherSuite::Aes256Gcm)));
+    assert!(cell_b
+        .infos()
+        .all(|(_, i)| i.key_id == 1 && i.suite == Some(CipherSuite::ChaCha20Poly1305)));
+    assert_eq!(partition.open(&cell_b, "k1", "policy").unwrap(), b"gated");
+    assert_eq!(vault.audit_log().len(), 5);
+}
+
+#[test]
+fn test_migration_to_suite_converges_with_streams() {
+    use hexvault::CipherSuite;
+
+    let mut vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "blob", b"layered", Layer::AtRest, "")
+        .unwrap();
+    vault
+        .seal_reader(
+            &partition,
+            &mut cell,
+            "stream",
+            &b"streamed"[..],
+            Layer::AtRest,
+            "",
+        )
+        .unwrap();
+
+    let run = |vault: &mut Vault, cell: &mut hexvault::cell::Cell| {
+        let mut migration = vault.migration([(&partition, cell)], "");
+        migration.set_suite(CipherSuite::ChaCha20Poly1305);
+        migration.run(|_| {}).unwrap()
+    };
+    // Only the layered payload changes suite; the stream is left alone,
+    // and a second run finds nothing to do.
+    assert_eq!(run(&mut vault, &mut cell), 1);
+    assert_eq!(run(&mut vault, &mut cell), 0);
+    assert_eq!(vault.audit_log().len(), 1);
+    assert_eq!(
+        cell.metadata("blob").unwrap().suite,
+        Some(CipherSuite::ChaCha20Poly1305)
+    );
+    assert_eq!(cell.metadata("stream").unwrap().suite, None);
+
+    // Rotating the master key still moves the stream.
+    let ring = vault.key_ring_mut();
+    ring.insert(1, generate_master_key().unwrap());
+    ring.set_primary(1).unwrap();
+    let partition = vault.get_partition("p1").unwrap();
+    let mut migration = vault.migration([(&partition, &mut cell)], "");
+    migration.set_suite(CipherSuite::ChaCha20Poly1305);
+    assert_eq!(migration.run(|_| {}).unwrap(), 2);
+    assert!(cell.infos().all(|(_, info)| info.key_id == 1));
+    let mut opened = Vec::new();
+    vault
+        .open_writer(&partition, &cell, "stream", &mut opened, "")
+        .unwrap();
+    assert_eq!(opened, b"streamed");
+}
+
+#[test]
+fn test_fingerprint_cell_confirms_restored_key() {
+    use hexvault::cell::Cell;
+    use hexvault::stack::SealOptions;
+    use hexvault::{CipherSuite, KdfHash};
+
+    let fingerprint = |master: [u8; 32], cell: &str, layer: Layer| {
+        let vault = Vault::new(
+            MasterKey::from_bytes(master),
+            std::sync::Arc::new(DummyResolver),
+        );
+        let partition = vault.get_partition("p1").unwrap();
+        let cell = partition.create_cell(cell.into()).unwrap();
+        vault
+            .fingerprint_cell(&partition, &cell, layer, "")
+            .unwrap()
+    };
+
+    let before = fingerprint([7u8; 32], "cell", Layer::AtRest);
+    assert_eq!(before.len(), 64);
+    assert_eq!(fingerprint([7u8; 32], "cell", Layer::AtRest), before);
+    assert_ne!(fingerprint([8u8; 32], "cell", Layer::AtRest), before);
+    assert_ne!(fingerprint([7u8; 32], "other", Layer::AtRest), before);
+
+    // The empty context cannot derive the access-gated key.
+    let vault = Vault::new(
+        MasterKey::from_bytes([7u8; 32]),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    assert!(matches!(
+        vault.fingerprint_cell(&partition, &cell, Layer::AccessGated, ""),
+        Err(HexvaultError::MissingOrInvalidContext)
+    ));
+
+    // The suite, KDF hash, and schema are read from the cell itself.
+    let options = SealOptions {
+        suite: CipherSuite::ChaCha20Poly1305,
+        kdf: KdfHash::Sha512,
+        ..SealOptions::default()
+    };
+    partition
+        .seal_with_options(&mut cell, "k", b"v", Layer::AtRest, "", &options)
+        .unwrap();
+    assert_ne!(
+        vault
+            .fingerprint_cell(&partition, &cell, Layer::AtRest, "")
+            .unwrap(),
+        before
+    );
+    let bound = Cell::with_bound_schema("cell".into(), 2).unwrap();
+    assert_ne!(
+        vault
+            .fingerprint_cell(&partition, &bound, Layer::AtRest, "")
+            .unwrap(),
+        before
+    );
+}
+
+#[test]
+fn test_self_test_covers_every_key() {
+    let mut vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(DummyResolver),
+    );
+    vault.self_test().unwrap();
+
+    vault
+        .key_ring_mut()
+        .insert(1, MasterKey::from_bytes([7u8; 32]));
+    vault.self_test().unwrap();
+    assert!(vault.audit_log().is_empty());
+}
+
+#[test]
+fn test_master_key_from_file() {
+    let path = std::env::temp_dir().join(format!("hexvault_master_key_{}", std::process::id()));
+    std::fs::write(&path, [9u8; 32]).unwrap();
+
+    let sealing = Vault::new(
+        MasterKey::from_bytes([9u8; 32]),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let partition = sealing.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "k", b"mounted", Layer::AtRest, "")
+        .unwrap();
+
+    let vault = Vault::new(
+        MasterKey::from_file(&path).unwrap(),
+        std::sync::Arc::new(DummyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    assert_eq!(partition.open(&cell, "k", "").unwrap(), b"mounted");
+
+    for len in [31, 33] {
+        std::fs::write(&path, vec![9u8; len]).unwrap();
+        assert!(matches!(
+            MasterKey::from_file(&path),
+            Err(HexvaultError::InvalidKey)
+        ));
+    }
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        MasterKey::from_file(&path),
+        Err(HexvaultError::Io(_))
+    ));
+}
@@ -0,0 +1,94 @@
+//! Property-based roundtrip tests.
+//!
+//! Complements the fixed-input tests with generated plaintexts (including
+//! empty and large), cell IDs, and context strings. Cell IDs and contexts
+//! deliberately include `:` so that any delimiter ambiguity in the HKDF
+//! info string or the AAD would surface as a cross-decryption.
+
+use hexvault::keys::{self, MasterKey};
+use hexvault::stack::{self, Layer, LayerContext};
+use proptest::prelude::*;
+
+fn layer() -> impl Strategy<Value = Layer> {
+    prop::sample::select(Layer::all().to_vec())
+}
+
+fn id() -> impl Strategy<Value = String> {
+    "[a-z0-9:_-]{1,24}"
+}
+
+fn context() -> impl Strategy<Value = LayerContext> {
+    (id(), id()).prop_map(|(policy, session)| {
+        LayerContext::new(
+            Some(policy.try_into().expect("non-empty policy")),
+            Some(session.try_into().expect("non-empty session")),
+        )
+        .expect("non-empty IDs")
+    })
+}
+
+fn plaintext() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        Just(Vec::new()),
+        prop::collection::vec(any::<u8>(), 0..256),
+        prop::collection::vec(any::<u8>(), 16 * 1024..64 * 1024),
+    ]
+}
+
+fn partition() -> keys::PartitionKey {
+    let master = MasterKey::from_bytes([3u8; 32]);
+    keys::derive_partition_key(&master, "prop").unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn seal_peel_roundtrips(
+        cell in id(),
+        layer in layer(),
+        ctx in context(),
+        plaintext in plaintext(),
+    ) {
+        let partition = partition();
+        let sealed = stack::seal(&partition, &cell, layer, &ctx, &plaintext).unwrap();
+        let peeled = stack::peel(&partition, &cell, layer, &ctx, &sealed).unwrap();
+        prop_assert_eq!(peeled, plaintext);
+    }
+
+    #[test]
+    fn wrong_context_fails(
+        cell in id(),
+        layer in layer(),
+        ids in (id(), id()),
+        other_ids in (id(), id()),
+        plaintext in prop::collection::vec(any::<u8>(), 0..256),
+    ) {
+        let ctx = LayerContext::new(Some(ids.0.as_str().try_into().unwrap()), Some(ids.1.as_str().try_into().unwrap())).unwrap();
+        let other = LayerContext::new(Some(other_ids.0.as_str().try_into().unwrap()), Some(other_ids.1.as_str().try_into().unwrap())).unwrap();
+        let partition = partition();
+        let sealed = stack::seal(&partition, &cell, layer, &ctx, &plaintext).unwrap();
+        let result = stack::peel(&partition, &cell, layer, &other, &sealed);
+
+        // Only the context IDs the sealed layers actually use must match.
+        let matches = match layer {
+            Layer::AtRest => true,
+            Layer::AccessGated => ids.0 == other_ids.0,
+            Layer::SessionBound => ids == other_ids,
+        };
+        prop_assert_eq!(result.is_ok(), matches);
+    }
+
+    #[test]
+    fn other_cell_never_decrypts(
+        cell in id(),
+        other in id(),
+        layer in layer(),
+        ctx in context(),
+    ) {
+        prop_assume!(cell != other);
+        let partition = partition();
+        let sealed = stack::seal(&partition, &cell, layer, &ctx, b"isolated").unwrap();
+        prop_assert!(stack::peel(&partition, &other, layer, &ctx, &sealed).is_err());
+    }
+}
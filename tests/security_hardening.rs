@@ -4,7 +4,7 @@
 //! Each test targets a specific finding from the audit.
 
 use hexvault::audit::AuditLog;
-use hexvault::stack::{self, Layer, LayerContext};
+use hexvault::stack::{self, Layer, LayerContext, PolicyId, SessionId};
 use hexvault::{generate_master_key, keys};
 
 // ---------------------------------------------------------------------------
@@ -35,19 +35,19 @@ fn test_cross_cell_replay_blocked_by_aad() {
 
 #[test]
 fn test_empty_session_id_rejected() {
-    let result = LayerContext::new(Some("policy".into()), Some("".into()));
+    let result = SessionId::try_from("");
     assert!(
         result.is_err(),
-        "Empty session_id should be rejected by LayerContext::new()"
+        "Empty session_id should be rejected by SessionId::try_from()"
     );
 }
 
 #[test]
 fn test_empty_access_policy_id_rejected() {
-    let result = LayerContext::new(Some("".into()), None);
+    let result = PolicyId::try_from("");
     assert!(
         result.is_err(),
-        "Empty access_policy_id should be rejected by LayerContext::new()"
+        "Empty access_policy_id should be rejected by PolicyId::try_from()"
     );
 }
 
@@ -89,6 +89,32 @@ fn test_empty_partition_id_rejected() {
     assert!(result.is_err(), "Empty partition_id should be rejected");
 }
 
+// ---------------------------------------------------------------------------
+// Reserved payload keys must be rejected
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_reserved_key_prefix_rejected() {
+    use hexvault::cell::{Cell, RESERVED_KEY_PREFIX};
+    use hexvault::error::HexvaultError;
+
+    let master = generate_master_key().unwrap();
+    let partition = keys::derive_partition_key(&master, "p").unwrap();
+    let ctx = LayerContext::empty();
+    let mut cell = Cell::new("cell".into());
+
+    let reserved = format!("{}audit", RESERVED_KEY_PREFIX);
+    assert!(matches!(
+        cell.store(&partition, &reserved, b"data", Layer::AtRest, &ctx),
+        Err(HexvaultError::ReservedKey)
+    ));
+    assert!(!cell.contains_key(&reserved));
+
+    // Only the exact prefix is reserved.
+    cell.store(&partition, "hexvault:audit", b"data", Layer::AtRest, &ctx)
+        .unwrap();
+}
+
 // ---------------------------------------------------------------------------
 // Layer skip: attempting to skip Layer 1 and directly unwrap Layer 0
 // ---------------------------------------------------------------------------
@@ -100,7 +126,11 @@ fn test_skip_layer1_direct_layer0_unwrap() {
     // layer is SessionBound, not AtRest.
     let master = generate_master_key().unwrap();
     let partition = keys::derive_partition_key(&master, "p").unwrap();
-    let ctx = LayerContext::new(Some("policy".into()), Some("session".into())).unwrap();
+    let ctx = LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap();
 
     let sealed = stack::seal(
         &partition,
@@ -126,24 +156,20 @@ fn test_skip_layer1_direct_layer0_unwrap() {
 #[test]
 fn test_audit_chain_tamper_detection() {
     use chrono::Utc;
-    use hexvault::audit::AuditRecord;
+    use hexvault::audit::{AuditEvent, AuditRecord};
 
     let mut log = AuditLog::new();
 
     log.append(AuditRecord {
-        source_cell_id: "a".into(),
         dest_cell_id: "b".into(),
-        layer: Layer::AtRest,
-        timestamp: Utc::now(),
-        entry_hash: String::new(),
-    });
+        ..AuditRecord::new(AuditEvent::Traverse, "a", Layer::AtRest, Utc::now())
+    })
+    .unwrap();
     log.append(AuditRecord {
-        source_cell_id: "b".into(),
         dest_cell_id: "c".into(),
-        layer: Layer::AccessGated,
-        timestamp: Utc::now(),
-        entry_hash: String::new(),
-    });
+        ..AuditRecord::new(AuditEvent::Traverse, "b", Layer::AccessGated, Utc::now())
+    })
+    .unwrap();
 
     // 1. Valid chain
     assert!(log.verify_chain(), "Unmodified chain should verify");
@@ -176,3 +202,242 @@ fn test_cross_partition_decryption_failure() {
     let result = stack::peel(&p2, "cell-a", Layer::AtRest, &ctx, &sealed);
     assert!(result.is_err(), "Cross-partition decryption should fail");
 }
+
+// ---------------------------------------------------------------------------
+// Malformed input: peel must return Err, never panic
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_peel_never_panics_on_malformed_input() {
+    use hexvault::error::HexvaultError;
+
+    let master = generate_master_key().unwrap();
+    let partition = keys::derive_partition_key(&master, "p").unwrap();
+    let ctx = LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap();
+    let sealed = stack::seal(&partition, "cell", Layer::SessionBound, &ctx, b"payload").unwrap();
+
+    // Every truncation of a valid ciphertext, at every claimed layer.
+    for len in 0..sealed.len() {
+        for &layer in Layer::all() {
+            assert!(stack::peel(&partition, "cell", layer, &ctx, &sealed[..len]).is_err());
+        }
+    }
+
+    // Short inputs are rejected structurally.
+    for input in [&[][..], &[1], &[1, 1], &[1, 1, 0, 0]] {
+        assert!(matches!(
+            stack::peel(&partition, "cell", Layer::AtRest, &ctx, input),
+            Err(HexvaultError::MalformedCiphertext)
+        ));
+    }
+
+    // Pseudo-random bytes of assorted lengths, with and without a valid header.
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for len in 0..256 {
+        let mut input: Vec<u8> = (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        assert!(stack::peel(&partition, "cell", Layer::SessionBound, &ctx, &input).is_err());
+        if input.len() >= 2 {
+            input[0] = 1;
+            input[1] = 1;
+            assert!(stack::peel(&partition, "cell", Layer::SessionBound, &ctx, &input).is_err());
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Empty plaintext is a valid, authenticated payload
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_empty_plaintext_roundtrip_at_every_layer() {
+    use hexvault::cell::Cell;
+    use hexvault::error::HexvaultError;
+
+    let master = generate_master_key().unwrap();
+    let partition = keys::derive_partition_key(&master, "p").unwrap();
+    let ctx = LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap();
+
+    for &layer in Layer::all() {
+        let sealed = stack::seal(&partition, "cell", layer, &ctx, b"").unwrap();
+        // Header, nonce, and tag per layer, around nothing.
+        assert_eq!(sealed.len(), (layer.index() + 1) * 30);
+        assert_eq!(
+            stack::peel(&partition, "cell", layer, &ctx, &sealed).unwrap(),
+            b""
+        );
+
+        // One byte short of the smallest ciphertext is structurally invalid,
+        // not an empty plaintext.
+        let short = &sealed[..sealed.len() - 1];
+        let result = stack::peel(&partition, "cell", layer, &ctx, short);
+        if layer == Layer::AtRest {
+            assert!(matches!(result, Err(HexvaultError::MalformedCiphertext)));
+        } else {
+            assert!(result.is_err());
+        }
+
+        let mut cell = Cell::new("cell".into());
+        cell.store(&partition, "empty", b"", layer, &ctx).unwrap();
+        assert_eq!(cell.retrieve(&partition, "empty", &ctx).unwrap(), b"");
+        cell.verify(&partition, "empty", &ctx).unwrap();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Minimum layer policy: seals below the vault's floor are refused
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_min_layer_rejects_low_seals() {
+    use std::sync::Arc;
+
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    struct FullResolver;
+    impl TokenResolver for FullResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            LayerContext::new(Some("policy".try_into()?), Some("session".try_into()?))
+        }
+    }
+
+    let mut vault = Vault::new(generate_master_key().unwrap(), Arc::new(FullResolver));
+    assert_eq!(vault.min_layer(), Layer::AtRest);
+    vault.set_min_layer(Layer::SessionBound);
+    let partition = vault.get_partition("p").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+
+    assert!(matches!(
+        partition.seal(&mut cell, "k", b"secret", Layer::AtRest, "t"),
+        Err(HexvaultError::LayerPolicyViolation {
+            layer: Layer::AtRest,
+            min: Layer::SessionBound,
+        })
+    ));
+    assert!(!cell.contains_key("k"));
+
+    partition
+        .seal(&mut cell, "k", b"secret", Layer::SessionBound, "t")
+        .unwrap();
+    assert!(matches!(
+        vault.downgrade(&partition, &mut cell, "k", Layer::AccessGated, "t"),
+        Err(HexvaultError::LayerPolicyViolation { .. })
+    ));
+    assert_eq!(partition.open(&cell, "k", "t").unwrap(), b"secret");
+}
+
+// ---------------------------------------------------------------------------
+// Integrity scan: every undecryptable payload is reported
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_scan_integrity_reports_every_failure() {
+    use std::sync::Arc;
+
+    use hexvault::cell::Cell;
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    struct PolicyResolver;
+    impl TokenResolver for PolicyResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            LayerContext::new(Some("policy".try_into()?), None)
+        }
+    }
+
+    let vault = Vault::new(generate_master_key().unwrap(), Arc::new(PolicyResolver));
+    let partition = vault.get_partition("p").unwrap();
+    let mut healthy = partition.create_cell("healthy".into()).unwrap();
+    let mut damaged = partition.create_cell("damaged".into()).unwrap();
+    for cell in [&mut healthy, &mut damaged] {
+        for key in ["a", "b", "c"] {
+            partition
+                .seal(cell, key, b"backed up", Layer::AccessGated, "t")
+                .unwrap();
+        }
+    }
+    assert!(vault
+        .scan_integrity([(&partition, &healthy, "t"), (&partition, &damaged, "t")])
+        .is_empty());
+
+    // Flip a ciphertext byte in two of the damaged cell's payloads.
+    let mut bundle = damaged.to_bundle();
+    for payload in &mut bundle.payloads {
+        if payload.key != "b" {
+            let last = payload.data.len() - 1;
+            payload.data[last] ^= 0x01;
+        }
+    }
+    let damaged = Cell::from_bundle(bundle).unwrap();
+
+    let report = vault.scan_integrity([(&partition, &healthy, "t"), (&partition, &damaged, "t")]);
+    let failed: Vec<(&str, &str)> = report
+        .iter()
+        .map(|(cell, key, _)| (cell.as_str(), key.as_str()))
+        .collect();
+    assert_eq!(failed, [("damaged", "a"), ("damaged", "c")]);
+    assert!(report
+        .iter()
+        .all(|(_, _, e)| matches!(e, HexvaultError::DecryptionFailure)));
+}
+
+// ---------------------------------------------------------------------------
+// Nonce reuse detection: a repeated (key, nonce) pair panics, per vault
+// ---------------------------------------------------------------------------
+
+#[cfg(all(feature = "insecure-deterministic", debug_assertions))]
+#[test]
+#[should_panic(expected = "nonce reused under the same key")]
+fn test_nonce_reuse_detection_is_per_vault() {
+    use std::sync::Arc;
+
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    struct EmptyResolver;
+    impl TokenResolver for EmptyResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            Ok(LayerContext::empty())
+        }
+    }
+
+    let master = keys::MasterKey::from_bytes([7u8; 32]);
+    let watched = Vault::new(master, Arc::new(EmptyResolver));
+    let other = Vault::new(generate_master_key().unwrap(), Arc::new(EmptyResolver));
+    watched.enable_nonce_reuse_detection(16);
+    // All-zero nonces on this thread: every seal under one key repeats.
+    watched.set_deterministic_nonces(true);
+
+    let partition = other.get_partition("p").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    for key in ["a", "b"] {
+        partition
+            .seal(&mut cell, key, b"unwatched", Layer::AtRest, "t")
+            .unwrap();
+    }
+
+    let partition = watched.get_partition("p").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "a", b"first", Layer::AtRest, "t")
+        .unwrap();
+    let _ = partition.seal(&mut cell, "b", b"second", Layer::AtRest, "t");
+}
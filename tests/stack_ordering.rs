@@ -11,7 +11,11 @@ fn test_layer_peeling_order() {
     let cell_id = "test-cell";
     let plaintext = b"layered secret";
 
-    let ctx = LayerContext::new(Some("policy".into()), Some("session".into())).unwrap();
+    let ctx = LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap();
 
     // 1. Seal to Layer 2 (SessionBound).
     // Stack: [AtRest] -> [AccessGated] -> [SessionBound]
@@ -34,7 +38,7 @@ fn test_invalid_context_rejection() {
     let cell_id = "test-auth";
     let plaintext = b"guarded secret";
 
-    let correct_ctx = LayerContext::new(Some("secret-policy".into()), None).unwrap();
+    let correct_ctx = LayerContext::new(Some("secret-policy".try_into().unwrap()), None).unwrap();
 
     // 1. Seal to Layer 1 (AccessGated).
     let sealed = stack::seal(
@@ -47,7 +51,7 @@ fn test_invalid_context_rejection() {
     .unwrap();
 
     // 2. Attempt to peel with WRONG policy ID.
-    let wrong_ctx = LayerContext::new(Some("public-policy".into()), None).unwrap();
+    let wrong_ctx = LayerContext::new(Some("public-policy".try_into().unwrap()), None).unwrap();
 
     let result = stack::peel(&partition, cell_id, Layer::AccessGated, &wrong_ctx, &sealed);
     assert!(
@@ -70,3 +74,379 @@ fn test_invalid_context_rejection() {
         "Peeling succeeded with missing access policy ID!"
     );
 }
+
+#[test]
+fn test_validate_batch_names_first_incomplete_item() {
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    /// Tokens are "policy", "full", or anything else for an empty context.
+    struct TieredResolver;
+    impl TokenResolver for TieredResolver {
+        fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+            match token {
+                "policy" => LayerContext::new(Some("policy".try_into()?), None),
+                "full" => {
+                    LayerContext::new(Some("policy".try_into()?), Some("session".try_into()?))
+                }
+                _ => Ok(LayerContext::empty()),
+            }
+        }
+    }
+
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(TieredResolver),
+    );
+
+    let mut batch = vec![
+        (Layer::AtRest, ""),
+        (Layer::AccessGated, "policy"),
+        (Layer::SessionBound, "full"),
+    ];
+    assert!(vault.validate_batch(&batch).is_ok());
+
+    batch.push((Layer::SessionBound, "policy"));
+    batch.push((Layer::AccessGated, ""));
+    match vault.validate_batch(&batch) {
+        Err(HexvaultError::IncompleteContext { index, missing }) => {
+            assert_eq!(index, 3);
+            assert_eq!(missing, [Layer::SessionBound]);
+        }
+        other => panic!("expected IncompleteContext, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_open_any_tries_tokens_in_order() {
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    /// Tokens name a session; "bad" fails to resolve.
+    struct SessionResolver;
+    impl TokenResolver for SessionResolver {
+        fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+            match token {
+                "bad" => Err(HexvaultError::MissingOrInvalidContext),
+                session => LayerContext::new(Some("policy".try_into()?), Some(session.try_into()?)),
+            }
+        }
+    }
+
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(SessionResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "k", b"rotating", Layer::SessionBound, "old")
+        .unwrap();
+
+    assert_eq!(
+        partition.open_any(&cell, "k", &["new", "old"]).unwrap(),
+        b"rotating"
+    );
+    assert!(matches!(
+        partition.open_any(&cell, "k", &["new", "other"]),
+        Err(HexvaultError::DecryptionFailure)
+    ));
+    // Only authentication failures move on to the next token.
+    assert!(matches!(
+        partition.open_any(&cell, "k", &["new", "bad", "old"]),
+        Err(HexvaultError::MissingOrInvalidContext)
+    ));
+    assert!(matches!(
+        partition.open_any(&cell, "k", &[]),
+        Err(HexvaultError::DecryptionFailure)
+    ));
+    assert!(matches!(
+        partition.open_any(&cell, "missing", &["old"]),
+        Err(HexvaultError::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn test_open_any_returns_expiry_unchanged() {
+    use std::sync::Arc;
+
+    use hexvault::clock::ManualClock;
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    struct SessionResolver;
+    impl TokenResolver for SessionResolver {
+        fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+            LayerContext::new(Some("policy".try_into()?), Some(token.try_into()?))
+        }
+    }
+
+    let start = chrono::Utc::now();
+    let clock = Arc::new(ManualClock::new(start));
+    let vault = Vault::with_clock(
+        generate_master_key().unwrap(),
+        Arc::new(SessionResolver),
+        clock.clone(),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal_with_ttl(
+            &mut cell,
+            "k",
+            b"short-lived",
+            Layer::SessionBound,
+            "old",
+            start + chrono::Duration::minutes(5),
+        )
+        .unwrap();
+
+    clock.advance(chrono::Duration::minutes(10));
+    assert!(matches!(
+        partition.open_any(&cell, "k", &["old", "new"]),
+        Err(HexvaultError::Expired)
+    ));
+}
+
+#[test]
+fn test_open_with_info_returns_payload_metadata() {
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    struct PolicyResolver;
+    impl TokenResolver for PolicyResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            LayerContext::new(Some("policy".try_into()?), None)
+        }
+    }
+
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(PolicyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "k", b"cached", Layer::AccessGated, "t")
+        .unwrap();
+
+    let (plaintext, info) = partition.open_with_info(&cell, "k", "t").unwrap();
+    assert_eq!(plaintext, b"cached");
+    assert_eq!(info.layer, Layer::AccessGated);
+    assert_eq!(Some(info), cell.metadata("k"));
+
+    assert!(matches!(
+        partition.open_with_info(&cell, "missing", "t"),
+        Err(HexvaultError::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn test_missing_key_is_key_not_found() {
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::TokenResolver;
+    use hexvault::Vault;
+
+    struct EmptyResolver;
+    impl TokenResolver for EmptyResolver {
+        fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+            Ok(LayerContext::empty())
+        }
+    }
+
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(EmptyResolver),
+    );
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    partition
+        .seal(&mut cell, "k", b"v", Layer::AtRest, "old")
+        .unwrap();
+
+    assert!(vault.exists(&cell, "k"));
+    assert!(!vault.exists(&cell, "missing"));
+    match partition.open(&cell, "missing", "old") {
+        Err(HexvaultError::KeyNotFound(key)) => assert_eq!(key, "missing"),
+        other => panic!("expected KeyNotFound, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_layer_guard_is_consulted_before_each_layer() {
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::{LayerGuard, TokenResolver};
+    use hexvault::Vault;
+    use std::sync::{Arc, Mutex};
+
+    /// Tokens name a session.
+    struct SessionResolver;
+    impl TokenResolver for SessionResolver {
+        fn resolve(&self, token: &str) -> Result<LayerContext, HexvaultError> {
+            LayerContext::new(Some("policy".try_into()?), Some(token.try_into()?))
+        }
+    }
+
+    /// Refuses the "revoked" session, and records every check.
+    struct RevocationGuard(Mutex<Vec<Layer>>);
+    impl LayerGuard for RevocationGuard {
+        fn check(&self, layer: Layer, context: &LayerContext) -> Result<(), HexvaultError> {
+            self.0.lock().unwrap().push(layer);
+            match context.session_id() {
+                Some(session) if layer == Layer::SessionBound && session.as_str() == "revoked" => {
+                    Err(HexvaultError::MissingOrInvalidContext)
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+
+    let guard = Arc::new(RevocationGuard(Mutex::new(Vec::new())));
+    let mut vault = Vault::new(generate_master_key().unwrap(), Arc::new(SessionResolver));
+    vault.set_layer_guard(guard.clone());
+    let partition = vault.get_partition("p1").unwrap();
+    let mut cell = partition.create_cell("cell".into()).unwrap();
+    let mut dest = partition.create_cell("dest".into()).unwrap();
+    partition
+        .seal(&mut cell, "live", b"ok", Layer::SessionBound, "live")
+        .unwrap();
+    partition
+        .seal(&mut cell, "revoked", b"no", Layer::SessionBound, "revoked")
+        .unwrap();
+    assert!(guard.0.lock().unwrap().is_empty());
+
+    assert_eq!(partition.open(&cell, "live", "live").unwrap(), b"ok");
+    assert_eq!(
+        *guard.0.lock().unwrap(),
+        [Layer::SessionBound, Layer::AccessGated, Layer::AtRest]
+    );
+
+    // Refused on every peel path, before anything is decrypted.
+    guard.0.lock().unwrap().clear();
+    assert!(partition.open(&cell, "revoked", "revoked").is_err());
+    assert!(partition.verify(&cell, "revoked", "revoked").is_err());
+    assert!(vault
+        .reseal(&partition, &mut cell, "revoked", "revoked")
+        .is_err());
+    assert!(vault
+        .traverse(
+            &partition,
+            &cell,
+            &partition,
+            &mut dest,
+            "revoked",
+            Layer::SessionBound,
+            "revoked",
+            "live",
+        )
+        .is_err());
+    assert!(guard
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|&layer| layer == Layer::SessionBound));
+    assert!(!dest.contains_key("revoked"));
+}
+
+#[test]
+fn test_seal_seeded_is_reproducible() {
+    let master = generate_master_key().unwrap();
+    let partition = keys::derive_partition_key(&master, "p").unwrap();
+    let ctx = LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap();
+    let seal = |plaintext: &[u8], seed: &[u8]| {
+        stack::seal_seeded(
+            &partition,
+            "cell",
+            Layer::SessionBound,
+            &ctx,
+            plaintext,
+            seed,
+        )
+        .unwrap()
+    };
+
+    // A retry with the same seed reproduces the write byte for byte.
+    let sealed = seal(b"order #1", b"request-42");
+    assert_eq!(seal(b"order #1", b"request-42"), sealed);
+    assert_eq!(
+        stack::peel(&partition, "cell", Layer::SessionBound, &ctx, &sealed).unwrap(),
+        b"order #1"
+    );
+
+    // Another seed, or another plaintext under the same seed, differs.
+    assert_ne!(seal(b"order #1", b"request-43"), sealed);
+    assert_ne!(seal(b"order #2", b"request-42")[2..14], sealed[2..14]);
+    assert_ne!(
+        stack::seal(&partition, "cell", Layer::SessionBound, &ctx, b"order #1").unwrap(),
+        sealed
+    );
+}
+
+#[test]
+fn test_truncated_tags_roundtrip_and_authenticate() {
+    use hexvault::error::HexvaultError;
+    use hexvault::stack::SealOptions;
+    use hexvault::{CipherSuite, TagLength};
+
+    let master = generate_master_key().unwrap();
+    let partition = keys::derive_partition_key(&master, "p").unwrap();
+    let ctx = LayerContext::new(
+        Some("policy".try_into().unwrap()),
+        Some("session".try_into().unwrap()),
+    )
+    .unwrap();
+    let seal = |options: &SealOptions| {
+        stack::seal_with_options(
+            &partition,
+            "cell",
+            Layer::SessionBound,
+            &ctx,
+            b"legacy peer",
+            options,
+        )
+    };
+    let peel = |sealed: &[u8]| stack::peel(&partition, "cell", Layer::SessionBound, &ctx, sealed);
+
+    let truncated = SealOptions {
+        tag_length: TagLength::Truncated96,
+        ..SealOptions::default()
+    };
+    let sealed = seal(&truncated).unwrap();
+    let full = seal(&SealOptions::default()).unwrap();
+    // Four bytes shorter per layer.
+    assert_eq!(full.len() - sealed.len(), 3 * 4);
+    assert_eq!(peel(&sealed).unwrap(), b"legacy peer");
+
+    // Every bit of the outer tag is checked.
+    for i in sealed.len() - 12..sealed.len() {
+        let mut forged = sealed.clone();
+        forged[i] ^= 0x01;
+        assert!(matches!(
+            peel(&forged),
+            Err(HexvaultError::DecryptionFailure)
+        ));
+    }
+    // The tag length is authenticated with the header.
+    let mut relabelled = sealed.clone();
+    relabelled[1] &= !0x40;
+    assert!(peel(&relabelled).is_err());
+
+    // Poly1305 tags cannot be truncated.
+    let chacha = SealOptions {
+        suite: CipherSuite::ChaCha20Poly1305,
+        ..truncated
+    };
+    assert!(matches!(
+        seal(&chacha),
+        Err(HexvaultError::InvalidCipherSuite(2))
+    ));
+}
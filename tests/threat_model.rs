@@ -23,8 +23,8 @@ fn test_insider_access_no_audit() {
     let mut vault = Vault::new(master, std::sync::Arc::new(DummyResolver));
 
     let partition = vault.get_partition("test").unwrap();
-    let mut cell_a = partition.create_cell("a".into());
-    let mut cell_b = partition.create_cell("b".into());
+    let mut cell_a = partition.create_cell("a".into()).unwrap();
+    let mut cell_b = partition.create_cell("b".into()).unwrap();
     let token = "";
 
     partition
@@ -0,0 +1,130 @@
+use hexvault::error::HexvaultError;
+use hexvault::stack::{Layer, LayerContext, TokenResolver};
+use hexvault::{generate_master_key, Vault, VaultStats};
+
+struct FullResolver;
+impl TokenResolver for FullResolver {
+    fn resolve(&self, _token: &str) -> Result<LayerContext, HexvaultError> {
+        LayerContext::new(Some("policy".try_into()?), Some("session".try_into()?))
+    }
+}
+
+#[test]
+fn test_stats_aggregate_metadata() {
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(FullResolver),
+    );
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+    let empty = partition.create_cell("empty".into()).unwrap();
+
+    assert_eq!(
+        vault.stats([&empty]),
+        VaultStats {
+            cells: 1,
+            ..VaultStats::default()
+        }
+    );
+
+    partition
+        .seal(&mut cell_a, "a", b"0123", Layer::AtRest, "")
+        .unwrap();
+    partition
+        .seal(&mut cell_a, "b", b"", Layer::SessionBound, "")
+        .unwrap();
+    partition
+        .seal(&mut cell_b, "c", b"xy", Layer::SessionBound, "")
+        .unwrap();
+
+    let stats = vault.stats([&cell_a, &cell_b, &empty]);
+    assert_eq!(stats.cells, 3);
+    assert_eq!(stats.payloads, 3);
+    assert_eq!(stats.count_at(Layer::AtRest), 1);
+    assert_eq!(stats.count_at(Layer::AccessGated), 0);
+    assert_eq!(stats.count_at(Layer::SessionBound), 2);
+    // 30 bytes of header, nonce, and tag per layer.
+    assert_eq!(stats.ciphertext_bytes, (4 + 30) + 90 + (2 + 90));
+
+    let created: Vec<_> = [&cell_a, &cell_b]
+        .iter()
+        .flat_map(|cell| cell.infos().map(|(_, info)| info.created_at))
+        .collect();
+    assert_eq!(stats.oldest, created.iter().min().copied());
+    assert_eq!(stats.newest, created.iter().max().copied());
+}
+
+#[test]
+fn test_entries_walk_every_payload() {
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(FullResolver),
+    );
+    let partition = vault.get_partition("test").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+    let empty = partition.create_cell("empty".into()).unwrap();
+    for key in ["x", "y"] {
+        partition
+            .seal(&mut cell_a, key, b"data", Layer::AtRest, "")
+            .unwrap();
+    }
+    partition
+        .seal(&mut cell_b, "z", b"data", Layer::SessionBound, "")
+        .unwrap();
+
+    let mut entries: Vec<_> = vault.entries([&cell_a, &empty, &cell_b]).collect();
+    entries.sort_unstable();
+    assert_eq!(entries, [("cell-a", "x"), ("cell-a", "y"), ("cell-b", "z")]);
+    for (cell, key) in vault.entries([&cell_a, &cell_b]) {
+        let cell = if cell == "cell-a" { &cell_a } else { &cell_b };
+        partition.verify(cell, key, "").unwrap();
+    }
+}
+
+#[test]
+fn test_find_by_content_id() {
+    use hexvault::stack::SealOptions;
+
+    let vault = Vault::new(
+        generate_master_key().unwrap(),
+        std::sync::Arc::new(FullResolver),
+    );
+    let partition = vault.get_partition("test").unwrap();
+    let other = vault.get_partition("other").unwrap();
+    let mut cell_a = partition.create_cell("cell-a".into()).unwrap();
+    let mut cell_b = partition.create_cell("cell-b".into()).unwrap();
+    let options = SealOptions {
+        content_id: true,
+        ..SealOptions::default()
+    };
+    for (key, text) in [("x", &b"invoice"[..]), ("y", b"receipt")] {
+        partition
+            .seal_with_options(&mut cell_a, key, text, Layer::AtRest, "", &options)
+            .unwrap();
+    }
+    partition
+        .seal_with_options(&mut cell_b, "z", b"invoice", Layer::AtRest, "", &options)
+        .unwrap();
+    partition
+        .seal(&mut cell_b, "untracked", b"invoice", Layer::AtRest, "")
+        .unwrap();
+
+    let id = vault.content_id(&partition, b"invoice").unwrap();
+    let mut found: Vec<_> = vault.find_by_content_id([&cell_a, &cell_b], &id).collect();
+    found.sort_unstable();
+    assert_eq!(found, [("cell-a", "x"), ("cell-b", "z")]);
+    assert_ne!(vault.content_id(&other, b"invoice").unwrap(), id);
+
+    // The ID is checked on open, so a tampered one is caught.
+    let mut bundle = cell_a.to_bundle();
+    let x = bundle.payloads.iter_mut().find(|p| p.key == "x").unwrap();
+    x.content_id = Some(vault.content_id(&partition, b"receipt").unwrap());
+    let tampered = hexvault::cell::Cell::from_bundle(bundle).unwrap();
+    assert!(matches!(
+        partition.open(&tampered, "x", ""),
+        Err(HexvaultError::IntegrityCheckFailed)
+    ));
+    assert_eq!(partition.open(&cell_a, "x", "").unwrap(), b"invoice");
+}